@@ -0,0 +1,40 @@
+use workflow_rs::{
+    Workflow,
+    graph::GraphBuilder,
+    node::{
+        condition::{ConditionConfig, Operator},
+        config::{BranchConfig, BranchPayload},
+    },
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let graph = GraphBuilder::new()
+        .input("start", "yes")
+        .branch(
+            "route",
+            BranchConfig {
+                branches: vec![BranchPayload {
+                    id: "yes".to_string(),
+                    when: ConditionConfig::compare(Operator::Eq, "yes"),
+                }],
+                default: None,
+            },
+        )
+        .prompt("a", "Node A Data")
+        .prompt("b", "Node B Data")
+        .end("end")
+        .edge("start", "route")
+        .handle_edge("route", "yes", "a")
+        .handle_edge("route", "default", "b")
+        .edge("a", "end")
+        .edge("b", "end")
+        .build()?;
+
+    println!("{}", graph.to_mermaid());
+
+    let r = Workflow::start(graph).await?;
+    println!("Graph execution result: {:?}", r);
+
+    Ok(())
+}
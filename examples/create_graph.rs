@@ -4,4 +4,5 @@ fn main() {
     // 创建一个新的图
     let graph = Graph::new_with_default_nodes().unwrap();
     println!("Graph created: {:#?}", graph);
+    println!("{}", graph.to_mermaid());
 }
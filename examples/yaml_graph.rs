@@ -0,0 +1,18 @@
+use workflow_rs::{Workflow, graph::Graph};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // 从简写 YAML 加载图
+    let yaml = std::fs::read_to_string("data/branch.yaml")?;
+    let graph = Graph::from_yaml(&yaml)?;
+
+    // 转换为 TOML，再加载回来
+    let toml = graph.to_toml()?;
+    println!("== TOML ==\n{}", toml);
+    let graph = Graph::from_toml(&toml)?;
+
+    let r = Workflow::start(graph).await?;
+    println!("Graph execution result: {:?}", r);
+
+    Ok(())
+}
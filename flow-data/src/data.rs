@@ -144,6 +144,43 @@ impl FlowData {
     }
 }
 
+/// JSON views of FlowData.
+impl FlowData {
+    /// 由 JSON 值构建：字符串与数字使用对应的标量类型，其余保留为 JSON
+    pub fn from_json_value(value: Value) -> Self {
+        match value {
+            Value::String(text) => Self::from(text),
+            Value::Number(number) => match number.as_f64() {
+                Some(n) => Self::from(n),
+                None => Self::from(Value::Number(number)),
+            },
+            other => Self::from(other),
+        }
+    }
+
+    /// 转为 JSON 值：集合转为数组，元素规则见 `SingleData::to_json_value`
+    pub fn to_json_value(&self) -> Value {
+        match self {
+            Self::Single(item) => item.to_json_value(),
+            Self::Collection(items) => {
+                Value::Array(items.iter().map(SingleData::to_json_value).collect())
+            }
+        }
+    }
+}
+
+impl SingleData {
+    /// 转为 JSON 值：文本、数字、JSON 取其本身，文件为 `{path, file_type}` 对象
+    pub fn to_json_value(&self) -> Value {
+        match self {
+            Self::Text(text) => Value::String(text.clone()),
+            Self::Number(n) => serde_json::Number::from_f64(*n).map_or(Value::Null, Value::Number),
+            Self::File(file) => serde_json::to_value(file).unwrap_or_default(),
+            Self::Json(value) => value.clone(),
+        }
+    }
+}
+
 /// Accessors (by value) for FlowData.
 impl FlowData {
     pub fn into_text(self) -> Result<String> {
@@ -236,7 +273,7 @@ impl FlowData {
 /// Utility methods for FlowData.
 impl FlowData {
     pub fn merge(self, other: Self) -> Self {
-        let merged = match self {
+        match self {
             Self::Collection(mut vec) => {
                 match other {
                     Self::Single(data) => vec.push(data),
@@ -252,8 +289,7 @@ impl FlowData {
                 }
                 Self::Collection(vec)
             }
-        };
-        merged
+        }
     }
 
     pub fn merge_mut(&mut self, other: Self) -> &mut Self {
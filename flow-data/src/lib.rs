@@ -1,6 +1,8 @@
 pub mod data;
 pub mod output;
+pub mod stream;
 pub mod types;
 
 pub use data::*;
+pub use stream::StreamFormat;
 pub use types::*;
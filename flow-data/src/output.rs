@@ -1,7 +1,7 @@
 use toolcraft_request::ByteStream;
 use workflow_error::{Error, Result};
 
-use crate::{FlowData, FlowOutputType};
+use crate::{FlowData, FlowOutputType, StreamFormat};
 
 pub struct ControlFlow {
     pub next_node: String,
@@ -19,7 +19,7 @@ impl ControlFlow {
 
 pub enum FlowOutputValue {
     Data(FlowData),
-    Stream(ByteStream),
+    Stream(ByteStream, StreamFormat),
     Control(ControlFlow),
     Parallel(Vec<ControlFlow>),
 }
@@ -45,9 +45,13 @@ impl FlowOutput {
     }
 
     pub fn new_stream(stream: ByteStream) -> Self {
+        Self::new_stream_with_format(stream, StreamFormat::default())
+    }
+
+    pub fn new_stream_with_format(stream: ByteStream, format: StreamFormat) -> Self {
         Self {
             output_type: FlowOutputType::Stream,
-            value: FlowOutputValue::Stream(stream),
+            value: FlowOutputValue::Stream(stream, format),
         }
     }
 
@@ -67,7 +71,7 @@ impl FlowOutput {
 
     pub fn as_stream(&self) -> Result<&ByteStream> {
         match &self.value {
-            FlowOutputValue::Stream(stream) => Ok(stream),
+            FlowOutputValue::Stream(stream, _) => Ok(stream),
             _ => Err(Error::FlowTypeMismatch),
         }
     }
@@ -88,11 +92,19 @@ impl FlowOutput {
 
     pub fn into_stream(self) -> Result<ByteStream> {
         match self.value {
-            FlowOutputValue::Stream(stream) => Ok(stream),
+            FlowOutputValue::Stream(stream, _) => Ok(stream),
             _ => Err(Error::FlowTypeMismatch),
         }
     }
 
+    /// 流输出的编码格式，非流输出时为 None
+    pub fn stream_format(&self) -> Option<StreamFormat> {
+        match &self.value {
+            FlowOutputValue::Stream(_, format) => Some(*format),
+            _ => None,
+        }
+    }
+
     pub fn get_type(&self) -> &FlowOutputType {
         &self.output_type
     }
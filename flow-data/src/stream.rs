@@ -0,0 +1,62 @@
+use serde_json::Value;
+use workflow_error::{Error, Result};
+
+use crate::FlowData;
+
+/// 流输出的编码格式，决定汇总后的字节如何转换为 FlowData
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StreamFormat {
+    /// 按 UTF-8 原样作为文本
+    #[default]
+    Text,
+    /// OpenAI 兼容接口的 SSE：拼接各事件的 `choices[0].delta.content`
+    OpenAiSse,
+    /// Ollama 的 NDJSON：拼接各行的 `message.content`
+    OllamaNdjson,
+    /// 完整的 JSON 文本
+    Json,
+}
+
+impl StreamFormat {
+    /// 解码汇总后的流数据
+    pub fn decode(self, bytes: &[u8]) -> Result<FlowData> {
+        let text = String::from_utf8_lossy(bytes);
+        match self {
+            StreamFormat::Text => Ok(FlowData::from(text.into_owned())),
+            StreamFormat::OpenAiSse => {
+                let events = text
+                    .lines()
+                    .filter_map(|line| line.trim().strip_prefix("data:"))
+                    .map(str::trim)
+                    .filter(|data| !data.is_empty() && *data != "[DONE]");
+                collect_content(events, "/choices/0/delta/content")
+            }
+            StreamFormat::OllamaNdjson => {
+                let lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+                collect_content(lines, "/message/content")
+            }
+            StreamFormat::Json => Ok(FlowData::from(serde_json::from_str::<Value>(&text)?)),
+        }
+    }
+}
+
+/// 拼接各 JSON 片段中 `pointer` 处的文本，片段带有 `error` 时返回错误
+fn collect_content<'a>(chunks: impl Iterator<Item = &'a str>, pointer: &str) -> Result<FlowData> {
+    let mut content = String::new();
+    for chunk in chunks {
+        let json: Value = serde_json::from_str(chunk)?;
+        if let Some(error) = json.get("error") {
+            let message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .map_or_else(|| error.to_string(), str::to_string);
+            return Err(Error::StreamChunkError(
+                format!("stream error: {}", message).into(),
+            ));
+        }
+        if let Some(delta) = json.pointer(pointer).and_then(Value::as_str) {
+            content.push_str(delta);
+        }
+    }
+    Ok(FlowData::from(content))
+}
@@ -1,5 +1,8 @@
+use flow_data::FlowData;
 use serde::{Deserialize, Serialize};
 
+use crate::node::config::ConditionConfig;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum EdgeType {
     Data,    // 数据传输路径
@@ -28,4 +31,70 @@ pub struct Edge {
     /// 目标节点句柄（可选）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub target_handle: Option<String>,
+
+    /// 回边守卫：存在时该边为循环回边，不参与环检测
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loop_guard: Option<LoopGuard>,
+}
+
+/// 循环回边的守卫条件，至少设置其中一项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoopGuard {
+    /// 最多回跳次数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_iterations: Option<usize>,
+
+    /// 退出条件：源节点输出满足该条件时不再回跳
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<ConditionConfig>,
+}
+
+impl LoopGuard {
+    /// 已回跳 `iterations` 次后，根据源节点输出判断是否继续回跳
+    pub fn should_continue(&self, iterations: usize, output: &FlowData) -> bool {
+        if self.max_iterations.is_some_and(|max| iterations >= max) {
+            return false;
+        }
+        !self
+            .until
+            .as_ref()
+            .is_some_and(|until| until.matches_data(output))
+    }
+}
+
+impl Edge {
+    /// 是否为循环回边
+    pub fn is_loop(&self) -> bool {
+        self.loop_guard.is_some()
+    }
+
+    /// 生成边 ID：`source[.source_handle]->target[.target_handle]`
+    ///
+    /// 若与 `taken` 中已有的 ID 冲突，则追加 `#2`、`#3` ... 后缀
+    pub fn make_id<'a>(
+        source: &str,
+        target: &str,
+        source_handle: Option<&str>,
+        target_handle: Option<&str>,
+        taken: impl Iterator<Item = &'a str> + Clone,
+    ) -> String {
+        let endpoint = |id: &str, handle: Option<&str>| match handle {
+            Some(handle) => format!("{}.{}", id, handle),
+            None => id.to_string(),
+        };
+        let base = format!(
+            "{}->{}",
+            endpoint(source, source_handle),
+            endpoint(target, target_handle)
+        );
+
+        let mut id = base.clone();
+        let mut n = 1;
+        while taken.clone().any(|existing| existing == id) {
+            n += 1;
+            id = format!("{}#{}", base, n);
+        }
+        id
+    }
 }
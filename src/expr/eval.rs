@@ -0,0 +1,203 @@
+use std::cmp::Ordering;
+
+use serde_json::{Map, Value};
+use workflow_error::{Error, Result};
+
+use super::{
+    Variables, functions, is_truthy,
+    parser::{BinaryOp, Expr, UnaryOp, number},
+    to_text,
+};
+
+pub fn eval(expr: &Expr, vars: &Variables) -> Result<Value> {
+    match expr {
+        Expr::Literal(value) => Ok(value.clone()),
+        Expr::Var(name) => vars
+            .get(name)
+            .cloned()
+            .ok_or_else(|| runtime(format!("unknown variable `{}`", name))),
+        Expr::Array(items) => items
+            .iter()
+            .map(|item| eval(item, vars))
+            .collect::<Result<Vec<_>>>()
+            .map(Value::Array),
+        Expr::Object(fields) => {
+            let mut map = Map::new();
+            for (key, value) in fields {
+                map.insert(key.clone(), eval(value, vars)?);
+            }
+            Ok(Value::Object(map))
+        }
+        // 成员与下标访问不存在时得到 null，配合 `??` 提供默认值
+        Expr::Member(target, name) => Ok(match eval(target, vars)? {
+            Value::Object(mut map) => map.remove(name).unwrap_or(Value::Null),
+            _ => Value::Null,
+        }),
+        Expr::Index(target, index) => {
+            let target = eval(target, vars)?;
+            let index = eval(index, vars)?;
+            Ok(index_value(target, &index))
+        }
+        Expr::Unary(op, operand) => {
+            let value = eval(operand, vars)?;
+            match op {
+                UnaryOp::Not => Ok(Value::Bool(!is_truthy(&value))),
+                UnaryOp::Neg => match value.as_f64() {
+                    Some(n) => Ok(number(-n)),
+                    None => Err(runtime(format!("cannot negate {}", type_name(&value)))),
+                },
+            }
+        }
+        Expr::Binary(op, left, right) => binary(*op, left, right, vars),
+        Expr::Conditional(condition, then, otherwise) => {
+            if is_truthy(&eval(condition, vars)?) {
+                eval(then, vars)
+            } else {
+                eval(otherwise, vars)
+            }
+        }
+        Expr::Call(name, args, _) => {
+            let args = args
+                .iter()
+                .map(|arg| eval(arg, vars))
+                .collect::<Result<Vec<_>>>()?;
+            functions::call(name, args)
+        }
+    }
+}
+
+fn binary(op: BinaryOp, left: &Expr, right: &Expr, vars: &Variables) -> Result<Value> {
+    let left = eval(left, vars)?;
+    // 短路求值
+    match op {
+        BinaryOp::And if !is_truthy(&left) => return Ok(Value::Bool(false)),
+        BinaryOp::Or if is_truthy(&left) => return Ok(Value::Bool(true)),
+        BinaryOp::Coalesce if !left.is_null() => return Ok(left),
+        _ => {}
+    }
+    let right = eval(right, vars)?;
+
+    match op {
+        BinaryOp::And | BinaryOp::Or => Ok(Value::Bool(is_truthy(&right))),
+        BinaryOp::Coalesce => Ok(right),
+        BinaryOp::Eq => Ok(Value::Bool(equals(&left, &right))),
+        BinaryOp::Ne => Ok(Value::Bool(!equals(&left, &right))),
+        BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+            let ordering = compare(&left, &right).ok_or_else(|| {
+                runtime(format!(
+                    "cannot compare {} with {}",
+                    type_name(&left),
+                    type_name(&right)
+                ))
+            })?;
+            Ok(Value::Bool(match op {
+                BinaryOp::Lt => ordering == Ordering::Less,
+                BinaryOp::Le => ordering != Ordering::Greater,
+                BinaryOp::Gt => ordering == Ordering::Greater,
+                _ => ordering != Ordering::Less,
+            }))
+        }
+        BinaryOp::Add => match (left, right) {
+            (Value::Array(mut a), Value::Array(b)) => {
+                a.extend(b);
+                Ok(Value::Array(a))
+            }
+            (left @ Value::String(_), right) | (left, right @ Value::String(_)) => {
+                Ok(Value::String(to_text(&left) + &to_text(&right)))
+            }
+            (left, right) => arithmetic(op, &left, &right),
+        },
+        _ => arithmetic(op, &left, &right),
+    }
+}
+
+fn arithmetic(op: BinaryOp, left: &Value, right: &Value) -> Result<Value> {
+    let (Some(a), Some(b)) = (left.as_f64(), right.as_f64()) else {
+        return Err(runtime(format!(
+            "cannot apply `{}` to {} and {}",
+            symbol(op),
+            type_name(left),
+            type_name(right)
+        )));
+    };
+    let result = match op {
+        BinaryOp::Add => a + b,
+        BinaryOp::Sub => a - b,
+        BinaryOp::Mul => a * b,
+        BinaryOp::Div | BinaryOp::Rem if b == 0.0 => {
+            return Err(runtime("division by zero".to_string()));
+        }
+        BinaryOp::Div => a / b,
+        _ => a % b,
+    };
+    Ok(number(result))
+}
+
+fn index_value(target: Value, index: &Value) -> Value {
+    match (target, index) {
+        (Value::Array(mut items), Value::Number(n)) => {
+            let Some(i) = n.as_f64().filter(|i| i.fract() == 0.0) else {
+                return Value::Null;
+            };
+            // 负下标从末尾计数
+            let i = if i < 0.0 { items.len() as f64 + i } else { i };
+            if i < 0.0 || i as usize >= items.len() {
+                return Value::Null;
+            }
+            items.swap_remove(i as usize)
+        }
+        (Value::Object(mut map), Value::String(key)) => map.remove(key).unwrap_or(Value::Null),
+        _ => Value::Null,
+    }
+}
+
+/// 数字按数值相等（`1 == 1.0`），其余按 JSON 相等
+pub fn equals(left: &Value, right: &Value) -> bool {
+    match (left.as_f64(), right.as_f64()) {
+        (Some(a), Some(b)) if left.is_number() && right.is_number() => a == b,
+        _ => left == right,
+    }
+}
+
+/// 数字与数字、文本与文本之间可比较
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+fn symbol(op: BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::Add => "+",
+        BinaryOp::Sub => "-",
+        BinaryOp::Mul => "*",
+        BinaryOp::Div => "/",
+        BinaryOp::Rem => "%",
+        BinaryOp::Eq => "==",
+        BinaryOp::Ne => "!=",
+        BinaryOp::Lt => "<",
+        BinaryOp::Le => "<=",
+        BinaryOp::Gt => ">",
+        BinaryOp::Ge => ">=",
+        BinaryOp::And => "&&",
+        BinaryOp::Or => "||",
+        BinaryOp::Coalesce => "??",
+    }
+}
+
+pub fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+pub fn runtime(message: String) -> Error {
+    Error::ExpressionError(message.into())
+}
@@ -0,0 +1,173 @@
+use regex::Regex;
+use serde_json::Value;
+use workflow_error::Result;
+
+use super::{
+    eval::{equals, runtime, type_name},
+    json_path,
+    parser::number,
+    to_number, to_text,
+};
+
+/// 白名单函数及其参数个数范围 `(最少, 最多)`，`None` 表示不限
+const FUNCTIONS: [(&str, usize, Option<usize>); 24] = [
+    ("len", 1, Some(1)),
+    ("upper", 1, Some(1)),
+    ("lower", 1, Some(1)),
+    ("trim", 1, Some(1)),
+    ("contains", 2, Some(2)),
+    ("starts_with", 2, Some(2)),
+    ("ends_with", 2, Some(2)),
+    ("replace", 3, Some(3)),
+    ("split", 2, Some(2)),
+    ("join", 1, Some(2)),
+    ("str", 1, Some(1)),
+    ("num", 1, Some(1)),
+    ("round", 1, Some(2)),
+    ("floor", 1, Some(1)),
+    ("ceil", 1, Some(1)),
+    ("abs", 1, Some(1)),
+    ("min", 1, None),
+    ("max", 1, None),
+    ("keys", 1, Some(1)),
+    ("values", 1, Some(1)),
+    ("json", 1, Some(1)),
+    ("to_json", 1, Some(1)),
+    ("path", 2, Some(2)),
+    ("matches", 2, Some(2)),
+];
+
+/// 构建期检查函数名与参数个数
+pub fn check(name: &str, count: usize) -> std::result::Result<(), String> {
+    let Some(&(_, min, max)) = FUNCTIONS.iter().find(|(n, ..)| *n == name) else {
+        return Err(format!("unknown function `{}`", name));
+    };
+    if count >= min && max.is_none_or(|max| count <= max) {
+        return Ok(());
+    }
+    let expected = match max {
+        Some(max) if max == min => plural(min),
+        Some(max) => format!("{} to {} arguments", min, max),
+        None => format!("at least {}", plural(min)),
+    };
+    Err(format!("`{}` expects {}, got {}", name, expected, count))
+}
+
+fn plural(count: usize) -> String {
+    match count {
+        1 => "1 argument".to_string(),
+        n => format!("{} arguments", n),
+    }
+}
+
+/// 调用白名单函数，参数个数已在构建期检查
+pub fn call(name: &str, args: Vec<Value>) -> Result<Value> {
+    let arg = |i: usize| &args[i];
+    let text = |i: usize| to_text(&args[i]);
+
+    let value = match name {
+        "len" => match arg(0) {
+            Value::String(s) => Value::from(s.chars().count()),
+            Value::Array(items) => Value::from(items.len()),
+            Value::Object(map) => Value::from(map.len()),
+            other => return Err(expects(name, "a string, array or object", other)),
+        },
+        "upper" => Value::String(text(0).to_uppercase()),
+        "lower" => Value::String(text(0).to_lowercase()),
+        "trim" => Value::String(text(0).trim().to_string()),
+        "contains" => Value::Bool(match arg(0) {
+            Value::Array(items) => items.iter().any(|item| equals(item, arg(1))),
+            Value::Object(map) => map.contains_key(&text(1)),
+            _ => text(0).contains(&text(1)),
+        }),
+        "starts_with" => Value::Bool(text(0).starts_with(&text(1))),
+        "ends_with" => Value::Bool(text(0).ends_with(&text(1))),
+        "replace" => Value::String(text(0).replace(&text(1), &text(2))),
+        "split" => text(0)
+            .split(&text(1))
+            .map(|part| Value::String(part.to_string()))
+            .collect(),
+        "join" => {
+            let Value::Array(items) = arg(0) else {
+                return Err(expects(name, "an array", arg(0)));
+            };
+            let separator = args.get(1).map(to_text).unwrap_or_default();
+            let parts: Vec<String> = items.iter().map(to_text).collect();
+            Value::String(parts.join(&separator))
+        }
+        "str" => Value::String(text(0)),
+        "num" => match to_number(arg(0)) {
+            Some(n) => number(n),
+            None => return Err(runtime(format!("cannot convert {} to a number", arg(0)))),
+        },
+        "round" => {
+            let n = numeric(name, arg(0))?;
+            let digits = match args.get(1) {
+                Some(digits) => numeric(name, digits)?.clamp(0.0, 15.0) as i32,
+                None => 0,
+            };
+            let scale = 10f64.powi(digits);
+            number((n * scale).round() / scale)
+        }
+        "floor" => number(numeric(name, arg(0))?.floor()),
+        "ceil" => number(numeric(name, arg(0))?.ceil()),
+        "abs" => number(numeric(name, arg(0))?.abs()),
+        "min" | "max" => {
+            // 单个数组参数时取数组元素
+            let items = match args.as_slice() {
+                [Value::Array(items)] => items.as_slice(),
+                items => items,
+            };
+            let mut result: Option<f64> = None;
+            for item in items {
+                let n = numeric(name, item)?;
+                result = Some(match result {
+                    Some(r) if name == "min" => r.min(n),
+                    Some(r) => r.max(n),
+                    None => n,
+                });
+            }
+            result.map_or(Value::Null, number)
+        }
+        "keys" | "values" => {
+            let Value::Object(map) = arg(0) else {
+                return Err(expects(name, "an object", arg(0)));
+            };
+            if name == "keys" {
+                map.keys().map(|key| Value::String(key.clone())).collect()
+            } else {
+                map.values().cloned().collect()
+            }
+        }
+        "json" => serde_json::from_str(&text(0))
+            .map_err(|e| runtime(format!("`json` failed to parse input: {}", e)))?,
+        "to_json" => Value::String(arg(0).to_string()),
+        "path" => {
+            let segments =
+                json_path::parse(&text(1)).map_err(|e| runtime(format!("`path` got {}", e)))?;
+            json_path::query(arg(0), &segments).unwrap_or(Value::Null)
+        }
+        "matches" => {
+            let regex = Regex::new(&text(1))
+                .map_err(|e| runtime(format!("`matches` got an invalid regex: {}", e)))?;
+            Value::Bool(regex.is_match(&text(0)))
+        }
+        _ => unreachable!("unknown functions are rejected when parsing"),
+    };
+    Ok(value)
+}
+
+fn numeric(name: &str, value: &Value) -> Result<f64> {
+    value
+        .as_f64()
+        .ok_or_else(|| expects(name, "a number", value))
+}
+
+fn expects(name: &str, expected: &str, got: &Value) -> workflow_error::Error {
+    runtime(format!(
+        "`{}` expects {}, got {}",
+        name,
+        expected,
+        type_name(got)
+    ))
+}
@@ -0,0 +1,130 @@
+use serde_json::Value;
+use workflow_error::{Error, Result};
+
+/// JSON 路径中的一段
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    /// 对象键；作用于数组时按下标解析
+    Key(String),
+    /// 数组下标
+    Index(usize),
+    /// `[*]` 或 `.*`：数组的全部元素或对象的全部值
+    Wildcard,
+}
+
+/// 解析 JSON 路径，支持两种写法：
+///
+/// - 点号路径：`$.user.name`、`items[0].id`、`$['a.b']`、`items[*].id`，`$` 可省略
+/// - JSON Pointer：`/user/name`、`/items/0`
+///
+/// 空路径与 `$` 表示整个值
+pub fn parse(path: &str) -> Result<Vec<Segment>> {
+    let invalid = |reason: &str| {
+        Error::ExecutionError(format!("Invalid JSON path `{}`: {}", path, reason).into())
+    };
+
+    if let Some(pointer) = path.strip_prefix('/') {
+        return Ok(pointer
+            .split('/')
+            .map(|key| Segment::Key(key.replace("~1", "/").replace("~0", "~")))
+            .collect());
+    }
+
+    let rest = path.trim();
+    let rest = rest.strip_prefix('$').unwrap_or(rest);
+    let mut chars = rest.chars().peekable();
+    let mut segments = Vec::new();
+    let mut expect_key = !rest.starts_with(['.', '[']) && !rest.is_empty();
+
+    loop {
+        if expect_key {
+            let mut key = String::new();
+            while let Some(&c) = chars.peek() {
+                if c == '.' || c == '[' {
+                    break;
+                }
+                key.push(c);
+                chars.next();
+            }
+            if key.is_empty() {
+                return Err(invalid("empty key"));
+            }
+            segments.push(match key.as_str() {
+                "*" => Segment::Wildcard,
+                _ => Segment::Key(key),
+            });
+            expect_key = false;
+            continue;
+        }
+
+        match chars.next() {
+            None => break,
+            Some('.') => expect_key = true,
+            Some('[') => {
+                let mut inner = String::new();
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    inner.push(c);
+                }
+                let inner = inner.trim();
+                let quoted = inner
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+                let segment = match quoted {
+                    Some(key) => Segment::Key(key.to_string()),
+                    None if inner == "*" => Segment::Wildcard,
+                    None => Segment::Index(
+                        inner
+                            .parse()
+                            .map_err(|_| invalid("brackets must hold an index or a quoted key"))?,
+                    ),
+                };
+                segments.push(segment);
+            }
+            Some(_) => return Err(invalid("expected `.` or `[`")),
+        }
+    }
+
+    Ok(segments)
+}
+
+/// 按已解析的路径取值，路径不存在或含通配符时返回 None
+pub fn select_segments<'a>(value: &'a Value, segments: &[Segment]) -> Option<&'a Value> {
+    segments
+        .iter()
+        .try_fold(value, |current, segment| match (current, segment) {
+            (Value::Object(map), Segment::Key(key)) => map.get(key),
+            (Value::Array(items), Segment::Index(index)) => items.get(*index),
+            (Value::Array(items), Segment::Key(key)) => {
+                key.parse::<usize>().ok().and_then(|index| items.get(index))
+            }
+            _ => None,
+        })
+}
+
+/// 按已解析的路径查询，通配符处展开为数组，其后路径不存在的元素被跳过
+pub fn query(value: &Value, segments: &[Segment]) -> Option<Value> {
+    let Some(position) = segments.iter().position(|s| *s == Segment::Wildcard) else {
+        return select_segments(value, segments).cloned();
+    };
+    let items: Vec<&Value> = match select_segments(value, &segments[..position])? {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(map) => map.values().collect(),
+        _ => return None,
+    };
+    let rest = &segments[position + 1..];
+    Some(Value::Array(
+        items
+            .into_iter()
+            .filter_map(|item| query(item, rest))
+            .collect(),
+    ))
+}
+
+/// 按路径查询，路径无效或不存在时返回 None
+pub fn select(value: &Value, path: &str) -> Option<Value> {
+    query(value, &parse(path).ok()?)
+}
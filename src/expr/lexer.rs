@@ -0,0 +1,118 @@
+use workflow_error::Result;
+
+use super::error_at;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Number(f64),
+    Str(String),
+    Ident(String),
+    /// 运算符与标点
+    Punct(&'static str),
+    End,
+}
+
+/// 带起始偏移（字符下标）的 token
+#[derive(Debug, Clone)]
+pub struct Spanned {
+    pub token: Token,
+    pub offset: usize,
+}
+
+/// 多字符运算符需排在其前缀之前
+const PUNCTS: [&str; 25] = [
+    "??", "==", "!=", "<=", ">=", "&&", "||", "(", ")", "[", "]", "{", "}", ",", ":", ".", "?",
+    "+", "-", "*", "/", "%", "!", "<", ">",
+];
+
+pub fn tokenize(source: &str) -> Result<Vec<Spanned>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        let token = if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                // `1.foo` 中的 `.` 属于成员访问
+                if chars[i] == '.' && !chars.get(i + 1).is_some_and(char::is_ascii_digit) {
+                    break;
+                }
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let number = text
+                .parse()
+                .map_err(|_| error_at(source, start, &format!("invalid number `{}`", text)))?;
+            Token::Number(number)
+        } else if c == '"' || c == '\'' {
+            i += 1;
+            let mut text = String::new();
+            loop {
+                match chars.get(i) {
+                    None => return Err(error_at(source, start, "unterminated string")),
+                    Some(&q) if q == c => break,
+                    Some('\\') => {
+                        let escaped = match chars.get(i + 1) {
+                            Some('n') => '\n',
+                            Some('t') => '\t',
+                            Some('r') => '\r',
+                            Some(&other) => other,
+                            None => return Err(error_at(source, start, "unterminated string")),
+                        };
+                        text.push(escaped);
+                        i += 2;
+                        continue;
+                    }
+                    Some(&other) => text.push(other),
+                }
+                i += 1;
+            }
+            i += 1;
+            Token::Str(text)
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+            {
+                i += 1;
+            }
+            Token::Ident(chars[start..i].iter().collect())
+        } else {
+            let punct = PUNCTS.iter().find(|p| {
+                p.chars()
+                    .enumerate()
+                    .all(|(k, pc)| chars.get(i + k) == Some(&pc))
+            });
+            match punct {
+                Some(p) => {
+                    i += p.chars().count();
+                    Token::Punct(p)
+                }
+                None => {
+                    return Err(error_at(
+                        source,
+                        start,
+                        &format!("unexpected character `{}`", c),
+                    ));
+                }
+            }
+        };
+
+        tokens.push(Spanned {
+            token,
+            offset: start,
+        });
+    }
+
+    tokens.push(Spanned {
+        token: Token::End,
+        offset: chars.len(),
+    });
+    Ok(tokens)
+}
@@ -0,0 +1,171 @@
+//! 沙箱表达式语言：只对传入的变量求值，不访问文件、网络或环境
+//!
+//! ```text
+//! input.score >= 60 && not contains(input.tags, "banned")
+//! upper(input.name ?? "anonymous") + " (" + len(input.items) + ")"
+//! input.total > 100 ? round(input.total * 0.9, 2) : input.total
+//! path(input, "$.items[0].id")
+//! ```
+//!
+//! - 字面量：数字、`'text'` / `"text"`、`true` / `false` / `null`、`[a, b]`、`{key: value}`
+//! - 运算符：`+ - * / %`（`+` 遇到文本时拼接）、`== != < <= > >=`、
+//!   `&&` / `and`、`||` / `or`、`!` / `not`、`??`、`?:`
+//! - 访问：`a.b`、`a[0]`、`a[-1]`、`a["key"]`，不存在时得到 null
+//! - 真值：null、false、0、空文本、空数组与空对象为假
+//! - 函数：见 `functions` 中的白名单
+
+mod eval;
+mod functions;
+pub mod json_path;
+mod lexer;
+mod parser;
+mod template;
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+use workflow_error::{Error, Result};
+
+pub use template::Template;
+
+use parser::Expr;
+
+/// 表达式变量
+pub type Variables = HashMap<String, Value>;
+
+/// 已解析的表达式，构建期解析一次，执行期可重复求值
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    source: String,
+    ast: Expr,
+}
+
+impl Expression {
+    /// 解析表达式，同时检查函数名与参数个数
+    pub fn parse(source: &str) -> Result<Self> {
+        let ast = parser::parse(source)?;
+        check_calls(source, &ast)?;
+        Ok(Self {
+            source: source.to_string(),
+            ast,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// 在给定变量下求值
+    pub fn eval(&self, vars: &Variables) -> Result<Value> {
+        eval::eval(&self.ast, vars).map_err(|e| match e {
+            Error::ExpressionError(message) => {
+                Error::ExpressionError(format!("{} (in `{}`)", message, self.source).into())
+            }
+            other => other,
+        })
+    }
+
+    /// 以 `input` 绑定节点输入求值
+    pub fn eval_input(&self, input: &Value) -> Result<Value> {
+        self.eval(&input_scope(input, &Variables::new()))
+    }
+
+    /// 求值并按真值规则转为布尔
+    pub fn eval_bool(&self, vars: &Variables) -> Result<bool> {
+        self.eval(vars).map(|value| is_truthy(&value))
+    }
+}
+
+/// 求值作用域：输入为 JSON 对象时其顶层键可直接引用，`vars` 覆盖同名键，`input` 绑定整个输入
+pub fn input_scope(input: &Value, vars: &Variables) -> Variables {
+    let mut scope = Variables::new();
+    if let Value::Object(map) = input {
+        scope.extend(map.iter().map(|(key, value)| (key.clone(), value.clone())));
+    }
+    scope.extend(vars.iter().map(|(key, value)| (key.clone(), value.clone())));
+    scope.insert("input".to_string(), input.clone());
+    scope
+}
+
+/// 真值规则：null、false、0、空文本、空数组与空对象为假
+pub fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(text) => !text.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+    }
+}
+
+/// 文本视图：字符串取其本身，其余取紧凑 JSON
+pub(crate) fn to_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        // f64 的 Display 会省略整数的小数部分：3.0 → "3"
+        Value::Number(n) => n.as_f64().map_or_else(|| n.to_string(), |f| f.to_string()),
+        other => other.to_string(),
+    }
+}
+
+/// 数值视图：数字或可解析为数字的字符串
+pub(crate) fn to_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn check_calls(source: &str, expr: &Expr) -> Result<()> {
+    let check = |e: &Expr| check_calls(source, e);
+    match expr {
+        Expr::Literal(_) | Expr::Var(_) => Ok(()),
+        Expr::Array(items) => items.iter().try_for_each(check),
+        Expr::Object(fields) => fields.iter().try_for_each(|(_, value)| check(value)),
+        Expr::Member(target, _) | Expr::Unary(_, target) => check(target),
+        Expr::Index(a, b) | Expr::Binary(_, a, b) => check(a).and(check(b)),
+        Expr::Conditional(a, b, c) => check(a).and(check(b)).and(check(c)),
+        Expr::Call(name, args, offset) => {
+            functions::check(name, args.len()).map_err(|m| error_at(source, *offset, &m))?;
+            args.iter().try_for_each(check)
+        }
+    }
+}
+
+/// 带位置的解析错误：
+///
+/// ```text
+/// expected `)`, found end of expression at line 1, column 9
+///   upper(a
+///          ^
+/// ```
+fn error_at(source: &str, offset: usize, message: &str) -> Error {
+    let mut line_start = 0;
+    let mut line_number = 1;
+    for (i, c) in source.chars().enumerate().take(offset) {
+        if c == '\n' {
+            line_start = i + 1;
+            line_number += 1;
+        }
+    }
+    let line: String = source
+        .chars()
+        .skip(line_start)
+        .take_while(|&c| c != '\n')
+        .collect();
+    let column = offset - line_start;
+
+    Error::ExpressionError(
+        format!(
+            "{} at line {}, column {}\n  {}\n  {}^",
+            message,
+            line_number,
+            column + 1,
+            line,
+            " ".repeat(column)
+        )
+        .into(),
+    )
+}
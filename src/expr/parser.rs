@@ -0,0 +1,359 @@
+use serde_json::Value;
+use workflow_error::Result;
+
+use super::{
+    error_at,
+    lexer::{Spanned, Token, tokenize},
+};
+
+/// 语法树深度上限（括号、一元运算、运算符链与访问链都计入），防止恶意表达式耗尽栈
+const MAX_DEPTH: usize = 128;
+
+/// 表达式源码长度上限（字节）
+const MAX_LENGTH: usize = 4096;
+
+/// 表达式语法树
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Literal(Value),
+    Var(String),
+    Array(Vec<Expr>),
+    Object(Vec<(String, Expr)>),
+    /// `a.b`
+    Member(Box<Expr>, String),
+    /// `a[expr]`
+    Index(Box<Expr>, Box<Expr>),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    /// `cond ? then : else`
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
+    /// 白名单函数调用，附带函数名在源码中的偏移
+    Call(String, Vec<Expr>, usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Not,
+    Neg,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+    Coalesce,
+}
+
+impl BinaryOp {
+    /// 左结合优先级，数值越大越先结合
+    fn precedence(&self) -> u8 {
+        match self {
+            BinaryOp::Coalesce => 1,
+            BinaryOp::Or => 2,
+            BinaryOp::And => 3,
+            BinaryOp::Eq | BinaryOp::Ne => 4,
+            BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => 5,
+            BinaryOp::Add | BinaryOp::Sub => 6,
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => 7,
+        }
+    }
+}
+
+pub fn parse(source: &str) -> Result<Expr> {
+    if source.len() > MAX_LENGTH {
+        return Err(workflow_error::Error::ExpressionError(
+            format!(
+                "expression is too long ({} bytes, at most {})",
+                source.len(),
+                MAX_LENGTH
+            )
+            .into(),
+        ));
+    }
+    let mut parser = Parser {
+        source,
+        tokens: tokenize(source)?,
+        pos: 0,
+        depth: 0,
+    };
+    let expr = parser.expression()?;
+    match parser.peek() {
+        Token::End => Ok(expr),
+        token => Err(parser.error(&format!("unexpected {}", describe(token)))),
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Spanned>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].token
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens[self.pos].offset
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].token.clone();
+        if token != Token::End {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        if matches!(self.peek(), Token::Punct(p) if *p == punct) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<()> {
+        if self.eat(punct) {
+            return Ok(());
+        }
+        Err(self.error(&format!(
+            "expected `{}`, found {}",
+            punct,
+            describe(self.peek())
+        )))
+    }
+
+    fn error(&self, message: &str) -> workflow_error::Error {
+        error_at(self.source, self.offset(), message)
+    }
+
+    /// 进入语法树的下一层
+    fn enter(&mut self) -> Result<()> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(self.error("expression is nested too deeply"));
+        }
+        Ok(())
+    }
+
+    fn expression(&mut self) -> Result<Expr> {
+        self.enter()?;
+        let result = self.conditional();
+        self.depth -= 1;
+        result
+    }
+
+    fn conditional(&mut self) -> Result<Expr> {
+        let condition = self.binary(0)?;
+        if !self.eat("?") {
+            return Ok(condition);
+        }
+        let then = self.expression()?;
+        self.expect(":")?;
+        let otherwise = self.expression()?;
+        Ok(Expr::Conditional(
+            Box::new(condition),
+            Box::new(then),
+            Box::new(otherwise),
+        ))
+    }
+
+    fn binary_op(&self) -> Option<BinaryOp> {
+        let op = match self.peek() {
+            Token::Punct(p) => match *p {
+                "+" => BinaryOp::Add,
+                "-" => BinaryOp::Sub,
+                "*" => BinaryOp::Mul,
+                "/" => BinaryOp::Div,
+                "%" => BinaryOp::Rem,
+                "==" => BinaryOp::Eq,
+                "!=" => BinaryOp::Ne,
+                "<" => BinaryOp::Lt,
+                "<=" => BinaryOp::Le,
+                ">" => BinaryOp::Gt,
+                ">=" => BinaryOp::Ge,
+                "&&" => BinaryOp::And,
+                "||" => BinaryOp::Or,
+                "??" => BinaryOp::Coalesce,
+                _ => return None,
+            },
+            Token::Ident(word) => match word.as_str() {
+                "and" => BinaryOp::And,
+                "or" => BinaryOp::Or,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    /// 优先级爬升，左结合的运算符链每多一个运算符，语法树加深一层
+    fn binary(&mut self, min_precedence: u8) -> Result<Expr> {
+        let depth = self.depth;
+        let mut left = self.unary()?;
+        while let Some(op) = self.binary_op() {
+            let precedence = op.precedence();
+            if precedence <= min_precedence {
+                break;
+            }
+            self.advance();
+            self.enter()?;
+            let right = self.binary(precedence)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        self.depth = depth;
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        let op = match self.peek() {
+            Token::Punct("!") => UnaryOp::Not,
+            Token::Punct("-") => UnaryOp::Neg,
+            Token::Ident(word) if word == "not" => UnaryOp::Not,
+            _ => return self.postfix(),
+        };
+        self.advance();
+        self.enter()?;
+        let operand = self.unary();
+        self.depth -= 1;
+        Ok(Expr::Unary(op, Box::new(operand?)))
+    }
+
+    /// 访问链 `a.b[0]...` 同样逐层计入深度
+    fn postfix(&mut self) -> Result<Expr> {
+        let depth = self.depth;
+        let mut expr = self.primary()?;
+        loop {
+            if matches!(self.peek(), Token::Punct("." | "[")) {
+                self.enter()?;
+            }
+            if self.eat(".") {
+                let offset = self.offset();
+                match self.advance() {
+                    Token::Ident(name) => expr = Expr::Member(Box::new(expr), name),
+                    Token::Number(n) if n.fract() == 0.0 => {
+                        let index = Expr::Literal(Value::from(n as u64));
+                        expr = Expr::Index(Box::new(expr), Box::new(index));
+                    }
+                    token => {
+                        return Err(error_at(
+                            self.source,
+                            offset,
+                            &format!(
+                                "expected a field name after `.`, found {}",
+                                describe(&token)
+                            ),
+                        ));
+                    }
+                }
+            } else if self.eat("[") {
+                let index = self.expression()?;
+                self.expect("]")?;
+                expr = Expr::Index(Box::new(expr), Box::new(index));
+            } else {
+                self.depth = depth;
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        let offset = self.offset();
+        match self.advance() {
+            Token::Number(n) => Ok(Expr::Literal(number(n))),
+            Token::Str(text) => Ok(Expr::Literal(Value::String(text))),
+            Token::Ident(name) => match name.as_str() {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                "null" => Ok(Expr::Literal(Value::Null)),
+                _ if self.eat("(") => {
+                    let args = self.list(")")?;
+                    Ok(Expr::Call(name, args, offset))
+                }
+                _ => Ok(Expr::Var(name)),
+            },
+            Token::Punct("(") => {
+                let expr = self.expression()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            Token::Punct("[") => Ok(Expr::Array(self.list("]")?)),
+            Token::Punct("{") => self.object(),
+            token => Err(error_at(
+                self.source,
+                offset,
+                &format!("expected a value, found {}", describe(&token)),
+            )),
+        }
+    }
+
+    /// 逗号分隔的表达式列表，允许末尾逗号
+    fn list(&mut self, close: &str) -> Result<Vec<Expr>> {
+        let mut items = Vec::new();
+        while !self.eat(close) {
+            items.push(self.expression()?);
+            if !self.eat(",") {
+                self.expect(close)?;
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    fn object(&mut self) -> Result<Expr> {
+        let mut fields = Vec::new();
+        while !self.eat("}") {
+            let offset = self.offset();
+            let key = match self.advance() {
+                Token::Ident(key) | Token::Str(key) => key,
+                token => {
+                    return Err(error_at(
+                        self.source,
+                        offset,
+                        &format!("expected an object key, found {}", describe(&token)),
+                    ));
+                }
+            };
+            self.expect(":")?;
+            fields.push((key, self.expression()?));
+            if !self.eat(",") {
+                self.expect("}")?;
+                break;
+            }
+        }
+        Ok(Expr::Object(fields))
+    }
+}
+
+/// 整数字面量保持为 JSON 整数
+pub fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < 9_007_199_254_740_992.0 {
+        Value::from(n as i64)
+    } else {
+        serde_json::Number::from_f64(n).map_or(Value::Null, Value::Number)
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Number(n) => format!("number `{}`", n),
+        Token::Str(text) => format!("string {:?}", text),
+        Token::Ident(name) => format!("`{}`", name),
+        Token::Punct(p) => format!("`{}`", p),
+        Token::End => "end of expression".to_string(),
+    }
+}
@@ -0,0 +1,80 @@
+use serde_json::Value;
+use workflow_error::{Error, Result};
+
+use super::{Expression, Variables, to_text};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Text(String),
+    Expr(Expression),
+}
+
+/// 文本模板：`{{ expr }}` 处替换为表达式的值，null 渲染为空文本
+///
+/// ```text
+/// Hello {{ upper(input.name) }}, you have {{ len(input.items) }} items
+/// ```
+///
+/// `\{{` 输出字面量 `{{`；`{{ }}` 中的内容不是合法表达式时按原文保留，
+/// 兼容含字面量双花括号的旧模板，如 `Return {{"answer": 1}} as JSON`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut rest = source;
+
+        while let Some(start) = rest.find("{{") {
+            if let Some(before) = rest[..start].strip_suffix('\\') {
+                text.push_str(before);
+                text.push_str("{{");
+                rest = &rest[start + 2..];
+                continue;
+            }
+            text.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let end = after.find("}}").ok_or_else(|| {
+                Error::ExpressionError(format!("Unclosed `{{{{` in template `{}`", source).into())
+            })?;
+            match Expression::parse(after[..end].trim()) {
+                Ok(expr) => {
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Part::Expr(expr));
+                }
+                Err(_) => text.push_str(&rest[start..start + end + 4]),
+            }
+            rest = &after[end + 2..];
+        }
+        text.push_str(rest);
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+
+        Ok(Self { parts })
+    }
+
+    /// 是否包含 `{{ }}` 占位
+    pub fn has_expressions(&self) -> bool {
+        self.parts.iter().any(|part| matches!(part, Part::Expr(_)))
+    }
+
+    pub fn render(&self, vars: &Variables) -> Result<String> {
+        let mut output = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => output.push_str(text),
+                Part::Expr(expr) => match expr.eval(vars)? {
+                    Value::Null => {}
+                    value => output.push_str(&to_text(&value)),
+                },
+            }
+        }
+        Ok(output)
+    }
+}
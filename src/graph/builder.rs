@@ -0,0 +1,260 @@
+use std::collections::HashSet;
+
+use flow_data::FlowData;
+use serde::Serialize;
+use serde_json::Value;
+use workflow_error::{Error, Result};
+
+use super::Graph;
+use crate::{
+    edge::LoopGuard,
+    model::node::{ControlNode, DataNode, DataProcessorMapping, Node, NodeType},
+    node::{
+        builder::build_node,
+        config::{
+            AgentConfig, AggregatorConfig, BranchConfig, ExpressionConfig, FilterConfig,
+            HttpConfig, InputConfig, LLMConfig, MapConfig, ParallelConfig, PromptConfig,
+            QuorumConfig, RaceConfig, ReduceConfig, RepeatConfig, RouterConfig, TransformConfig,
+        },
+    },
+};
+
+/// 链式构建 Graph 的辅助结构
+///
+/// 节点配置使用 `node::config` 中的类型，`build` 时统一校验：
+/// 节点配置能否实例化、边的端点是否存在、控制节点的句柄是否合法，最后编译图。
+#[derive(Debug, Default)]
+pub struct GraphBuilder {
+    nodes: Vec<Node>,
+    edges: Vec<(String, String, Option<String>)>,
+    loop_edges: Vec<(String, String, LoopGuard)>,
+    start_node: Option<String>,
+    end_node: Option<String>,
+    error: Option<Error>,
+}
+
+impl GraphBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加 Input 节点，第一个 Input 节点同时作为起始节点
+    pub fn input(mut self, id: &str, input: impl Into<FlowData>) -> Self {
+        if self.start_node.is_none() {
+            self.start_node = Some(id.to_string());
+        }
+        let config = InputConfig {
+            input: input.into(),
+        };
+        self.config_node(id, NodeType::Data(DataNode::Input), &config)
+    }
+
+    /// 添加结束节点（Identity）
+    pub fn end(mut self, id: &str) -> Self {
+        self.end_node = Some(id.to_string());
+        self.identity(id)
+    }
+
+    pub fn identity(self, id: &str) -> Self {
+        self.node(Node::new(
+            id,
+            NodeType::Data(DataNode::Identity),
+            Value::Null,
+            DataProcessorMapping::default(),
+            None,
+            None,
+        ))
+    }
+
+    pub fn prompt(self, id: &str, template: &str) -> Self {
+        let config = PromptConfig {
+            template: template.to_string(),
+        };
+        self.config_node(id, NodeType::Data(DataNode::Prompt), &config)
+    }
+
+    pub fn llm(self, id: &str, config: LLMConfig) -> Self {
+        self.config_node(id, NodeType::Data(DataNode::LLM), &config)
+    }
+
+    pub fn agent(self, id: &str, config: AgentConfig) -> Self {
+        self.config_node(id, NodeType::Data(DataNode::Agent), &config)
+    }
+
+    pub fn http(self, id: &str, config: HttpConfig) -> Self {
+        self.config_node(id, NodeType::Data(DataNode::Http), &config)
+    }
+
+    pub fn filter(self, id: &str, config: FilterConfig) -> Self {
+        self.config_node(id, NodeType::Data(DataNode::Filter), &config)
+    }
+
+    pub fn reduce(self, id: &str, config: ReduceConfig) -> Self {
+        self.config_node(id, NodeType::Data(DataNode::Reduce), &config)
+    }
+
+    pub fn expression(self, id: &str, expression: &str) -> Self {
+        let config = ExpressionConfig {
+            expression: expression.to_string(),
+            ..Default::default()
+        };
+        self.config_node(id, NodeType::Data(DataNode::Expression), &config)
+    }
+
+    pub fn transform(self, id: &str, config: TransformConfig) -> Self {
+        self.config_node(id, NodeType::Data(DataNode::Transform), &config)
+    }
+
+    pub fn branch(self, id: &str, config: BranchConfig) -> Self {
+        self.config_node(id, NodeType::Control(ControlNode::Branch), &config)
+    }
+
+    pub fn router(self, id: &str, config: RouterConfig) -> Self {
+        self.config_node(id, NodeType::Control(ControlNode::Router), &config)
+    }
+
+    pub fn parallel(self, id: &str, config: ParallelConfig) -> Self {
+        self.config_node(id, NodeType::Control(ControlNode::Parallel), &config)
+    }
+
+    pub fn repeat(self, id: &str, config: RepeatConfig) -> Self {
+        self.config_node(id, NodeType::Control(ControlNode::Repeat), &config)
+    }
+
+    pub fn race(self, id: &str, config: RaceConfig) -> Self {
+        self.config_node(id, NodeType::Control(ControlNode::Race), &config)
+    }
+
+    pub fn quorum(self, id: &str, config: QuorumConfig) -> Self {
+        self.config_node(id, NodeType::Control(ControlNode::Quorum), &config)
+    }
+
+    pub fn aggregator(self, id: &str, config: AggregatorConfig) -> Self {
+        self.config_node(id, NodeType::Control(ControlNode::Aggregator), &config)
+    }
+
+    pub fn map(self, id: &str, config: MapConfig) -> Self {
+        self.config_node(id, NodeType::Control(ControlNode::Map), &config)
+    }
+
+    /// 添加任意节点
+    pub fn node(mut self, node: Node) -> Self {
+        self.nodes.push(node);
+        self
+    }
+
+    /// 添加数据边
+    pub fn edge(mut self, source: &str, target: &str) -> Self {
+        self.edges
+            .push((source.to_string(), target.to_string(), None));
+        self
+    }
+
+    /// 添加控制节点出口边：`source` 的 `handle` 出口连接到 `target`
+    pub fn handle_edge(mut self, source: &str, handle: &str, target: &str) -> Self {
+        self.edges.push((
+            source.to_string(),
+            target.to_string(),
+            Some(handle.to_string()),
+        ));
+        self
+    }
+
+    /// 添加循环回边：`source` 完成后按 `guard` 回跳到上游的 `target`
+    pub fn loop_edge(mut self, source: &str, target: &str, guard: LoopGuard) -> Self {
+        self.loop_edges
+            .push((source.to_string(), target.to_string(), guard));
+        self
+    }
+
+    /// 校验并生成已编译的 Graph
+    pub fn build(self) -> Result<Graph> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+
+        let mut graph = Graph::new();
+        for node in self.nodes {
+            // 提前实例化一次，尽早暴露配置错误
+            build_node(&node)?;
+            graph.add_node(node)?;
+        }
+
+        // 先设置起止节点，使 add_edge 的起止规则生效
+        graph.start_node = self.start_node;
+        graph.end_node = self.end_node;
+
+        for (source, target, handle) in self.edges {
+            if let Some(node) = graph.nodes.get(&source) {
+                check_handle(node, handle.as_deref())?;
+            }
+            graph.add_edge(&source, &target, handle, None)?;
+        }
+        for (source, target, guard) in self.loop_edges {
+            graph.add_loop_edge(&source, &target, guard)?;
+        }
+
+        graph.compile()?;
+
+        Ok(graph)
+    }
+
+    fn config_node<T: Serialize>(self, id: &str, node_type: NodeType, config: &T) -> Self {
+        match serde_json::to_value(config) {
+            Ok(data) => self.node(Node::new(
+                id,
+                node_type,
+                data,
+                DataProcessorMapping::default(),
+                None,
+                None,
+            )),
+            Err(e) => self.fail(e.into()),
+        }
+    }
+
+    fn fail(mut self, error: Error) -> Self {
+        self.error.get_or_insert(error);
+        self
+    }
+}
+
+/// 检查边的源句柄是否为源节点的合法出口
+fn check_handle(node: &Node, handle: Option<&str>) -> Result<()> {
+    let handles = match &node.node_type {
+        NodeType::Control(ControlNode::Branch) => {
+            let config: BranchConfig = serde_json::from_value(node.data.clone())?;
+            let mut handles: HashSet<String> = config.branches.into_iter().map(|b| b.id).collect();
+            handles.insert("default".to_string());
+            Some(handles)
+        }
+        NodeType::Control(ControlNode::Parallel) => {
+            let config: ParallelConfig = serde_json::from_value(node.data.clone())?;
+            // 合并输出时为普通数据边
+            config
+                .output
+                .is_none()
+                .then(|| config.branches.into_keys().collect())
+        }
+        NodeType::Control(ControlNode::Router) => {
+            let config: RouterConfig = serde_json::from_value(node.data.clone())?;
+            let mut handles: HashSet<String> = config.branches.into_iter().map(|b| b.id).collect();
+            handles.insert("default".to_string());
+            Some(handles)
+        }
+        _ => None,
+    };
+
+    match (handles, handle) {
+        (Some(handles), Some(handle)) if !handles.contains(handle) => Err(Error::ExecutionError(
+            format!("Node `{}` has no handle `{}`", node.id, handle).into(),
+        )),
+        (Some(_), None) => Err(Error::ExecutionError(
+            format!("Edge from node `{}` requires a handle", node.id).into(),
+        )),
+        (None, Some(handle)) => Err(Error::ExecutionError(
+            format!("Node `{}` does not route by handle `{}`", node.id, handle).into(),
+        )),
+        _ => Ok(()),
+    }
+}
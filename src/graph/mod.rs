@@ -1,12 +1,25 @@
+pub mod builder;
+pub mod patch;
+mod render;
+
 use std::collections::{HashMap, HashSet, VecDeque};
 
 use serde::{Deserialize, Serialize};
 use workflow_error::{Error, Result};
 
 use crate::{
-    edge::{Edge, EdgeType},
-    model::{graph_data::GraphData, node::Node},
+    edge::{Edge, EdgeType, LoopGuard},
+    model::{
+        graph_data::{EdgeData, GraphData},
+        graph_doc::GraphDoc,
+        migration,
+        node::{ControlNode, DataNode, Node, NodeType},
+    },
 };
+
+pub use builder::GraphBuilder;
+pub use patch::GraphOp;
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Graph {
     /// 节点数据：持久化存储，保存节点的静态配置信息
@@ -28,6 +41,16 @@ pub struct Graph {
 
     /// 控制节点的出口映射：由 (source_node_id, source_handle) → target_node_id
     pub handle_routes: HashMap<(String, String), String>,
+
+    /// 循环回边：源节点 ID → 回边 ID 列表（按添加顺序）
+    #[serde(default)]
+    pub loop_edges: HashMap<String, Vec<String>>,
+}
+
+impl Default for Graph {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Graph {
@@ -42,6 +65,7 @@ impl Graph {
             predecessors: HashMap::new(),
             successors: HashMap::new(),
             handle_routes: HashMap::new(),
+            loop_edges: HashMap::new(),
         }
     }
 
@@ -129,39 +153,161 @@ impl Graph {
         Ok(())
     }
 
-    /// 添加边，自动推断 edge_type
+    /// 添加边，自动推断 edge_type，返回新边的 ID
     pub fn add_edge(
         &mut self,
         source: &str,
         target: &str,
         source_handle: Option<String>,
         target_handle: Option<String>,
-    ) -> Result<()> {
-        if self.end_node.as_deref() == Some(source) {
+    ) -> Result<String> {
+        let edge_type = self.check_edge(source, target)?;
+
+        let id = Edge::make_id(
+            source,
+            target,
+            source_handle.as_deref(),
+            target_handle.as_deref(),
+            self.edges.iter().map(|edge| edge.id.as_str()),
+        );
+
+        self.edges.push(Edge {
+            id: id.clone(),
+            source: source.to_string(),
+            target: target.to_string(),
+            edge_type,
+            source_handle,
+            target_handle,
+            loop_guard: None,
+        });
+
+        self.mark_uncompiled();
+
+        Ok(id)
+    }
+
+    /// 添加循环回边 `source → target`，返回新边的 ID
+    ///
+    /// `source` 执行完成后若守卫条件允许，则重新调度 `target` 到 `source` 之间的循环体，
+    /// 否则照常流向后继节点。`target` 必须能沿普通边到达 `source`，在 `compile` 时校验
+    pub fn add_loop_edge(
+        &mut self,
+        source: &str,
+        target: &str,
+        guard: LoopGuard,
+    ) -> Result<String> {
+        if self.check_edge(source, target)? == EdgeType::Control {
             return Err(Error::ExecutionError(
-                "End node cannot have outgoing edges.".to_string().into(),
+                format!("Loop edge source '{}' must be a data node", source).into(),
             ));
         }
+        if guard.max_iterations.is_none() && guard.until.is_none() {
+            return Err(Error::ExecutionError(
+                format!(
+                    "Loop edge {} -> {} requires max_iterations or until",
+                    source, target
+                )
+                .into(),
+            ));
+        }
+        if let Some(until) = &guard.until {
+            until.validate()?;
+        }
 
-        if self.start_node.as_deref() == Some(target) {
+        let id = Edge::make_id(
+            source,
+            target,
+            None,
+            None,
+            self.edges.iter().map(|edge| edge.id.as_str()),
+        );
+
+        self.edges.push(Edge {
+            id: id.clone(),
+            source: source.to_string(),
+            target: target.to_string(),
+            edge_type: EdgeType::Data,
+            source_handle: None,
+            target_handle: None,
+            loop_guard: Some(guard),
+        });
+
+        self.mark_uncompiled();
+
+        Ok(id)
+    }
+
+    /// 按持久化的边数据添加边，保留非空的边 ID，返回边 ID
+    ///
+    /// 加载与应用补丁时使用，保证边 ID 在往返中保持不变；ID 与已有边重复、
+    /// 循环回边带有句柄时返回错误
+    pub(crate) fn add_edge_data(&mut self, edge: EdgeData) -> Result<String> {
+        if self.edges.iter().any(|existing| existing.id == edge.id) {
             return Err(Error::ExecutionError(
-                "Start node cannot have incoming edges.".to_string().into(),
+                format!("Edge `{}` already exists.", edge.id).into(),
             ));
         }
 
-        if !self.nodes.contains_key(source) {
-            return Err(Error::NodeNotFound(source.to_string().into()));
+        let generated = match edge.loop_guard {
+            Some(_) if edge.source_handle.is_some() || edge.target_handle.is_some() => {
+                return Err(Error::ExecutionError(
+                    format!(
+                        "Loop edge {} -> {} cannot have handles",
+                        edge.source, edge.target
+                    )
+                    .into(),
+                ));
+            }
+            Some(guard) => self.add_loop_edge(&edge.source, &edge.target, guard)?,
+            None => self.add_edge(
+                &edge.source,
+                &edge.target,
+                edge.source_handle,
+                edge.target_handle,
+            )?,
+        };
+        if edge.id.is_empty() {
+            return Ok(generated);
         }
-        if !self.nodes.contains_key(target) {
-            return Err(Error::NodeNotFound(target.to_string().into()));
+        let index = self.edge_index(&generated)?;
+        self.edges[index].id = edge.id.clone();
+        Ok(edge.id)
+    }
+
+    /// 校验边的端点，返回推断出的 edge_type
+    ///
+    /// - 结束节点不能有出边，起始节点不能有入边
+    /// - 两端节点必须存在
+    /// - 控制节点不能直接连接到另一个控制节点
+    fn check_edge(&self, source: &str, target: &str) -> Result<EdgeType> {
+        if self.end_node.as_deref() == Some(source) {
+            return Err(Error::ExecutionError(
+                "End node cannot have outgoing edges.".to_string().into(),
+            ));
         }
 
-        let start_node = self.nodes.get(source).unwrap();
-        let end_node = self.nodes.get(target).unwrap();
+        if self.start_node.as_deref() == Some(target) {
+            return Err(Error::ExecutionError(
+                "Start node cannot have incoming edges.".to_string().into(),
+            ));
+        }
 
-        let edge_type = if start_node.is_control_node() {
-            // 控制节点出口，只能连接到数据节点
-            if end_node.is_control_node() {
+        let start_node = self
+            .nodes
+            .get(source)
+            .ok_or_else(|| Error::NodeNotFound(source.to_string().into()))?;
+        let end_node = self
+            .nodes
+            .get(target)
+            .ok_or_else(|| Error::NodeNotFound(target.to_string().into()))?;
+
+        if start_node.is_control_node() {
+            // 控制节点出口，只能连接到数据节点；Aggregator 不读取入边数据，可直接接在控制节点之后
+            let aggregator = matches!(
+                end_node.node_type,
+                NodeType::Control(ControlNode::Aggregator)
+            );
+            if end_node.is_control_node() && !aggregator {
                 return Err(Error::ExecutionError(
                     format!(
                         "Control node '{}' cannot connect to another control node '{}'",
@@ -170,27 +316,26 @@ impl Graph {
                     .into(),
                 ));
             }
-            EdgeType::Control
+            Ok(EdgeType::Control)
         } else {
             // 数据节点出口，可以连接到控制节点或数据节点
-            EdgeType::Data
-        };
-
-        self.edges.push(Edge {
-            id: format!("{}-{}", source, target),
-            source: source.to_string(),
-            target: target.to_string(),
-            edge_type,
-            source_handle,
-            target_handle,
-        });
+            Ok(EdgeType::Data)
+        }
+    }
 
-        self.mark_uncompiled();
+    /// 按 ID 获取边
+    pub fn get_edge(&self, id: &str) -> Option<&Edge> {
+        self.edges.iter().find(|edge| edge.id == id)
+    }
 
-        Ok(())
+    fn edge_index(&self, id: &str) -> Result<usize> {
+        self.edges
+            .iter()
+            .position(|edge| edge.id == id)
+            .ok_or_else(|| Error::ExecutionError(format!("Edge `{}` not found.", id).into()))
     }
 
-    /// 删除指定的边
+    /// 删除 start → end 之间的所有边（不区分句柄）
     pub fn remove_edge(&mut self, start: &str, end: &str) -> Result<()> {
         // 检查起点和终点是否存在
         if !self.nodes.contains_key(start) {
@@ -218,59 +363,73 @@ impl Graph {
         Ok(())
     }
 
-    /// 更新指定的边
-    pub fn update_edge(&mut self, new_start: &str, new_end: &str) -> Result<()> {
-        // 查找是否存在边 new_start → new_end
-        let edge_index = self
-            .edges
-            .iter()
-            .position(|edge| edge.source == new_start && edge.target == new_end);
-
-        if edge_index.is_none() {
-            return Err(Error::ExecutionError(
-                format!("Edge from {} to {} not found.", new_start, new_end).into(),
-            ));
-        }
-
-        // 检查新起点和终点节点是否存在
-        if !self.nodes.contains_key(new_start) {
-            return Err(Error::NodeNotFound(new_start.to_string().into()));
-        }
-
-        if !self.nodes.contains_key(new_end) {
-            return Err(Error::NodeNotFound(new_end.to_string().into()));
-        }
+    /// 按 ID 删除边，返回被删除的边
+    pub fn remove_edge_by_id(&mut self, id: &str) -> Result<Edge> {
+        let index = self.edge_index(id)?;
+        let edge = self.edges.remove(index);
+        self.mark_uncompiled();
+        Ok(edge)
+    }
 
-        let start_node = self.nodes.get(new_start).unwrap();
-        let end_node = self.nodes.get(new_end).unwrap();
+    /// 按 ID 更新边的端点与句柄，规则与 `add_edge` 相同
+    ///
+    /// 边 ID 与边在列表中的位置保持不变；循环回边不能设置句柄
+    pub fn update_edge(
+        &mut self,
+        id: &str,
+        source: &str,
+        target: &str,
+        source_handle: Option<String>,
+        target_handle: Option<String>,
+    ) -> Result<()> {
+        let index = self.edge_index(id)?;
+        let edge_type = self.check_edge(source, target)?;
 
-        // 推断 edge_type
-        let edge_type = if start_node.is_control_node() {
-            if end_node.is_control_node() {
+        let edge = &mut self.edges[index];
+        if edge.loop_guard.is_some() {
+            if edge_type == EdgeType::Control {
                 return Err(Error::ExecutionError(
-                    format!(
-                        "Control node '{}' cannot connect to another control node '{}'",
-                        new_start, new_end
-                    )
-                    .into(),
+                    format!("Loop edge source '{}' must be a data node", source).into(),
                 ));
             }
-            EdgeType::Control
-        } else {
-            EdgeType::Data
-        };
+            if source_handle.is_some() || target_handle.is_some() {
+                return Err(Error::ExecutionError(
+                    format!("Loop edge {} -> {} cannot have handles", source, target).into(),
+                ));
+            }
+        }
 
-        // 更新边
-        let edge = &mut self.edges[edge_index.unwrap()];
-        edge.source = new_start.to_string();
-        edge.target = new_end.to_string();
+        edge.source = source.to_string();
+        edge.target = target.to_string();
         edge.edge_type = edge_type;
+        edge.source_handle = source_handle;
+        edge.target_handle = target_handle;
 
         // 标记为未编译状态
         self.mark_uncompiled();
         Ok(())
     }
 
+    /// 修改边的起点与终点，保留句柄
+    pub fn retarget_edge(&mut self, id: &str, source: &str, target: &str) -> Result<()> {
+        let edge = &self.edges[self.edge_index(id)?];
+        let (source_handle, target_handle) =
+            (edge.source_handle.clone(), edge.target_handle.clone());
+        self.update_edge(id, source, target, source_handle, target_handle)
+    }
+
+    /// 修改边的源句柄与目标句柄（目标端口），保留端点
+    pub fn set_edge_handles(
+        &mut self,
+        id: &str,
+        source_handle: Option<String>,
+        target_handle: Option<String>,
+    ) -> Result<()> {
+        let edge = &self.edges[self.edge_index(id)?];
+        let (source, target) = (edge.source.clone(), edge.target.clone());
+        self.update_edge(id, &source, &target, source_handle, target_handle)
+    }
+
     fn topological_sort(&self) -> Result<Vec<String>> {
         let mut in_degree: HashMap<String, usize> = HashMap::new();
         let mut queue = VecDeque::new();
@@ -283,7 +442,7 @@ impl Graph {
 
         // 计算入度
         let mut seen_edges = HashSet::new();
-        for edge in &self.edges {
+        for edge in self.edges.iter().filter(|edge| !edge.is_loop()) {
             let key = (edge.source.clone(), edge.target.clone());
             if seen_edges.insert(key) {
                 *in_degree.entry(edge.target.clone()).or_insert(0) += 1;
@@ -291,10 +450,11 @@ impl Graph {
         }
 
         // 优先处理 `start_node`
-        if let Some(start) = &self.start_node {
-            if in_degree.contains_key(start) && in_degree[start] == 0 {
-                queue.push_back(start.clone());
-            }
+        if let Some(start) = &self.start_node
+            && in_degree.contains_key(start)
+            && in_degree[start] == 0
+        {
+            queue.push_back(start.clone());
         }
 
         // 将其他入度为 0 的节点入队
@@ -328,20 +488,24 @@ impl Graph {
         }
 
         // 将 `end_node` 加入结果
-        if let Some(end) = &self.end_node {
-            if self.nodes.contains_key(end) && !sorted_nodes.contains(end) {
-                sorted_nodes.push(end.clone());
-            }
+        if let Some(end) = &self.end_node
+            && self.nodes.contains_key(end)
+            && !sorted_nodes.contains(end)
+        {
+            sorted_nodes.push(end.clone());
         }
 
         Ok(sorted_nodes)
     }
 
     /// 编译图：检查循环依赖并构建前置/后继节点关系
+    ///
+    /// 循环回边不计入前置/后继关系与环检测，单独记录在 `loop_edges` 中
     pub fn compile(&mut self) -> Result<()> {
         self.predecessors.clear();
         self.successors.clear();
         self.handle_routes.clear();
+        self.loop_edges.clear();
 
         // 构建前置/后继节点关系
         for edge in &self.edges {
@@ -351,6 +515,14 @@ impl Graph {
                 ));
             }
 
+            if edge.is_loop() {
+                self.loop_edges
+                    .entry(edge.source.clone())
+                    .or_default()
+                    .push(edge.id.clone());
+                continue;
+            }
+
             self.successors
                 .entry(edge.source.clone())
                 .or_default()
@@ -379,25 +551,171 @@ impl Graph {
 
         // 调用拓扑排序方法
         self.topological_sort()?;
+
+        // 回边的目标必须是源节点的祖先（或源节点自身）
+        for edge in self.edges.iter().filter(|edge| edge.is_loop()) {
+            if self.loop_body(&edge.source, &edge.target).is_empty() {
+                return Err(Error::ExecutionError(
+                    format!(
+                        "Loop edge {} -> {} does not point back to an upstream node",
+                        edge.source, edge.target
+                    )
+                    .into(),
+                ));
+            }
+        }
+
         self.compiled = true;
 
         Ok(())
     }
 
+    /// 由其他节点在内部执行的子节点：Parallel / Race / Quorum 的分支节点、Repeat / Map 的 `child_id`
+    /// 与 Agent 的节点工具
+    ///
+    /// 这些节点不由 Runner 单独调度
+    pub fn embedded_nodes(&self) -> HashSet<String> {
+        let mut embedded = HashSet::new();
+        for node in self.nodes.values() {
+            match &node.node_type {
+                NodeType::Control(
+                    ControlNode::Parallel | ControlNode::Race | ControlNode::Quorum,
+                ) => {
+                    if let Some(branches) = node.data.get("branches").and_then(|b| b.as_object()) {
+                        embedded.extend(
+                            branches
+                                .values()
+                                .filter_map(|id| id.as_str())
+                                .map(str::to_string),
+                        );
+                    }
+                }
+                NodeType::Control(ControlNode::Repeat | ControlNode::Map) => {
+                    if let Some(child_id) = node.data.get("child_id").and_then(|id| id.as_str()) {
+                        embedded.insert(child_id.to_string());
+                    }
+                }
+                NodeType::Data(DataNode::Agent) => {
+                    if let Some(tools) = node.data.get("tools").and_then(|t| t.as_array()) {
+                        embedded.extend(
+                            tools
+                                .iter()
+                                .filter_map(|tool| tool.get("node_id")?.as_str())
+                                .map(str::to_string),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+        embedded
+    }
+
+    /// 任一前驱完成即可执行的节点（`wait` 为 `any` 的 Aggregator）
+    pub fn wait_any_nodes(&self) -> HashSet<String> {
+        self.nodes
+            .values()
+            .filter(|node| {
+                matches!(node.node_type, NodeType::Control(ControlNode::Aggregator))
+                    && node.data.get("wait").and_then(|w| w.as_str()) == Some("any")
+            })
+            .map(|node| node.id.clone())
+            .collect()
+    }
+
+    /// 回边 `source → target` 的循环体：从 `target` 出发沿普通边能到达 `source` 的所有节点
+    ///
+    /// `target` 不能到达 `source` 时返回空集合；需在 `compile` 之后调用
+    pub fn loop_body(&self, source: &str, target: &str) -> HashSet<String> {
+        let forward = reachable(target, &self.successors);
+        let backward = reachable(source, &self.predecessors);
+        forward.intersection(&backward).cloned().collect()
+    }
+
     /// 序列化为 JSON 字符串
     pub fn to_json(&self) -> String {
         let graph_data: GraphData = self.clone().into();
         serde_json::to_string_pretty(&graph_data).expect("Failed to serialize Graph")
     }
 
-    /// 从 JSON 字符串反序列化
+    /// 规范化的紧凑 JSON：节点与边按 ID 排序、对象键按字典序，适合比较与哈希
+    pub fn to_canonical_json(&self) -> String {
+        let graph_data = GraphData::from(self.clone()).canonicalize();
+        serde_json::to_value(&graph_data)
+            .expect("Failed to serialize Graph")
+            .to_string()
+    }
+
+    /// 基于规范化 JSON 的 64 位指纹（FNV-1a），跨进程、跨版本稳定
+    pub fn fingerprint(&self) -> u64 {
+        const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0100_0000_01b3;
+
+        self.to_canonical_json().bytes().fold(OFFSET, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(PRIME)
+        })
+    }
+
+    /// 从 JSON 字符串反序列化，旧版本文档会先迁移到当前 schema 版本
     pub fn from_json(json: &str) -> Result<Self> {
-        let graph_data: GraphData = serde_json::from_str(json)?;
-        let mut graph: Graph = graph_data.into();
+        let doc = migration::migrate(serde_json::from_str(json)?)?;
+        let graph_data: GraphData = serde_json::from_value(doc)?;
+        let mut graph = Graph::try_from(graph_data)?;
 
         // 调用 compile() 构建 predecessors 和 successors
         graph.compile()?;
 
         Ok(graph)
     }
+
+    /// 序列化为 YAML 字符串（简写格式，见 `GraphDoc`）
+    pub fn to_yaml(&self) -> Result<String> {
+        Ok(serde_yaml::to_string(&self.to_doc())?)
+    }
+
+    /// 从 YAML 字符串反序列化（简写格式，见 `GraphDoc`）
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        let doc: GraphDoc = serde_yaml::from_str(yaml)?;
+        Self::from_doc(doc)
+    }
+
+    /// 序列化为 TOML 字符串（简写格式，见 `GraphDoc`）
+    ///
+    /// TOML 不支持 null，节点配置中含有 null 值时会返回错误
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string(&self.to_doc())?)
+    }
+
+    /// 从 TOML 字符串反序列化（简写格式，见 `GraphDoc`）
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let doc: GraphDoc = toml::from_str(text)?;
+        Self::from_doc(doc)
+    }
+
+    fn to_doc(&self) -> GraphDoc {
+        let graph_data: GraphData = self.clone().into();
+        graph_data.into()
+    }
+
+    fn from_doc(doc: GraphDoc) -> Result<Self> {
+        let graph_data = GraphData::try_from(doc)?;
+        let mut graph = Graph::try_from(graph_data)?;
+        graph.compile()?;
+
+        Ok(graph)
+    }
+}
+
+/// 沿 `links` 从 `from` 出发可到达的节点（含自身）
+fn reachable(from: &str, links: &HashMap<String, HashSet<String>>) -> HashSet<String> {
+    let mut visited = HashSet::from([from.to_string()]);
+    let mut stack = vec![from.to_string()];
+    while let Some(current) = stack.pop() {
+        for next in links.get(&current).into_iter().flatten() {
+            if visited.insert(next.clone()) {
+                stack.push(next.clone());
+            }
+        }
+    }
+    visited
 }
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use workflow_error::{Error, Result};
+
+use super::Graph;
+use crate::model::{graph_data::EdgeData, node::Node};
+
+/// 图编辑操作，`Graph::diff` 生成，`Graph::apply_patch` 应用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum GraphOp {
+    AddNode { node: Node },
+    UpdateNode { node: Node },
+    RemoveNode { id: String },
+    AddEdge { edge: EdgeData },
+    RemoveEdge { id: String },
+    SetStartNode { id: Option<String> },
+    SetEndNode { id: Option<String> },
+}
+
+impl Graph {
+    /// 计算从 `self` 变为 `other` 所需的操作列表
+    ///
+    /// 节点按 ID 比较，边按边 ID 比较；结果顺序保证可直接交给 `apply_patch`：
+    /// 先删边、删节点，再加/改节点、设置起止节点，最后加边
+    pub fn diff(&self, other: &Graph) -> Vec<GraphOp> {
+        let mut ops = Vec::new();
+
+        let self_edges: HashMap<&str, EdgeData> = self
+            .edges
+            .iter()
+            .map(|edge| (edge.id.as_str(), EdgeData::from(edge.clone())))
+            .collect();
+        let other_edges: HashMap<&str, EdgeData> = other
+            .edges
+            .iter()
+            .map(|edge| (edge.id.as_str(), EdgeData::from(edge.clone())))
+            .collect();
+
+        for edge in &self.edges {
+            let changed = other_edges
+                .get(edge.id.as_str())
+                .is_none_or(|other_edge| *other_edge != self_edges[edge.id.as_str()]);
+            if changed {
+                ops.push(GraphOp::RemoveEdge {
+                    id: edge.id.clone(),
+                });
+            }
+        }
+
+        let mut removed: Vec<&String> = self
+            .nodes
+            .keys()
+            .filter(|id| !other.nodes.contains_key(*id))
+            .collect();
+        removed.sort();
+        ops.extend(
+            removed
+                .into_iter()
+                .map(|id| GraphOp::RemoveNode { id: id.clone() }),
+        );
+
+        let mut nodes: Vec<&Node> = other.nodes.values().collect();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        for node in nodes {
+            match self.nodes.get(&node.id) {
+                None => ops.push(GraphOp::AddNode { node: node.clone() }),
+                Some(current) if current != node => {
+                    ops.push(GraphOp::UpdateNode { node: node.clone() })
+                }
+                Some(_) => {}
+            }
+        }
+
+        if self.start_node != other.start_node {
+            ops.push(GraphOp::SetStartNode {
+                id: other.start_node.clone(),
+            });
+        }
+        if self.end_node != other.end_node {
+            ops.push(GraphOp::SetEndNode {
+                id: other.end_node.clone(),
+            });
+        }
+
+        for edge in &other.edges {
+            let changed = self_edges
+                .get(edge.id.as_str())
+                .is_none_or(|self_edge| *self_edge != other_edges[edge.id.as_str()]);
+            if changed {
+                ops.push(GraphOp::AddEdge {
+                    edge: other_edges[edge.id.as_str()].clone(),
+                });
+            }
+        }
+
+        ops
+    }
+
+    /// 原子地应用一组编辑操作：任一操作失败或结果无法编译时回滚并返回错误
+    pub fn apply_patch(&mut self, ops: Vec<GraphOp>) -> Result<()> {
+        let snapshot = self.clone();
+
+        let result = ops
+            .into_iter()
+            .try_for_each(|op| self.apply_op(op))
+            .and_then(|_| self.compile());
+
+        if result.is_err() {
+            *self = snapshot;
+        }
+        result
+    }
+
+    fn apply_op(&mut self, op: GraphOp) -> Result<()> {
+        match op {
+            GraphOp::AddNode { node } => self.add_node(node),
+            GraphOp::UpdateNode { node } => self.update_node(node),
+            GraphOp::RemoveNode { id } => self.remove_node(&id),
+            GraphOp::AddEdge { edge } => self.add_edge_data(edge).map(|_| ()),
+            GraphOp::RemoveEdge { id } => self.remove_edge_by_id(&id).map(|_| ()),
+            GraphOp::SetStartNode { id } => {
+                self.check_node_exists(id.as_deref())?;
+                self.start_node = id;
+                self.mark_uncompiled();
+                Ok(())
+            }
+            GraphOp::SetEndNode { id } => {
+                self.check_node_exists(id.as_deref())?;
+                self.end_node = id;
+                self.mark_uncompiled();
+                Ok(())
+            }
+        }
+    }
+
+    fn check_node_exists(&self, id: Option<&str>) -> Result<()> {
+        match id {
+            Some(id) if !self.nodes.contains_key(id) => {
+                Err(Error::NodeNotFound(id.to_string().into()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use std::{collections::HashMap, fmt::Write};
+
+use super::Graph;
+use crate::{
+    edge::{Edge, EdgeType},
+    model::node::{Node, NodeType},
+    node::base::NodeState,
+    runner::NodeRun,
+};
+
+impl Graph {
+    /// 导出为 Mermaid flowchart
+    pub fn to_mermaid(&self) -> String {
+        self.render_mermaid(None)
+    }
+
+    /// 导出为 Mermaid flowchart，并标注一次运行中的节点状态与耗时
+    pub fn to_mermaid_with_runs(&self, runs: &HashMap<String, NodeRun>) -> String {
+        self.render_mermaid(Some(runs))
+    }
+
+    /// 导出为 Graphviz DOT
+    pub fn to_dot(&self) -> String {
+        self.render_dot(None)
+    }
+
+    /// 导出为 Graphviz DOT，并标注一次运行中的节点状态与耗时
+    pub fn to_dot_with_runs(&self, runs: &HashMap<String, NodeRun>) -> String {
+        self.render_dot(Some(runs))
+    }
+
+    /// 按 id 排序的节点列表，保证输出稳定
+    fn sorted_nodes(&self) -> Vec<&Node> {
+        let mut nodes: Vec<&Node> = self.nodes.values().collect();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        nodes
+    }
+
+    fn render_mermaid(&self, runs: Option<&HashMap<String, NodeRun>>) -> String {
+        let nodes = self.sorted_nodes();
+        // Mermaid 对节点 id 的字符有限制，统一映射为 n0, n1, ...
+        let keys: HashMap<&str, String> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.id.as_str(), format!("n{}", i)))
+            .collect();
+
+        let mut out = String::from("flowchart TD\n");
+
+        for node in &nodes {
+            let key = &keys[node.id.as_str()];
+            let mut label = format!(
+                "{}<br/>{}",
+                escape_mermaid(&node.id),
+                type_label(&node.node_type)
+            );
+            let run = runs.and_then(|runs| runs.get(&node.id));
+            if let Some(elapsed) = run.and_then(|run| run.elapsed) {
+                let _ = write!(label, "<br/>{:.1?}", elapsed);
+            }
+
+            let _ = if self.is_terminal(&node.id) {
+                writeln!(out, "    {}([\"{}\"])", key, label)
+            } else if node.is_control_node() {
+                writeln!(out, "    {}{{\"{}\"}}", key, label)
+            } else {
+                writeln!(out, "    {}[\"{}\"]", key, label)
+            };
+        }
+
+        for edge in &self.edges {
+            let (Some(source), Some(target)) = (
+                keys.get(edge.source.as_str()),
+                keys.get(edge.target.as_str()),
+            ) else {
+                continue;
+            };
+            let arrow = match edge.edge_type {
+                _ if edge.is_loop() => "==>",
+                EdgeType::Data => "-->",
+                EdgeType::Control => "-.->",
+            };
+            let _ = match edge_label(edge) {
+                Some(label) => writeln!(
+                    out,
+                    "    {} {}|\"{}\"| {}",
+                    source,
+                    arrow,
+                    escape_mermaid(&label),
+                    target
+                ),
+                None => writeln!(out, "    {} {} {}", source, arrow, target),
+            };
+        }
+
+        if let Some(runs) = runs {
+            for (class, fill) in STATE_STYLES {
+                let _ = writeln!(out, "    classDef {} fill:{}", class, fill);
+            }
+            for node in &nodes {
+                if let Some(run) = runs.get(&node.id) {
+                    let _ = writeln!(
+                        out,
+                        "    class {} {}",
+                        keys[node.id.as_str()],
+                        state_class(&run.state)
+                    );
+                }
+            }
+        }
+
+        out
+    }
+
+    fn render_dot(&self, runs: Option<&HashMap<String, NodeRun>>) -> String {
+        let mut out = String::from("digraph workflow {\n    rankdir=TB;\n");
+
+        for node in self.sorted_nodes() {
+            let mut label = format!("{}\n{}", node.id, type_label(&node.node_type));
+            let run = runs.and_then(|runs| runs.get(&node.id));
+            if let Some(elapsed) = run.and_then(|run| run.elapsed) {
+                let _ = write!(label, "\n{:.1?}", elapsed);
+            }
+
+            let shape = if self.is_terminal(&node.id) {
+                "oval"
+            } else if node.is_control_node() {
+                "diamond"
+            } else {
+                "box"
+            };
+
+            let mut attrs = format!("label=\"{}\", shape={}", escape_dot(&label), shape);
+            if let Some(run) = run {
+                let _ = write!(
+                    attrs,
+                    ", style=filled, fillcolor=\"{}\"",
+                    state_fill(&run.state)
+                );
+            }
+            let _ = writeln!(out, "    \"{}\" [{}];", escape_dot(&node.id), attrs);
+        }
+
+        for edge in &self.edges {
+            let mut attrs = Vec::new();
+            if edge.is_loop() {
+                // 回边不参与分层，避免打乱主流程的布局
+                attrs.push("style=bold, constraint=false".to_string());
+            } else if edge.edge_type == EdgeType::Control {
+                attrs.push("style=dashed".to_string());
+            }
+            if let Some(label) = edge_label(edge) {
+                attrs.push(format!("label=\"{}\"", escape_dot(&label)));
+            }
+            let attrs = if attrs.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", attrs.join(", "))
+            };
+            let _ = writeln!(
+                out,
+                "    \"{}\" -> \"{}\"{};",
+                escape_dot(&edge.source),
+                escape_dot(&edge.target),
+                attrs
+            );
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    fn is_terminal(&self, node_id: &str) -> bool {
+        self.start_node.as_deref() == Some(node_id) || self.end_node.as_deref() == Some(node_id)
+    }
+}
+
+/// 各节点状态对应的样式类名与填充色
+const STATE_STYLES: [(&str, &str); 6] = [
+    ("pending", "#eeeeee"),
+    ("running", "#fff3cd"),
+    ("completed", "#d4edda"),
+    ("failed", "#f8d7da"),
+    ("cancelled", "#d6d8db"),
+    ("skipped", "#ffffff"),
+];
+
+fn state_class(state: &NodeState) -> &'static str {
+    match state {
+        NodeState::Pending => STATE_STYLES[0].0,
+        NodeState::Running => STATE_STYLES[1].0,
+        NodeState::Completed => STATE_STYLES[2].0,
+        NodeState::Failed => STATE_STYLES[3].0,
+        NodeState::Cancelled => STATE_STYLES[4].0,
+        NodeState::Skipped => STATE_STYLES[5].0,
+    }
+}
+
+fn state_fill(state: &NodeState) -> &'static str {
+    match state {
+        NodeState::Pending => STATE_STYLES[0].1,
+        NodeState::Running => STATE_STYLES[1].1,
+        NodeState::Completed => STATE_STYLES[2].1,
+        NodeState::Failed => STATE_STYLES[3].1,
+        NodeState::Cancelled => STATE_STYLES[4].1,
+        NodeState::Skipped => STATE_STYLES[5].1,
+    }
+}
+
+fn type_label(node_type: &NodeType) -> String {
+    match node_type {
+        NodeType::Data(kind) => format!("Data:{:?}", kind),
+        NodeType::Control(kind) => format!("Control:{:?}", kind),
+    }
+}
+
+/// 边标签：源句柄与目标句柄；回边显示守卫条件
+fn edge_label(edge: &Edge) -> Option<String> {
+    if let Some(guard) = &edge.loop_guard {
+        let mut label = String::from("loop");
+        if let Some(max) = guard.max_iterations {
+            let _ = write!(label, " ≤{}", max);
+        }
+        if let Some(until) = &guard.until {
+            let _ = write!(label, " until {}", until);
+        }
+        return Some(label);
+    }
+
+    match (&edge.source_handle, &edge.target_handle) {
+        (Some(source), Some(target)) => Some(format!("{} → {}", source, target)),
+        (Some(source), None) => Some(source.clone()),
+        (None, Some(target)) => Some(format!("→ {}", target)),
+        (None, None) => None,
+    }
+}
+
+fn escape_mermaid(text: &str) -> String {
+    text.chars().fold(String::new(), |mut out, c| {
+        match c {
+            '"' => out.push_str("#quot;"),
+            '&' => out.push_str("#amp;"),
+            '<' => out.push_str("#lt;"),
+            '>' => out.push_str("#gt;"),
+            c => out.push(c),
+        }
+        out
+    })
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
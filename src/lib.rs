@@ -1,10 +1,13 @@
 pub mod edge;
+pub mod expr;
 pub mod graph;
 pub mod inputs;
 pub mod mcp;
+pub mod memory;
 pub mod model;
 pub mod node;
 pub mod processor;
+pub mod provider;
 pub mod runner;
 pub mod storage;
 pub mod types;
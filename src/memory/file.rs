@@ -0,0 +1,62 @@
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use model_gateway_rs::model::llm::ChatMessage;
+use workflow_error::{Error, Result};
+
+use super::MemoryStore;
+
+/// 以 JSON 文件持久化的会话记忆：每个会话保存为 `<dir>/<session_id>.json`
+#[derive(Debug, Clone)]
+pub struct FileMemoryStore {
+    dir: PathBuf,
+}
+
+impl FileMemoryStore {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// 会话文件路径；会话 ID 只允许字母、数字、`-` 与 `_`，避免写到目录之外
+    fn path(&self, session_id: &str) -> Result<PathBuf> {
+        let valid = !session_id.is_empty()
+            && session_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(Error::ExecutionError(
+                format!("Invalid session id `{}` for FileMemoryStore", session_id).into(),
+            ));
+        }
+        Ok(self.dir.join(format!("{}.json", session_id)))
+    }
+}
+
+impl MemoryStore for FileMemoryStore {
+    fn load(&self, session_id: &str) -> Result<Vec<ChatMessage>> {
+        match fs::read_to_string(self.path(session_id)?) {
+            Ok(json) => Ok(serde_json::from_str(&json)?),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, session_id: &str, messages: &[ChatMessage]) -> Result<()> {
+        let path = self.path(session_id)?;
+        fs::create_dir_all(&self.dir)?;
+        fs::write(path, serde_json::to_string_pretty(messages)?)?;
+        Ok(())
+    }
+
+    fn clear(&self, session_id: &str) -> Result<()> {
+        match fs::remove_file(self.path(session_id)?) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+pub mod file;
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use model_gateway_rs::model::llm::ChatMessage;
+use once_cell::sync::Lazy;
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+use workflow_error::{Error, Result};
+
+pub use file::FileMemoryStore;
+
+/// 存储名缺省值，对应进程内的 `InMemoryStore`
+pub const DEFAULT_MEMORY_STORE: &str = "default";
+
+/// 会话记忆存储：按会话 ID 保存对话消息
+///
+/// `load` 与 `save` 各自独立，不构成事务；LLM 节点通过 `MemoryStoreRegistry::lock_session`
+/// 在进程内串行化同一会话的一轮对话，多个进程共享存储时需由存储自行处理并发写入
+pub trait MemoryStore: Send + Sync {
+    /// 读取会话的全部消息，会话不存在时返回空列表
+    fn load(&self, session_id: &str) -> Result<Vec<ChatMessage>>;
+
+    /// 用 `messages` 替换会话的全部消息
+    fn save(&self, session_id: &str, messages: &[ChatMessage]) -> Result<()>;
+
+    /// 删除会话
+    fn clear(&self, session_id: &str) -> Result<()>;
+}
+
+/// 进程内的会话记忆，进程退出后丢失
+#[derive(Default)]
+pub struct InMemoryStore {
+    sessions: Mutex<HashMap<String, Vec<ChatMessage>>>,
+}
+
+impl MemoryStore for InMemoryStore {
+    fn load(&self, session_id: &str) -> Result<Vec<ChatMessage>> {
+        let sessions = self.sessions.lock().unwrap();
+        Ok(sessions.get(session_id).cloned().unwrap_or_default())
+    }
+
+    fn save(&self, session_id: &str, messages: &[ChatMessage]) -> Result<()> {
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.insert(session_id.to_string(), messages.to_vec());
+        Ok(())
+    }
+
+    fn clear(&self, session_id: &str) -> Result<()> {
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.remove(session_id);
+        Ok(())
+    }
+}
+
+/// 会话锁，以 (存储名, 会话 ID) 为键
+type SessionLock = Arc<AsyncMutex<()>>;
+
+/// Memory Store Registry，供 LLM 节点的 `memory.store` 按名称查找
+pub struct MemoryStoreRegistry {
+    stores: Mutex<HashMap<String, Arc<dyn MemoryStore>>>,
+    sessions: Mutex<HashMap<(String, String), SessionLock>>,
+}
+
+impl Default for MemoryStoreRegistry {
+    fn default() -> Self {
+        let registry = Self {
+            stores: Mutex::new(HashMap::new()),
+            sessions: Mutex::new(HashMap::new()),
+        };
+        registry.register(DEFAULT_MEMORY_STORE, Arc::new(InMemoryStore::default()));
+        registry
+    }
+}
+
+impl MemoryStoreRegistry {
+    pub fn register(&self, name: &str, store: Arc<dyn MemoryStore>) {
+        let mut stores = self.stores.lock().unwrap();
+        stores.insert(name.to_string(), store);
+    }
+
+    pub fn get(&self, name: &str) -> Result<Arc<dyn MemoryStore>> {
+        let stores = self.stores.lock().unwrap();
+        stores.get(name).cloned().ok_or_else(|| {
+            Error::ExecutionError(format!("Memory store `{}` is not registered", name).into())
+        })
+    }
+
+    /// 获取会话锁，持有期间同一存储中同一会话的其他对话轮次等待
+    pub async fn lock_session(&self, store: &str, session_id: &str) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut sessions = self.sessions.lock().unwrap();
+            // 清理无人持有或等待的会话锁
+            sessions.retain(|_, lock| Arc::strong_count(lock) > 1);
+            sessions
+                .entry((store.to_string(), session_id.to_string()))
+                .or_default()
+                .clone()
+        };
+        lock.lock_owned().await
+    }
+}
+
+pub static MEMORY_REGISTRY: Lazy<MemoryStoreRegistry> = Lazy::new(MemoryStoreRegistry::default);
@@ -1,4 +1,9 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use flow_data::FlowData;
 
 use crate::{
     graph::Graph,
@@ -10,6 +15,10 @@ use crate::{
 pub struct Context {
     pub nodes: HashMap<String, Arc<dyn Executable>>,
     pub metadata: HashMap<String, String>,
+    /// 本次运行中已完成节点的输出：节点ID → 输出
+    outputs: Arc<RwLock<HashMap<String, FlowData>>>,
+    /// 本次运行所属的会话，LLM 节点的会话记忆按此读写
+    session_id: Option<String>,
 }
 
 impl Context {
@@ -26,14 +35,48 @@ impl Context {
         Arc::new(Self {
             nodes,
             metadata: HashMap::new(),
+            outputs: Arc::default(),
+            session_id: None,
         })
     }
 
+    /// 根据 Graph 生成节点实例，并指定本次运行所属的会话
+    pub fn with_session(graph: &Graph, session_id: &str) -> Arc<Self> {
+        let mut context = Arc::unwrap_or_clone(Self::from_graph(graph));
+        context.session_id = Some(session_id.to_string());
+        Arc::new(context)
+    }
+
+    /// 本次运行所属的会话 ID
+    pub fn session_id(&self) -> Option<&str> {
+        self.session_id.as_deref()
+    }
+
     /// 获取节点实例
     pub fn get_node(&self, id: &str) -> Option<&Arc<dyn Executable>> {
         self.nodes.get(id)
     }
 
+    /// 记录节点在本次运行中的输出，重复执行时覆盖
+    pub fn record_output(&self, id: &str, data: FlowData) {
+        if let Ok(mut outputs) = self.outputs.write() {
+            outputs.insert(id.to_string(), data);
+        }
+    }
+
+    /// 清除节点的输出，循环回跳时由 Runner 对将重新执行的节点调用
+    pub fn clear_output(&self, id: &str) {
+        if let Ok(mut outputs) = self.outputs.write() {
+            outputs.remove(id);
+        }
+    }
+
+    /// 获取节点在本次运行中的输出，节点未执行或被跳过时为 None；
+    /// 循环中为当前轮次的输出，本轮尚未执行或被跳过时同样为 None
+    pub fn get_output(&self, id: &str) -> Option<FlowData> {
+        self.outputs.read().ok()?.get(id).cloned()
+    }
+
     /// 设置元数据
     pub fn set_metadata(&mut self, key: &str, value: &str) {
         self.metadata.insert(key.to_string(), value.to_string());
@@ -166,6 +166,12 @@ pub struct DataCollection {
     pub video_frames: Vec<VideoFrames>,
 }
 
+impl Default for DataCollection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DataCollection {
     /// 创建空集合
     pub fn new() -> Self {
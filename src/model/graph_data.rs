@@ -1,9 +1,13 @@
 use serde::{Deserialize, Serialize};
+use workflow_error::{Error, Result};
 
-use super::node::Node;
-use crate::{edge::Edge, graph::Graph};
+use super::{migration::CURRENT_SCHEMA_VERSION, node::Node};
+use crate::{
+    edge::{Edge, LoopGuard},
+    graph::Graph,
+};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EdgeData {
     pub id: String,
@@ -11,11 +15,17 @@ pub struct EdgeData {
     pub target: String,
     pub source_handle: Option<String>,
     pub target_handle: Option<String>,
+    /// 循环回边守卫，普通边为 None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loop_guard: Option<LoopGuard>,
 }
 
 /// 持久化使用的 Graph 数据结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphData {
+    /// schema 版本，缺省视为 0，加载时由 `migration::migrate` 升级
+    #[serde(default)]
+    pub schema_version: u32,
     /// 节点数据：节点 ID -> 节点配置
     pub nodes: Vec<Node>,
     /// 边信息
@@ -34,14 +44,20 @@ impl From<Edge> for EdgeData {
             target: edge.target,
             source_handle: edge.source_handle,
             target_handle: edge.target_handle,
+            loop_guard: edge.loop_guard,
         }
     }
 }
 
 impl From<Graph> for GraphData {
+    /// 节点按 ID 排序，边保持添加顺序，保证多次序列化结果一致
     fn from(graph: Graph) -> Self {
+        let mut nodes: Vec<Node> = graph.nodes.into_values().collect();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+
         Self {
-            nodes: graph.nodes.values().cloned().collect(),
+            schema_version: CURRENT_SCHEMA_VERSION,
+            nodes,
             edges: graph.edges.iter().cloned().map(EdgeData::from).collect(),
             start_node: graph.start_node,
             end_node: graph.end_node,
@@ -49,25 +65,28 @@ impl From<Graph> for GraphData {
     }
 }
 
-impl From<GraphData> for Graph {
-    fn from(data: GraphData) -> Self {
+impl TryFrom<GraphData> for Graph {
+    type Error = Error;
+
+    /// 逐个添加节点、设置起止节点后再添加边，保留边 ID；节点或边 ID 重复、
+    /// 边端点不存在、违反起止节点规则或循环回边不合法时返回错误
+    fn try_from(data: GraphData) -> Result<Self> {
         let mut graph = Graph::new();
         for node in data.nodes {
-            graph.add_node(node).unwrap();
-        }
-        for edge in data.edges {
-            graph
-                .add_edge(
-                    &edge.source,
-                    &edge.target,
-                    edge.source_handle,
-                    edge.target_handle,
-                )
-                .unwrap();
+            graph.add_node(node)?;
         }
         graph.start_node = data.start_node;
         graph.end_node = data.end_node;
-        graph
+        for edge in data.edges {
+            graph.add_edge_data(edge)?;
+        }
+        Ok(graph)
+    }
+}
+
+impl Default for GraphData {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -75,6 +94,7 @@ impl GraphData {
     /// 创建新的 GraphData
     pub fn new() -> Self {
         Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
             nodes: Vec::new(),
             edges: Vec::new(),
             start_node: None,
@@ -82,6 +102,13 @@ impl GraphData {
         }
     }
 
+    /// 规范化：节点与边均按 ID 排序，用于比较与哈希
+    pub fn canonicalize(mut self) -> Self {
+        self.nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        self.edges.sort_by(|a, b| a.id.cmp(&b.id));
+        self
+    }
+
     /// 添加节点数据
     pub fn add_node(&mut self, data: Node) {
         self.nodes.push(data);
@@ -95,12 +122,41 @@ impl GraphData {
         source_handle: Option<String>,
         target_handle: Option<String>,
     ) {
+        let id = Edge::make_id(
+            source,
+            target,
+            source_handle.as_deref(),
+            target_handle.as_deref(),
+            self.edges.iter().map(|edge| edge.id.as_str()),
+        );
+
         self.edges.push(EdgeData {
-            id: format!("{}-{}", source, target),
+            id,
             source: source.to_string(),
             target: target.to_string(),
             source_handle,
             target_handle,
+            loop_guard: None,
+        });
+    }
+
+    /// 添加循环回边信息
+    pub fn add_loop_edge(&mut self, source: &str, target: &str, guard: LoopGuard) {
+        let id = Edge::make_id(
+            source,
+            target,
+            None,
+            None,
+            self.edges.iter().map(|edge| edge.id.as_str()),
+        );
+
+        self.edges.push(EdgeData {
+            id,
+            source: source.to_string(),
+            target: target.to_string(),
+            source_handle: None,
+            target_handle: None,
+            loop_guard: Some(guard),
         });
     }
 }
@@ -0,0 +1,304 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use workflow_error::{Error, Result};
+
+use super::{
+    graph_data::{EdgeData, GraphData},
+    node::{DataNode, DataProcessorMapping, Node, NodeType},
+};
+use crate::edge::LoopGuard;
+
+/// 手写友好的图定义格式（用于 YAML / TOML），加载时降级为 `GraphData`
+///
+/// ```yaml
+/// start: start
+/// end: end
+/// nodes:
+///   start:
+///     type: Input
+///     data:
+///       input: "hello"   # 等价于 {type: Single, value: {type: Text, value: hello}}
+///   route:
+///     type: Branch
+///     data: { ... }
+///   end:
+///     type: Identity
+/// edges:
+///   - start -> route
+///   - route.yes -> end
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphDoc {
+    /// 起始节点
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    /// 结束节点
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    /// 节点：节点 ID -> 节点定义
+    #[serde(default)]
+    pub nodes: BTreeMap<String, NodeDoc>,
+    /// 边：`source[.handle] -> target[.handle]` 或完整形式
+    #[serde(default)]
+    pub edges: Vec<EdgeDoc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeDoc {
+    /// 节点类型，按类型名读写，如 `Input`、`LLM`、`Branch`
+    #[serde(rename = "type", with = "node_kind")]
+    pub kind: NodeType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_processor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_processor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum EdgeDoc {
+    /// 简写形式：`a -> b`、`branch.yes -> c`
+    Short(String),
+    /// 完整形式，用于节点 ID 中含有 `.` 的情况或循环回边
+    Full {
+        source: String,
+        target: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        source_handle: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        target_handle: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        loop_guard: Option<Box<LoopGuard>>,
+    },
+}
+
+/// `EdgeDoc` 解析结果：(source, target, source_handle, target_handle, loop_guard)
+type EdgeParts = (
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<LoopGuard>,
+);
+
+impl EdgeDoc {
+    fn into_parts(self) -> Result<EdgeParts> {
+        match self {
+            EdgeDoc::Full {
+                source,
+                target,
+                source_handle,
+                target_handle,
+                loop_guard,
+            } => Ok((
+                source,
+                target,
+                source_handle,
+                target_handle,
+                loop_guard.map(|guard| *guard),
+            )),
+            EdgeDoc::Short(text) => {
+                let (source, target) = text.split_once("->").ok_or_else(|| {
+                    Error::ExecutionError(format!("Invalid edge definition: `{}`", text).into())
+                })?;
+                let (source, source_handle) = split_endpoint(source);
+                let (target, target_handle) = split_endpoint(target);
+                if source.is_empty() || target.is_empty() {
+                    return Err(Error::ExecutionError(
+                        format!("Invalid edge definition: `{}`", text).into(),
+                    ));
+                }
+                Ok((source, target, source_handle, target_handle, None))
+            }
+        }
+    }
+}
+
+impl From<EdgeData> for EdgeDoc {
+    fn from(edge: EdgeData) -> Self {
+        if edge.source.contains('.') || edge.target.contains('.') || edge.loop_guard.is_some() {
+            return EdgeDoc::Full {
+                source: edge.source,
+                target: edge.target,
+                source_handle: edge.source_handle,
+                target_handle: edge.target_handle,
+                loop_guard: edge.loop_guard.map(Box::new),
+            };
+        }
+
+        let endpoint = |id: &str, handle: &Option<String>| match handle {
+            Some(handle) => format!("{}.{}", id, handle),
+            None => id.to_string(),
+        };
+        EdgeDoc::Short(format!(
+            "{} -> {}",
+            endpoint(&edge.source, &edge.source_handle),
+            endpoint(&edge.target, &edge.target_handle)
+        ))
+    }
+}
+
+/// 拆分 `node.handle`，句柄可选
+fn split_endpoint(text: &str) -> (String, Option<String>) {
+    let text = text.trim();
+    match text.split_once('.') {
+        Some((id, handle)) => (id.trim().to_string(), Some(handle.trim().to_string())),
+        None => (text.to_string(), None),
+    }
+}
+
+/// 以类型名读写 `NodeType`，未知类型名作为反序列化错误报告
+mod node_kind {
+    use serde::{Deserialize, Deserializer, Serializer, de};
+    use serde_json::Value;
+
+    use crate::model::node::NodeType;
+
+    pub fn serialize<S: Serializer>(
+        node_type: &NodeType,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let value = match node_type {
+            NodeType::Data(node) => serde_json::to_value(node),
+            NodeType::Control(node) => serde_json::to_value(node),
+        };
+        let name = value.ok().and_then(|v| v.as_str().map(str::to_string));
+        serializer.serialize_str(&name.unwrap_or_default())
+    }
+
+    /// 数据节点与控制节点的名称互不重复
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NodeType, D::Error> {
+        let kind = String::deserialize(deserializer)?;
+        let name = Value::String(kind.clone());
+        if let Ok(node) = serde_json::from_value(name.clone()) {
+            return Ok(NodeType::Data(node));
+        }
+        if let Ok(node) = serde_json::from_value(name) {
+            return Ok(NodeType::Control(node));
+        }
+        Err(de::Error::custom(format!("unknown node type `{}`", kind)))
+    }
+}
+
+/// 将 Input 节点的简写输入（字符串 / 数字）展开为 `FlowData` 的标签格式
+fn expand_input(mut data: Value) -> Value {
+    if let Some(input) = data.get_mut("input") {
+        let expanded = match input {
+            Value::String(_) => {
+                Some(json!({ "type": "Single", "value": { "type": "Text", "value": input } }))
+            }
+            Value::Number(_) => {
+                Some(json!({ "type": "Single", "value": { "type": "Number", "value": input } }))
+            }
+            _ => None,
+        };
+        if let Some(expanded) = expanded {
+            *input = expanded;
+        }
+    }
+    data
+}
+
+/// `expand_input` 的逆过程，仅收缩文本与数字输入
+fn collapse_input(mut data: Value) -> Value {
+    if let Some(input) = data.get_mut("input") {
+        let single = input
+            .get("value")
+            .filter(|_| input.get("type").and_then(Value::as_str) == Some("Single"));
+        let collapsed =
+            single.and_then(|single| match single.get("type").and_then(Value::as_str) {
+                Some("Text") | Some("Number") => single.get("value").cloned(),
+                _ => None,
+            });
+        if let Some(collapsed) = collapsed {
+            *input = collapsed;
+        }
+    }
+    data
+}
+
+impl TryFrom<GraphDoc> for GraphData {
+    type Error = Error;
+
+    fn try_from(doc: GraphDoc) -> Result<Self> {
+        let mut graph_data = GraphData::new();
+
+        for (id, node) in doc.nodes {
+            let node_type = node.kind;
+            let mut data = node.data.unwrap_or(Value::Null);
+            if matches!(node_type, NodeType::Data(DataNode::Input)) {
+                data = expand_input(data);
+            }
+
+            graph_data.add_node(Node::new(
+                &id,
+                node_type,
+                data,
+                DataProcessorMapping {
+                    input: node.input_processor,
+                    output: node.output_processor,
+                },
+                node.input_id,
+                node.output_id,
+            ));
+        }
+
+        for edge in doc.edges {
+            let (source, target, source_handle, target_handle, loop_guard) = edge.into_parts()?;
+            match loop_guard {
+                Some(_) if source_handle.is_some() || target_handle.is_some() => {
+                    return Err(Error::ExecutionError(
+                        format!("Loop edge {} -> {} cannot have handles", source, target).into(),
+                    ));
+                }
+                Some(guard) => graph_data.add_loop_edge(&source, &target, guard),
+                None => graph_data.add_edge(&source, &target, source_handle, target_handle),
+            }
+        }
+
+        graph_data.start_node = doc.start;
+        graph_data.end_node = doc.end;
+
+        Ok(graph_data)
+    }
+}
+
+impl From<GraphData> for GraphDoc {
+    fn from(data: GraphData) -> Self {
+        let nodes = data
+            .nodes
+            .into_iter()
+            .map(|node| {
+                let mut value = node.data;
+                if matches!(node.node_type, NodeType::Data(DataNode::Input)) {
+                    value = collapse_input(value);
+                }
+
+                let doc = NodeDoc {
+                    kind: node.node_type,
+                    data: (!value.is_null()).then_some(value),
+                    input_processor: node.processors.input,
+                    output_processor: node.processors.output,
+                    input_id: node.input_id,
+                    output_id: node.output_id,
+                };
+                (node.id, doc)
+            })
+            .collect();
+
+        Self {
+            start: data.start_node,
+            end: data.end_node,
+            nodes,
+            edges: data.edges.into_iter().map(EdgeDoc::from).collect(),
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value, json};
+use workflow_error::{Error, Result};
+
+use crate::edge::Edge;
+
+/// 当前 `GraphData` 的 schema 版本
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+/// 单步迁移：将文档从版本 `n` 升级到 `n + 1`
+type Migration = fn(&mut Map<String, Value>) -> Result<()>;
+
+/// 迁移列表，下标即起始版本
+const MIGRATIONS: [Migration; CURRENT_SCHEMA_VERSION as usize] = [v0_to_v1];
+
+/// 将任意旧版本的图文档逐步升级到当前版本
+///
+/// 没有 `schema_version` 字段的文档视为版本 0
+pub fn migrate(mut doc: Value) -> Result<Value> {
+    let object = doc
+        .as_object_mut()
+        .ok_or_else(|| Error::ExecutionError("Graph document must be a JSON object".into()))?;
+
+    let version = match object.get("schema_version") {
+        None => 0,
+        Some(v) => {
+            let version = v.as_u64().ok_or_else(|| {
+                Error::ExecutionError("`schema_version` must be an integer".into())
+            })?;
+            u32::try_from(version).map_err(|_| {
+                Error::ExecutionError(
+                    format!("`schema_version` {} is out of range", version).into(),
+                )
+            })?
+        }
+    };
+
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(Error::UnsupportedSchemaVersion(version));
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migration(object)?;
+        object.insert("schema_version".to_string(), json!(from + 1));
+    }
+
+    Ok(doc)
+}
+
+/// v0 → v1
+///
+/// - `nodes` 由 `{id: node}` 映射改为节点列表
+/// - 边的 `start` / `end` 字段改为 `source` / `target`，缺失的 `id` 按 `Edge::make_id` 补齐
+/// - Input 节点的 `FlowData` 由外部标签格式 `{"Single": {"Text": "A"}}` 改为 `{"type":
+///   "Single", "value": {"type": "Text", "value": "A"}}`
+/// - Branch 节点的 `branches` 由 `{condition: node_id}` 映射改为 `BranchPayload` 列表，
+///   出边补齐 `sourceHandle`（目标节点 ID，默认分支为 `default`）
+fn v0_to_v1(doc: &mut Map<String, Value>) -> Result<()> {
+    // Branch 节点 ID -> 默认分支目标节点
+    let mut branch_defaults: HashMap<String, Option<String>> = HashMap::new();
+
+    if let Some(Value::Object(nodes)) = doc.get("nodes") {
+        let nodes = nodes.values().cloned().collect();
+        doc.insert("nodes".to_string(), Value::Array(nodes));
+    }
+
+    if let Some(Value::Array(nodes)) = doc.get_mut("nodes") {
+        for node in nodes {
+            let kind = node
+                .pointer("/node_type/Data")
+                .or_else(|| node.pointer("/node_type/Control"))
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+            let id = node
+                .get("id")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+            let Some(data) = node.get_mut("data") else {
+                continue;
+            };
+
+            match kind.as_str() {
+                "Input" => {
+                    if let Some(input) = data.get_mut("input") {
+                        *input = retag_flow_data(input.take());
+                    }
+                }
+                "Branch" => {
+                    if let Some(branches) = data.get_mut("branches") {
+                        *branches = branch_map_to_list(branches.take());
+                    }
+                    let default = data.get("default").and_then(Value::as_str);
+                    branch_defaults.insert(id, default.map(str::to_string));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if let Some(Value::Array(edges)) = doc.get_mut("edges") {
+        // 补齐的 ID 与 `Edge::make_id` 一致，并避开已有的 ID
+        let mut taken: Vec<String> = edges
+            .iter()
+            .filter_map(|edge| edge.get("id").and_then(Value::as_str))
+            .map(str::to_string)
+            .collect();
+        for edge in edges.iter_mut().filter_map(Value::as_object_mut) {
+            for (old, new) in [("start", "source"), ("end", "target")] {
+                if !edge.contains_key(new)
+                    && let Some(value) = edge.remove(old)
+                {
+                    edge.insert(new.to_string(), value);
+                }
+            }
+            edge.remove("edge_type");
+
+            let source = edge
+                .get("source")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let target = edge
+                .get("target")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            if let Some(default) = branch_defaults.get(source)
+                && !edge.contains_key("sourceHandle")
+            {
+                let handle = if default.as_deref() == Some(target) {
+                    "default"
+                } else {
+                    target
+                };
+                let handle = Value::String(handle.to_string());
+                edge.insert("sourceHandle".to_string(), handle);
+            }
+
+            if !edge.contains_key("id") {
+                let field = |key: &str| edge.get(key).and_then(Value::as_str);
+                let id = Edge::make_id(
+                    field("source").unwrap_or_default(),
+                    field("target").unwrap_or_default(),
+                    field("sourceHandle"),
+                    field("targetHandle"),
+                    taken.iter().map(String::as_str),
+                );
+                taken.push(id.clone());
+                edge.insert("id".to_string(), Value::String(id));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 外部标签格式的 `FlowData` → 相邻标签格式，已是新格式时原样返回
+fn retag_flow_data(value: Value) -> Value {
+    match value {
+        Value::Object(map) if map.len() == 1 && !map.contains_key("type") => {
+            let (tag, inner) = map.into_iter().next().unwrap();
+            let inner = match tag.as_str() {
+                "Single" => retag_single_data(inner),
+                "Collection" => match inner {
+                    Value::Array(items) => {
+                        Value::Array(items.into_iter().map(retag_single_data).collect())
+                    }
+                    other => other,
+                },
+                _ => inner,
+            };
+            json!({ "type": tag, "value": inner })
+        }
+        other => other,
+    }
+}
+
+fn retag_single_data(value: Value) -> Value {
+    match value {
+        Value::Object(map) if map.len() == 1 && !map.contains_key("type") => {
+            let (tag, inner) = map.into_iter().next().unwrap();
+            json!({ "type": tag, "value": inner })
+        }
+        other => other,
+    }
+}
+
+/// `{condition: node_id}` → `[{id: node_id, condition: "==", value: condition, valueType: "string"}]`
+fn branch_map_to_list(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Array(
+            map.into_iter()
+                .map(|(value, id)| {
+                    json!({
+                        "id": id,
+                        "condition": "==",
+                        "value": value,
+                        "valueType": "string",
+                    })
+                })
+                .collect(),
+        ),
+        other => other,
+    }
+}
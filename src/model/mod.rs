@@ -1,7 +1,9 @@
 pub mod context;
 pub mod data_payload;
 pub mod graph_data;
+pub mod graph_doc;
 pub mod input;
+pub mod migration;
 pub mod node;
 pub mod output;
 
@@ -1,46 +1,46 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum NodeType {
     Data(DataNode),
     Control(ControlNode),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DataNode {
     Input,
     Prompt,
     Identity,
     LLM,
     Http,
+    Filter,
+    Reduce,
+    Expression,
+    Transform,
+    Agent,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ControlNode {
     Branch,
     Parallel,
     Repeat,
     Aggregator,
+    Map,
+    Router,
+    Race,
+    Quorum,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct DataProcessorMapping {
     pub input: Option<String>,
     pub output: Option<String>,
 }
 
-impl Default for DataProcessorMapping {
-    fn default() -> Self {
-        DataProcessorMapping {
-            input: None,
-            output: None,
-        }
-    }
-}
-
 /// 用于序列化和持久化的 Node 数据结构
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Node {
     pub id: String,
     pub node_type: NodeType,
@@ -78,8 +78,10 @@ impl Node {
             NodeType::Data(DataNode::Input),
             serde_json::json!({
                 "input": {
-                    "Single": {
-                        "Text": "A"
+                    "type": "Single",
+                    "value": {
+                        "type": "Text",
+                        "value": "A"
                     }
                 }
             }),
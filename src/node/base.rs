@@ -10,19 +10,16 @@ use crate::{
 };
 
 /// 节点状态表示
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub enum NodeState {
+    #[default]
     Pending,
     Running,
     Completed,
     Failed,
     Cancelled,
-}
-
-impl Default for NodeState {
-    fn default() -> Self {
-        NodeState::Pending
-    }
+    /// 所在分支未被激活
+    Skipped,
 }
 
 #[derive(Debug, Clone)]
@@ -48,17 +45,17 @@ impl Default for NodeBase {
 
 impl NodeBase {
     pub async fn process_input(&self, input: Option<FlowData>) -> Option<FlowData> {
-        if let Some(name) = &self.input_processor_name {
-            if let Some(processor) = PROCESSOR_REGISTRY.get_input(name) {
-                match input {
-                    Some(data) => {
-                        // 如果输入数据存在，使用处理器处理
-                        return processor.process(data).await;
-                    }
-                    None => {
-                        // 如果没有输入数据，直接返回 None
-                        return None;
-                    }
+        if let Some(name) = &self.input_processor_name
+            && let Some(processor) = PROCESSOR_REGISTRY.get_input(name)
+        {
+            match input {
+                Some(data) => {
+                    // 如果输入数据存在，使用处理器处理
+                    return processor.process(data).await;
+                }
+                None => {
+                    // 如果没有输入数据，直接返回 None
+                    return None;
                 }
             }
         }
@@ -66,10 +63,10 @@ impl NodeBase {
     }
 
     pub async fn process_output(&self, output: FlowOutput) -> Option<FlowOutput> {
-        if let Some(name) = &self.output_processor_name {
-            if let Some(processor) = PROCESSOR_REGISTRY.get_output(name) {
-                return processor.process(output).await;
-            }
+        if let Some(name) = &self.output_processor_name
+            && let Some(processor) = PROCESSOR_REGISTRY.get_output(name)
+        {
+            return processor.process(output).await;
         }
         Some(output)
     }
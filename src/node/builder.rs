@@ -4,8 +4,14 @@ use workflow_error::Result;
 
 use super::{
     Executable,
-    control::{AggregatorNode, BranchNode, ParallelNode, RepeatNode},
-    data::{HttpNode, PromptNode, indentity::IdentityNode},
+    control::{
+        AggregatorNode, BranchNode, MapNode, ParallelNode, QuorumNode, RaceNode, RepeatNode,
+        RouterNode,
+    },
+    data::{
+        AgentNode, ExpressionNode, FilterNode, HttpNode, PromptNode, ReduceNode, TransformNode,
+        indentity::IdentityNode,
+    },
 };
 use crate::{
     model::node::{ControlNode, DataNode, Node, NodeType},
@@ -27,12 +33,21 @@ pub fn build_node(node: &Node) -> Result<Arc<dyn Executable>> {
             DataNode::Identity => Box::new(IdentityNode::new(id, data, processors)?),
             DataNode::LLM => Box::new(LLMNode::new(id, data, processors)?),
             DataNode::Http => Box::new(HttpNode::new(id, data, processors)?),
+            DataNode::Filter => Box::new(FilterNode::new(id, data, processors)?),
+            DataNode::Reduce => Box::new(ReduceNode::new(id, data, processors)?),
+            DataNode::Expression => Box::new(ExpressionNode::new(id, data, processors)?),
+            DataNode::Transform => Box::new(TransformNode::new(id, data, processors)?),
+            DataNode::Agent => Box::new(AgentNode::new(id, data, processors)?),
         },
         NodeType::Control(orch_node) => match orch_node {
             ControlNode::Branch => Box::new(BranchNode::new(id, data, processors)?),
             ControlNode::Parallel => Box::new(ParallelNode::new(id, data, processors)?),
             ControlNode::Repeat => Box::new(RepeatNode::new(id, data, processors)?),
             ControlNode::Aggregator => Box::new(AggregatorNode::new(id, data, processors)?),
+            ControlNode::Map => Box::new(MapNode::new(id, data, processors)?),
+            ControlNode::Router => Box::new(RouterNode::new(id, data, processors)?),
+            ControlNode::Race => Box::new(RaceNode::new(id, data, processors)?),
+            ControlNode::Quorum => Box::new(QuorumNode::new(id, data, processors)?),
         },
     };
 
@@ -0,0 +1,398 @@
+use std::{fmt, sync::OnceLock};
+
+use flow_data::FlowData;
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, de};
+use serde_json::Value;
+use workflow_error::{Error, Result};
+
+use crate::expr::{Expression, is_truthy, json_path, to_number, to_text};
+
+/// 条件：单个比较、表达式，或 `all` / `any` / `not` 组合
+///
+/// 兼容原有的 `{condition, value, valueType}` 格式：
+///
+/// ```json
+/// { "condition": "==", "value": "yes", "valueType": "string" }
+/// { "path": "$.user.age", "condition": ">=", "value": 18 }
+/// { "any": [
+///     { "condition": "starts_with", "value": "ok" },
+///     { "not": { "path": "errors", "condition": "is_empty" } }
+/// ] }
+/// { "expr": "input.score >= 60 && len(input.errors) == 0" }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ConditionConfig {
+    /// 全部满足
+    All {
+        all: Vec<ConditionConfig>,
+    },
+    /// 任一满足
+    Any {
+        any: Vec<ConditionConfig>,
+    },
+    /// 取反
+    Not {
+        not: Box<ConditionConfig>,
+    },
+    /// 表达式，以 `input` 绑定输入，结果按真值规则判断
+    Expr(ExprCondition),
+    Compare(Comparison),
+}
+
+/// 表达式条件，首次校验或求值时解析并缓存
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExprCondition {
+    pub expr: String,
+    #[serde(skip)]
+    compiled: Compiled<Expression>,
+}
+
+impl ExprCondition {
+    pub fn new(expr: &str) -> Self {
+        Self {
+            expr: expr.to_string(),
+            compiled: Compiled::default(),
+        }
+    }
+
+    fn expression(&self) -> Result<&Expression> {
+        self.compiled
+            .get_or_try_init(|| Expression::parse(&self.expr))
+    }
+}
+
+/// 单个比较：取输入（或其 `path` 处的值），使用 `condition` 运算符与 `value` 比较
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Comparison {
+    /// JSON 路径，如 `$.user.name`、`items[0]`、`/user/name`，缺省为整个输入
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub condition: Operator,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub value: Value,
+    /// `string` 或 `number`，缺省时按 `value` 的 JSON 类型推断
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_type: Option<String>,
+    /// `regex` 运算符编译后的正则
+    #[serde(skip)]
+    regex: Compiled<Regex>,
+}
+
+/// 比较运算符，未知运算符在反序列化时即报错
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Operator {
+    #[serde(rename = "==")]
+    Eq,
+    #[serde(rename = "!=")]
+    Ne,
+    #[serde(rename = ">")]
+    Gt,
+    #[serde(rename = ">=")]
+    Ge,
+    #[serde(rename = "<")]
+    Lt,
+    #[serde(rename = "<=")]
+    Le,
+    /// 文本包含子串、数组包含元素、对象包含键
+    #[serde(rename = "contains")]
+    Contains,
+    #[serde(rename = "starts_with")]
+    StartsWith,
+    #[serde(rename = "ends_with")]
+    EndsWith,
+    #[serde(rename = "regex")]
+    Regex,
+    /// 值属于 `value` 数组
+    #[serde(rename = "in")]
+    In,
+    /// 路径存在且不为 null
+    #[serde(rename = "exists")]
+    Exists,
+    /// 路径不存在、null、空文本、空数组或空对象
+    #[serde(rename = "is_empty")]
+    IsEmpty,
+}
+
+impl Operator {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operator::Eq => "==",
+            Operator::Ne => "!=",
+            Operator::Gt => ">",
+            Operator::Ge => ">=",
+            Operator::Lt => "<",
+            Operator::Le => "<=",
+            Operator::Contains => "contains",
+            Operator::StartsWith => "starts_with",
+            Operator::EndsWith => "ends_with",
+            Operator::Regex => "regex",
+            Operator::In => "in",
+            Operator::Exists => "exists",
+            Operator::IsEmpty => "is_empty",
+        }
+    }
+
+    fn is_ordering(&self) -> bool {
+        matches!(
+            self,
+            Operator::Gt | Operator::Ge | Operator::Lt | Operator::Le
+        )
+    }
+
+    fn needs_value(&self) -> bool {
+        !matches!(self, Operator::Exists | Operator::IsEmpty)
+    }
+}
+
+impl<'de> Deserialize<'de> for ConditionConfig {
+    /// 按 `all` / `any` / `not` / `expr` 键选择变体，其余视为比较，
+    /// 使运算符或字段拼写错误报告具体原因，而不是笼统的“不匹配任何变体”
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let mut value = Value::deserialize(deserializer)?;
+        let mut take = |key: &str| value.get_mut(key).map(Value::take);
+
+        let parsed = if let Some(all) = take("all") {
+            serde_json::from_value(all).map(|all| ConditionConfig::All { all })
+        } else if let Some(any) = take("any") {
+            serde_json::from_value(any).map(|any| ConditionConfig::Any { any })
+        } else if let Some(not) = take("not") {
+            serde_json::from_value(not).map(|not| ConditionConfig::Not { not })
+        } else if value.get("expr").is_some() {
+            serde_json::from_value(value).map(ConditionConfig::Expr)
+        } else {
+            serde_json::from_value(value).map(ConditionConfig::Compare)
+        };
+        parsed.map_err(de::Error::custom)
+    }
+}
+
+impl ConditionConfig {
+    /// 对整个输入的单个比较
+    pub fn compare(condition: Operator, value: impl Into<Value>) -> Self {
+        ConditionConfig::Compare(Comparison {
+            path: None,
+            condition,
+            value: value.into(),
+            value_type: None,
+            regex: Compiled::default(),
+        })
+    }
+
+    /// 对输入中 `path` 处的值的单个比较
+    pub fn compare_path(path: &str, condition: Operator, value: impl Into<Value>) -> Self {
+        ConditionConfig::Compare(Comparison {
+            path: Some(path.to_string()),
+            condition,
+            value: value.into(),
+            value_type: None,
+            regex: Compiled::default(),
+        })
+    }
+
+    /// 构建期校验：路径语法、正则、`in` 的数组值、数值比较的数字值等
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            ConditionConfig::All { all: items } | ConditionConfig::Any { any: items } => {
+                if items.is_empty() {
+                    return Err(invalid(self, "combination must not be empty"));
+                }
+                items.iter().try_for_each(ConditionConfig::validate)
+            }
+            ConditionConfig::Not { not } => not.validate(),
+            ConditionConfig::Expr(condition) => condition.expression().map(|_| ()),
+            ConditionConfig::Compare(comparison) => comparison.validate(),
+        }
+    }
+
+    /// 判断节点输出是否满足条件
+    pub fn matches_data(&self, data: &FlowData) -> bool {
+        self.matches(&data.to_json_value())
+    }
+
+    /// 判断 JSON 值是否满足条件；无法解析的值视为不满足，不影响其余条件
+    pub fn matches(&self, input: &Value) -> bool {
+        match self {
+            ConditionConfig::All { all } => all.iter().all(|c| c.matches(input)),
+            ConditionConfig::Any { any } => any.iter().any(|c| c.matches(input)),
+            ConditionConfig::Not { not } => !not.matches(input),
+            // 求值出错（如类型不匹配）视为不满足
+            ConditionConfig::Expr(condition) => condition
+                .expression()
+                .and_then(|e| e.eval_input(input))
+                .is_ok_and(|value| is_truthy(&value)),
+            ConditionConfig::Compare(comparison) => comparison.matches(input),
+        }
+    }
+}
+
+impl Comparison {
+    fn validate(&self) -> Result<()> {
+        let fail = |reason: &str| Err(invalid(&ConditionConfig::Compare(self.clone()), reason));
+
+        if let Some(path) = &self.path {
+            json_path::parse(path)?;
+        }
+        match self.value_type.as_deref() {
+            None | Some("string") | Some("number") => {}
+            Some(_) => return fail("`valueType` must be `string` or `number`"),
+        }
+        if self.condition.needs_value() && self.value.is_null() {
+            return fail("`value` is required");
+        }
+
+        match self.condition {
+            Operator::Regex => {
+                if let Err(e) = self.regex() {
+                    return fail(&format!("invalid regex: {}", e));
+                }
+            }
+            Operator::In if !self.value.is_array() => return fail("`in` requires an array value"),
+            op if op.is_ordering() && to_number(&self.value).is_none() => {
+                return fail("ordering comparison requires a numeric value");
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn regex(&self) -> std::result::Result<&Regex, regex::Error> {
+        self.regex
+            .get_or_try_init(|| Regex::new(&to_text(&self.value)))
+    }
+
+    fn matches(&self, input: &Value) -> bool {
+        let selected;
+        let actual = match &self.path {
+            Some(path) => {
+                selected = json_path::select(input, path);
+                selected.as_ref()
+            }
+            None => Some(input),
+        };
+
+        match self.condition {
+            Operator::Exists => actual.is_some_and(|v| !v.is_null()),
+            Operator::IsEmpty => actual.is_none_or(is_empty),
+            op => actual.is_some_and(|actual| self.compare(op, actual)),
+        }
+    }
+
+    fn compare(&self, op: Operator, actual: &Value) -> bool {
+        let expected = &self.value;
+        match op {
+            Operator::Eq => self.equals(actual, expected),
+            Operator::Ne => !self.equals(actual, expected),
+            Operator::Gt | Operator::Ge | Operator::Lt | Operator::Le => {
+                let (Some(a), Some(b)) = (to_number(actual), to_number(expected)) else {
+                    return false;
+                };
+                match op {
+                    Operator::Gt => a > b,
+                    Operator::Ge => a >= b,
+                    Operator::Lt => a < b,
+                    _ => a <= b,
+                }
+            }
+            Operator::Contains => match actual {
+                Value::Array(items) => items.iter().any(|item| self.equals(item, expected)),
+                Value::Object(map) => map.contains_key(&to_text(expected)),
+                _ => to_text(actual).contains(&to_text(expected)),
+            },
+            Operator::StartsWith => to_text(actual).starts_with(&to_text(expected)),
+            Operator::EndsWith => to_text(actual).ends_with(&to_text(expected)),
+            Operator::Regex => self
+                .regex()
+                .is_ok_and(|regex| regex.is_match(&to_text(actual))),
+            Operator::In => expected
+                .as_array()
+                .is_some_and(|items| items.iter().any(|item| self.equals(actual, item))),
+            Operator::Exists | Operator::IsEmpty => unreachable!("handled in `matches`"),
+        }
+    }
+
+    /// 按 `valueType` 比较；未指定时数字按数值、字符串按文本、其余按 JSON 相等
+    fn equals(&self, actual: &Value, expected: &Value) -> bool {
+        let numeric = match self.value_type.as_deref() {
+            Some(value_type) => value_type == "number",
+            None => expected.is_number(),
+        };
+        if numeric {
+            return matches!((to_number(actual), to_number(expected)), (Some(a), Some(b)) if a == b);
+        }
+        if self.value_type.is_some() || expected.is_string() {
+            return to_text(actual) == to_text(expected);
+        }
+        actual == expected
+    }
+}
+
+impl fmt::Display for ConditionConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |f: &mut fmt::Formatter<'_>, items: &[ConditionConfig], sep: &str| {
+            let parts: Vec<String> = items.iter().map(ToString::to_string).collect();
+            write!(f, "({})", parts.join(sep))
+        };
+        match self {
+            ConditionConfig::All { all } => join(f, all, " and "),
+            ConditionConfig::Any { any } => join(f, any, " or "),
+            ConditionConfig::Not { not } => write!(f, "not {}", not),
+            ConditionConfig::Expr(condition) => write!(f, "{}", condition.expr),
+            ConditionConfig::Compare(comparison) => {
+                if let Some(path) = &comparison.path {
+                    write!(f, "{} ", path)?;
+                }
+                write!(f, "{}", comparison.condition.as_str())?;
+                if !comparison.value.is_null() {
+                    write!(f, " {}", comparison.value)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// 构建期编译结果（表达式、正则）的缓存，不参与序列化与比较
+#[derive(Debug, Clone)]
+struct Compiled<T>(OnceLock<T>);
+
+impl<T> Compiled<T> {
+    fn get_or_try_init<E>(
+        &self,
+        init: impl FnOnce() -> std::result::Result<T, E>,
+    ) -> std::result::Result<&T, E> {
+        if let Some(value) = self.0.get() {
+            return Ok(value);
+        }
+        let value = init()?;
+        Ok(self.0.get_or_init(|| value))
+    }
+}
+
+impl<T> Default for Compiled<T> {
+    fn default() -> Self {
+        Self(OnceLock::new())
+    }
+}
+
+impl<T> PartialEq for Compiled<T> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+fn invalid(condition: &ConditionConfig, reason: &str) -> Error {
+    Error::ExecutionError(format!("Invalid condition `{}`: {}", condition, reason).into())
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(text) => text.is_empty(),
+        Value::Array(items) => items.is_empty(),
+        Value::Object(map) => map.is_empty(),
+        _ => false,
+    }
+}
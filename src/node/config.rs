@@ -1,9 +1,12 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use flow_data::FlowData;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+pub use super::condition::ConditionConfig;
+use crate::model::graph_data::GraphData;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InputConfig {
     pub input: FlowData,
@@ -14,6 +17,184 @@ pub struct PromptConfig {
     pub template: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LLMConfig {
+    /// `MODEL_PROFILES` 中的模型配置档案名，节点中的模型设置覆盖档案中的同名设置
+    #[serde(rename = "model", default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    #[serde(flatten)]
+    pub settings: ModelSettings,
+    /// 用户消息模板，以 `input` 绑定节点输入渲染；不含 `{{ }}` 时放在输入文本之前
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// 期望输出的 JSON Schema，设置后模型回复按 JSON 解析并校验，输出为 JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<Value>,
+    /// 结构化输出解析或校验失败后的重试次数，缺省为 2
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<usize>,
+    /// 为 true 时以流的形式输出模型回复，不能与 `output_schema` 同时使用
+    #[serde(default)]
+    pub stream: bool,
+    /// 会话记忆：按运行时提供的会话 ID 读取历史消息，并追加本轮对话
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemoryConfig>,
+    /// 集合输入的处理方式
+    #[serde(default)]
+    pub collection: CollectionMode,
+}
+
+/// 模型接口与采样参数，既可写在 LLM 节点中，也可注册为 `MODEL_PROFILES` 中的配置档案
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelSettings {
+    /// 缺省为 `openai`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<Provider>,
+    /// 缺省为所选 provider 的官方地址
+    #[serde(rename = "apiHost", default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(rename = "modelName", default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// 停止序列
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+}
+
+impl ModelSettings {
+    /// 以 `overrides` 中已设置的项覆盖当前设置
+    pub fn merge(&self, overrides: &ModelSettings) -> ModelSettings {
+        ModelSettings {
+            provider: overrides.provider.or(self.provider),
+            base_url: overrides.base_url.clone().or_else(|| self.base_url.clone()),
+            api_key: overrides.api_key.clone().or_else(|| self.api_key.clone()),
+            model: overrides.model.clone().or_else(|| self.model.clone()),
+            temperature: overrides.temperature.or(self.temperature),
+            max_tokens: overrides.max_tokens.or(self.max_tokens),
+            top_p: overrides.top_p.or(self.top_p),
+            stop: overrides.stop.clone().or_else(|| self.stop.clone()),
+        }
+    }
+}
+
+/// 模型接口的提供方
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    /// OpenAI 兼容的 `chat/completions` 接口，支持图片与视频输入
+    #[default]
+    OpenAi,
+    /// Ollama 的 `chat` 接口
+    Ollama,
+}
+
+/// LLM 节点如何处理集合输入
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectionMode {
+    /// 文本按换行拼接，图片与视频附在同一条用户消息中
+    #[default]
+    Join,
+    /// 每个元素作为一条单独的用户消息
+    Messages,
+}
+
+/// LLM 节点的会话记忆配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryConfig {
+    /// `MEMORY_REGISTRY` 中的存储名，缺省为进程内存储 `default`
+    #[serde(default = "default_memory_store")]
+    pub store: String,
+    #[serde(default)]
+    pub strategy: MemoryStrategy,
+}
+
+fn default_memory_store() -> String {
+    crate::memory::DEFAULT_MEMORY_STORE.to_string()
+}
+
+/// 历史消息的保留策略
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MemoryStrategy {
+    /// 保留全部历史
+    #[default]
+    Full,
+    /// 只保留最近的 `max_messages` 条消息
+    Window { max_messages: usize },
+    /// 历史超过 `max_messages` 条时，请模型将较早的消息总结为一条，只保留最近的 `keep_recent` 条原文
+    Summarize {
+        max_messages: usize,
+        keep_recent: usize,
+    },
+}
+
+/// Agent 节点配置：在 LLM 配置的基础上循环调用工具，直到模型给出最终回答或达到 `max_iterations`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentConfig {
+    #[serde(flatten)]
+    pub llm: LLMConfig,
+    #[serde(default)]
+    pub tools: Vec<AgentTool>,
+    /// 最多请求模型的次数
+    #[serde(default = "default_max_iterations")]
+    pub max_iterations: usize,
+}
+
+fn default_max_iterations() -> usize {
+    8
+}
+
+/// Agent 可调用的工具
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentTool {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// 参数的 JSON Schema，原样展示给模型
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<Value>,
+    #[serde(flatten)]
+    pub source: ToolSource,
+}
+
+/// 工具的实现方式，工具参数为模型给出的 JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ToolSource {
+    /// 以参数为输入执行图中的节点
+    Node { node_id: String },
+    /// `TOOL_REGISTRY` 中注册的函数
+    Function { function: String },
+    /// 以参数为请求数据调用 HTTP 接口
+    Http {
+        url: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        method: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        headers: Option<HashMap<String, String>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        timeout_seconds: Option<u64>,
+    },
+    /// `MCP_CLIENT_REGISTRY` 中 MCP 服务提供的工具，`call_name` 缺省为工具名
+    Mcp {
+        server_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        call_name: Option<String>,
+    },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelConfig {
     pub model_type: String,
@@ -24,9 +205,8 @@ pub struct ModelConfig {
 #[serde(rename_all = "camelCase")]
 pub struct BranchPayload {
     pub id: String,
-    pub condition: String,
-    pub value: String,
-    pub value_type: String,
+    #[serde(flatten)]
+    pub when: ConditionConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,30 +215,239 @@ pub struct BranchConfig {
     pub default: Option<String>,
 }
 
+/// Router 节点配置：分支格式与 Branch 相同，可同时激活多个匹配的分支
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouterConfig {
+    pub branches: Vec<BranchPayload>,
+    #[serde(default)]
+    pub mode: RouterMode,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouterMode {
+    /// 激活全部匹配的分支
+    #[default]
+    All,
+    /// 只激活第一个匹配的分支
+    First,
+}
+
 impl BranchConfig {
     pub fn to_hashmap(&self) -> HashMap<String, String> {
         let mut map = HashMap::new();
         for branch in &self.branches {
-            map.insert(branch.condition.clone(), branch.id.clone());
+            map.insert(branch.when.to_string(), branch.id.clone());
         }
         map
     }
 }
 
+/// Parallel 节点配置：`branches` 为 分支名 → 节点 ID，按分支名排序执行与输出
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParallelConfig {
-    pub branches: HashMap<String, String>,
+    pub branches: BTreeMap<String, String>,
+    /// 未设置时按分支名作为句柄分别输出到下游；设置后合并为单个数据输出
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<BranchOutput>,
+}
+
+/// 多个分支结果合并为单个输出的形式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BranchOutput {
+    /// 按分支名顺序合并为集合
+    #[default]
+    Collection,
+    /// JSON 对象：分支名 → 输出
+    Object,
+    /// 集合，每个元素为 `{"branch": 分支名, "output": 输出}`
+    Keyed,
 }
 
+/// Race 节点配置：并发执行各分支，取第一个成功的结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AggregatorConfig {
-    pub branches: HashMap<String, String>,
+pub struct RaceConfig {
+    pub branches: BTreeMap<String, String>,
 }
 
+/// Quorum 节点配置：并发执行各分支，`required` 个分支成功后合并结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuorumConfig {
+    pub branches: BTreeMap<String, String>,
+    pub required: usize,
+    #[serde(default)]
+    pub combine: QuorumCombine,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuorumCombine {
+    /// 取获得过半票数的结果（文本去除首尾空白后比较）：至少 `required` 个分支成功后开始计票，
+    /// 无过半结果（如平票）时继续等待其余分支，全部结束仍无过半结果则失败
+    #[default]
+    Majority,
+    /// 按分支名顺序收集为集合
+    Collect,
+}
+
+/// Aggregator 节点配置：收集本次运行中各分支节点已产生的输出，不会重新执行分支
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AggregatorConfig {
+    pub branches: BTreeMap<String, String>,
+    #[serde(default)]
+    pub strategy: AggregateStrategy,
+    /// `collect` 策略的输出形式
+    #[serde(default)]
+    pub output: BranchOutput,
+    /// `concat` 策略的分隔符
+    #[serde(default)]
+    pub separator: String,
+    #[serde(default)]
+    pub wait: AggregateWait,
+}
+
+/// Aggregator 的合并策略，各分支按分支名顺序参与合并
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregateStrategy {
+    /// 按 `output` 收集为集合、对象或带分支名的条目
+    #[default]
+    Collect,
+    /// 以 `separator` 拼接文本
+    Concat,
+    /// 取第一个非空输出
+    FirstNonEmpty,
+    /// 浅合并 JSON 对象，后出现的键覆盖先出现的
+    MergeJson,
+}
+
+/// Aggregator 的触发时机
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregateWait {
+    /// 等待所有前驱完成（或被跳过）
+    #[default]
+    All,
+    /// 任一前驱完成即执行，本次运行中只执行一次
+    Any,
+}
+
+/// Repeat 节点配置，`child_id` 与 `subgraph` 二选一
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RepeatConfig {
-    pub child_id: String,
+    /// 每次迭代执行的节点
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub child_id: Option<String>,
+    /// 每次迭代执行的子图：输入交给起始节点，输出取自结束节点
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subgraph: Option<GraphData>,
     pub max_iterations: usize,
+    /// 退出条件：某次迭代的输出满足时提前停止
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<ConditionConfig>,
+    /// 为 true 时输出每次迭代结果组成的 Collection（每轮一个元素，Collection 结果转为 JSON 数组），
+    /// 否则只输出最后一次结果
+    #[serde(default)]
+    pub collect: bool,
+}
+
+/// Map 节点配置：对输入集合的每个元素执行 `child_id` 或 `subgraph`（二选一）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MapConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub child_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subgraph: Option<GraphData>,
+    /// 最大并发数，缺省为 1（顺序执行）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<usize>,
+    /// 元素执行失败时的处理方式
+    #[serde(default)]
+    pub on_error: MapErrorMode,
+}
+
+/// Map 节点的错误处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MapErrorMode {
+    /// 任一元素失败即取消其余任务并返回错误
+    #[default]
+    FailFast,
+    /// 继续执行，失败元素在结果中记为 `{"error": "..."}`
+    Collect,
+}
+
+/// Filter 节点配置：保留集合中满足条件的元素，条件格式与 Branch 分支相同
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterConfig {
+    #[serde(flatten)]
+    pub when: ConditionConfig,
+}
+
+/// Reduce 节点配置：将集合归约为单个值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum ReduceConfig {
+    /// 以分隔符拼接文本
+    Concat {
+        #[serde(default)]
+        separator: String,
+    },
+    /// 数字求和
+    Sum,
+    /// 浅合并 JSON 对象，后出现的键覆盖先出现的
+    MergeJson,
+    /// 使用 `REDUCER_REGISTRY` 中注册的函数
+    Custom { function: String },
+}
+
+/// Expression 节点配置：以 `input` 绑定节点输入对表达式求值，语法见 `crate::expr`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ExpressionConfig {
+    pub expression: String,
+    /// 额外的常量变量，不可覆盖 `input`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub variables: HashMap<String, Value>,
+}
+
+/// Transform 节点配置：从 JSON 输入中选取或重组数据
+///
+/// ```json
+/// { "select": "$.data.items[0].name", "output": "text" }
+/// { "select": "$.items[*].id", "default": [] }
+/// { "shape": { "name": "$.user.name", "ids": ["$.items[*].id"] } }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransformConfig {
+    #[serde(flatten)]
+    pub mapping: TransformMapping,
+    /// 路径不存在时使用的值；未设置时 `select` 报错、`shape` 中取 null
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    #[serde(default)]
+    pub output: TransformOutput,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TransformMapping {
+    /// 取单个路径处的值
+    Select { select: String },
+    /// 按模板构建新值：字符串叶子为 JSON 路径，对象与数组逐层构建，其余字面量原样保留
+    Shape { shape: Value },
+}
+
+/// Transform 结果的输出类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransformOutput {
+    /// 字符串为 Text，数字为 Number，其余为 Json
+    #[default]
+    Auto,
+    Text,
+    Number,
+    Json,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1,29 +1,49 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::BTreeMap, sync::Arc};
 
-use flow_data::{FlowData, output::FlowOutput};
+use flow_data::{FlowData, SingleData, output::FlowOutput};
 use serde_json::Value;
 use workflow_error::{Error, Result};
 use workflow_macro::impl_executable;
 
 use crate::{
     model::{context::Context, node::DataProcessorMapping},
-    node::{Executable, NodeBase, config::AggregatorConfig},
+    node::{
+        Executable, NodeBase,
+        config::{AggregateStrategy, AggregatorConfig, BranchOutput},
+        control::parallel::combine_branches,
+        data::reduce::merge_json,
+    },
 };
 
+/// AggregatorNode：按分支名顺序收集各分支节点在本次运行中的输出并按 `strategy` 合并
+///
+/// 未执行或被跳过的分支不参与合并；分支节点不会被重新执行。
+/// 不读取入边数据，因此可直接连接在 Parallel 等控制节点之后
 #[derive(Debug, Clone)]
 pub struct AggregatorNode {
     pub base: NodeBase,
-    pub branches: HashMap<String, String>, // key: 名称, value: 节点ID
+    pub branches: BTreeMap<String, String>, // key: 名称, value: 节点ID
+    pub strategy: AggregateStrategy,
+    pub output: BranchOutput,
+    pub separator: String,
 }
 
 impl AggregatorNode {
     pub fn new(id: &str, data: Value, processor: &DataProcessorMapping) -> Result<Self> {
         let config: AggregatorConfig = serde_json::from_value(data)
             .map_err(|_| Error::ExecutionError("Invalid data format for AggregatorNode".into()))?;
+        if config.branches.is_empty() {
+            return Err(Error::ExecutionError(
+                "`branches` of AggregatorNode must not be empty".into(),
+            ));
+        }
 
         Ok(Self {
             base: NodeBase::new(id, processor),
             branches: config.branches,
+            strategy: config.strategy,
+            output: config.output,
+            separator: config.separator,
         })
     }
 }
@@ -32,21 +52,60 @@ impl AggregatorNode {
 impl Executable for AggregatorNode {
     async fn core_execute(
         &self,
-        input: Option<FlowData>,
+        _input: Option<FlowData>,
         context: Arc<Context>,
     ) -> Result<FlowOutput> {
-        let mut aggregated = FlowData::new_collection();
+        let results: Vec<(String, FlowData)> = self
+            .branches
+            .iter()
+            .filter_map(|(key, node_id)| Some((key.clone(), context.get_output(node_id)?)))
+            .collect();
+        if results.is_empty() {
+            return Err(Error::ExecutionError(
+                format!(
+                    "None of the branches ({}) produced an output",
+                    self.branches.keys().cloned().collect::<Vec<_>>().join(", ")
+                )
+                .into(),
+            ));
+        }
 
-        for (_key, node_id) in &self.branches {
-            let node = context
-                .get_node(node_id)
-                .ok_or(Error::NodeNotFound(node_id.clone().into()))?
-                .clone();
+        let merged = || {
+            results
+                .iter()
+                .fold(FlowData::new_collection(), |acc, (_, data)| {
+                    acc.merge(data.clone())
+                })
+        };
+        let output = match self.strategy {
+            AggregateStrategy::Collect => combine_branches(results, self.output),
+            AggregateStrategy::Concat => {
+                FlowData::from(merged().as_text_list()?.join(&self.separator))
+            }
+            AggregateStrategy::FirstNonEmpty => results
+                .into_iter()
+                .map(|(_, data)| data)
+                .find(|data| !is_empty(data))
+                .unwrap_or_else(FlowData::new_collection),
+            AggregateStrategy::MergeJson => merge_json(&merged())?,
+        };
 
-            let output = node.execute(input.clone(), context.clone()).await?;
-            aggregated.merge_mut(output.into_data()?);
-        }
+        Ok(output.into())
+    }
+}
 
-        Ok(aggregated.into())
+/// 空白文本、null、空 JSON 数组/对象/字符串以及空集合视为空
+fn is_empty(data: &FlowData) -> bool {
+    match data {
+        FlowData::Collection(items) => items.is_empty(),
+        FlowData::Single(SingleData::Text(text)) => text.trim().is_empty(),
+        FlowData::Single(SingleData::Json(value)) => match value {
+            Value::Null => true,
+            Value::String(text) => text.trim().is_empty(),
+            Value::Array(items) => items.is_empty(),
+            Value::Object(object) => object.is_empty(),
+            _ => false,
+        },
+        FlowData::Single(_) => false,
     }
 }
@@ -22,8 +22,12 @@ pub struct BranchNode {
 
 impl BranchNode {
     pub fn new(id: &str, data: Value, processor: &DataProcessorMapping) -> Result<Self> {
-        let config: BranchConfig = serde_json::from_value(data)
-            .map_err(|_| Error::ExecutionError("Invalid data format for BranchNode".into()))?;
+        let config: BranchConfig = serde_json::from_value(data).map_err(|e| {
+            Error::ExecutionError(format!("Invalid data format for BranchNode: {}", e).into())
+        })?;
+        for branch in &config.branches {
+            branch.when.validate()?;
+        }
 
         Ok(Self {
             base: NodeBase::new(id, processor),
@@ -43,8 +47,7 @@ impl Executable for BranchNode {
         match input {
             None => Err(Error::ExecutionError("No input data provided".into())),
             Some(data) => {
-                let input_str = data.as_text()?;
-                let node_id = match_branch(input_str, &self.branches);
+                let node_id = match_branch(&data.to_json_value(), &self.branches);
                 let next_node_id = match node_id {
                     None => "default",
                     Some(id) => id,
@@ -58,38 +61,9 @@ impl Executable for BranchNode {
     }
 }
 
-fn match_branch<'a>(input: &str, branches: &'a [BranchPayload]) -> Option<&'a str> {
-    // println!("Matching input: {}", input);
-    // println!("Against branches: {:?}", branches);
-
-    for branch in branches {
-        let matched = match branch.value_type.as_str() {
-            "string" => match branch.condition.as_str() {
-                "==" => input == branch.value,
-                "!=" => input != branch.value,
-                "contains" => input.contains(&branch.value),
-                _ => false,
-            },
-            "number" => {
-                let input_num = input.parse::<f64>().ok()?;
-                let target = branch.value.parse::<f64>().ok()?;
-                match branch.condition.as_str() {
-                    "==" => input_num == target,
-                    "!=" => input_num != target,
-                    ">" => input_num > target,
-                    ">=" => input_num >= target,
-                    "<" => input_num < target,
-                    "<=" => input_num <= target,
-                    _ => false,
-                }
-            }
-            _ => false,
-        };
-
-        if matched {
-            return Some(branch.id.as_str());
-        }
-    }
-
-    None
+fn match_branch<'a>(input: &Value, branches: &'a [BranchPayload]) -> Option<&'a str> {
+    branches
+        .iter()
+        .find(|branch| branch.when.matches(input))
+        .map(|branch| branch.id.as_str())
 }
@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use flow_data::{FlowData, SingleData, output::FlowOutput};
+use serde_json::{Value, json};
+use tokio::task::JoinSet;
+use workflow_error::{Error, Result};
+use workflow_macro::impl_executable;
+
+use crate::{
+    model::{context::Context, node::DataProcessorMapping},
+    node::{
+        Executable, NodeBase,
+        config::{MapConfig, MapErrorMode},
+        control::subgraph::{ChildBody, into_single},
+    },
+};
+
+/// MapNode：对集合中的每个元素执行子节点或子图，按原顺序输出结果集合
+#[derive(Debug, Clone)]
+pub struct MapNode {
+    pub base: NodeBase,
+    pub body: Arc<ChildBody>,
+    pub concurrency: usize,
+    pub on_error: MapErrorMode,
+}
+
+impl MapNode {
+    pub fn new(id: &str, data: Value, processor: &DataProcessorMapping) -> Result<Self> {
+        let config: MapConfig = serde_json::from_value(data)
+            .map_err(|_| Error::ExecutionError("Invalid data format for MapNode".into()))?;
+
+        let concurrency = config.concurrency.unwrap_or(1);
+        if concurrency == 0 {
+            return Err(Error::ExecutionError(
+                "`concurrency` of MapNode must be at least 1".into(),
+            ));
+        }
+
+        Ok(Self {
+            base: NodeBase::new(id, processor),
+            body: Arc::new(ChildBody::from_config(config.child_id, config.subgraph)?),
+            concurrency,
+            on_error: config.on_error,
+        })
+    }
+}
+
+#[impl_executable]
+impl Executable for MapNode {
+    async fn core_execute(
+        &self,
+        input: Option<FlowData>,
+        context: Arc<Context>,
+    ) -> Result<FlowOutput> {
+        let input = input.ok_or_else(|| Error::ExecutionError("No input data provided".into()))?;
+        let items = split_items(input)?;
+
+        let mut results: Vec<Option<SingleData>> = vec![None; items.len()];
+        let mut pending = items.into_iter().enumerate();
+        let mut set = JoinSet::new();
+
+        loop {
+            // 补足并发任务
+            while set.len() < self.concurrency {
+                let Some((index, item)) = pending.next() else {
+                    break;
+                };
+                let body = self.body.clone();
+                let context = context.clone();
+                set.spawn(async move { (index, body.run(item, &context).await) });
+            }
+
+            let Some(joined) = set.join_next().await else {
+                break;
+            };
+            let (index, result) =
+                joined.map_err(|e| Error::ExecutionError(e.to_string().into()))?;
+
+            let item = match (result, self.on_error) {
+                (Ok(data), _) => into_single(data),
+                // 提前返回时 JoinSet 被丢弃，其余任务随之取消
+                (Err(e), MapErrorMode::FailFast) => {
+                    return Err(Error::ExecutionError(
+                        format!("Map item {} failed: {}", index, e).into(),
+                    ));
+                }
+                (Err(e), MapErrorMode::Collect) => {
+                    SingleData::Json(json!({ "error": e.to_string() }))
+                }
+            };
+            results[index] = Some(item);
+        }
+
+        let results = results.into_iter().flatten().collect();
+        Ok(FlowData::Collection(results).into())
+    }
+}
+
+/// 将输入拆分为元素：Collection 按元素拆分，JSON 数组按数组元素拆分
+fn split_items(input: FlowData) -> Result<Vec<FlowData>> {
+    match input {
+        FlowData::Collection(items) => Ok(items.into_iter().map(FlowData::Single).collect()),
+        FlowData::Single(SingleData::Json(Value::Array(values))) => {
+            Ok(values.into_iter().map(FlowData::from_json_value).collect())
+        }
+        FlowData::Single(_) => Err(Error::FlowTypeMismatch),
+    }
+}
@@ -1,11 +1,19 @@
 pub mod aggregator;
 pub mod branch;
+pub mod map;
 pub mod parallel;
+pub mod quorum;
+pub mod race;
 pub mod repeat;
+pub mod router;
 pub mod subgraph;
 
 pub use aggregator::AggregatorNode;
 pub use branch::BranchNode;
+pub use map::MapNode;
 pub use parallel::ParallelNode;
+pub use quorum::QuorumNode;
+pub use race::RaceNode;
 pub use repeat::RepeatNode;
+pub use router::RouterNode;
 pub use subgraph::SubGraphNode;
@@ -1,23 +1,30 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::BTreeMap, sync::Arc};
 
 use flow_data::{
-    FlowData,
+    FlowData, SingleData,
     output::{ControlFlow, FlowOutput},
 };
-use serde_json::Value;
+use serde_json::{Map, Value, json};
 use tokio::task::JoinSet;
 use workflow_error::{Error, Result};
 use workflow_macro::impl_executable;
 
 use crate::{
     model::{context::Context, node::DataProcessorMapping},
-    node::{Executable, NodeBase, config::ParallelConfig},
+    node::{
+        Executable, NodeBase,
+        config::{BranchOutput, ParallelConfig},
+    },
 };
 
+/// ParallelNode：以相同输入并发执行各分支节点
+///
+/// 未设置 `output` 时按分支名作为句柄分别输出到下游，否则按 `output` 合并为单个数据
 #[derive(Debug, Clone)]
 pub struct ParallelNode {
     pub base: NodeBase,
-    pub branches: HashMap<String, String>, // key: 名称, value: 节点ID
+    pub branches: BTreeMap<String, String>, // key: 名称, value: 节点ID
+    pub output: Option<BranchOutput>,
 }
 
 impl ParallelNode {
@@ -28,6 +35,7 @@ impl ParallelNode {
         Ok(Self {
             base: NodeBase::new(id, processor),
             branches: config.branches,
+            output: config.output,
         })
     }
 }
@@ -39,54 +47,104 @@ impl Executable for ParallelNode {
         input: Option<FlowData>,
         context: Arc<Context>,
     ) -> Result<FlowOutput> {
-        let mut set = JoinSet::new();
+        let mut set = spawn_branches(&self.branches, &input, &context)?;
 
-        for (key, node_id) in &self.branches {
-            let node = context
-                .get_node(node_id)
-                .ok_or(Error::NodeNotFound(node_id.clone().into()))?
-                .clone();
+        let mut flows = Vec::new();
+        while let Some(res) = set.join_next().await {
+            flows.push(res??);
+        }
+        // 完成顺序不确定，按分支名排序
+        flows.sort_by(|(a, _), (b, _)| a.cmp(b));
 
-            let input_clone = input.clone();
-            let context_clone = context.clone();
-            let key = key.clone();
-            let node_id = node_id.clone();
+        match self.output {
+            None => Ok(flows
+                .into_iter()
+                .map(|(_, flow)| flow)
+                .collect::<Vec<_>>()
+                .into()),
+            Some(mode) => {
+                let results = flows
+                    .into_iter()
+                    .map(|(key, flow)| (key, flow.data))
+                    .collect();
+                Ok(combine_branches(results, mode).into())
+            }
+        }
+    }
+}
 
-            // 使用独立的 spawn_task 方法启动任务
-            set.spawn(spawn_task(node_id, key, node, input_clone, context_clone));
+/// 按 `mode` 合并 `(分支名, 输出)` 列表，保持列表顺序
+pub(crate) fn combine_branches(results: Vec<(String, FlowData)>, mode: BranchOutput) -> FlowData {
+    match mode {
+        BranchOutput::Collection => results
+            .into_iter()
+            .fold(FlowData::new_collection(), |acc, (_, data)| acc.merge(data)),
+        BranchOutput::Object => {
+            let object: Map<String, Value> = results
+                .into_iter()
+                .map(|(key, data)| (key, data.to_json_value()))
+                .collect();
+            FlowData::Single(SingleData::Json(Value::Object(object)))
         }
+        BranchOutput::Keyed => FlowData::Collection(
+            results
+                .into_iter()
+                .map(|(key, data)| {
+                    SingleData::Json(json!({ "branch": key, "output": data.to_json_value() }))
+                })
+                .collect(),
+        ),
+    }
+}
 
-        let mut vec = Vec::new();
+/// 以相同输入并发启动各分支节点，任务结果为 `(分支名, ControlFlow)`，ControlFlow 以分支名为句柄
+///
+/// 丢弃返回的 JoinSet 会取消尚未完成的分支
+pub(crate) fn spawn_branches<'a>(
+    branches: impl IntoIterator<Item = (&'a String, &'a String)>,
+    input: &Option<FlowData>,
+    context: &Arc<Context>,
+) -> Result<JoinSet<Result<(String, ControlFlow)>>> {
+    let mut set = JoinSet::new();
 
-        // let mut outputs = OutputData::default_parallel();
-        while let Some(res) = set.join_next().await {
-            if let Ok((_key, output)) = res? {
-                vec.push(output);
-            } else {
-                // 处理错误
-                return Err(Error::ExecutionError("Task execution failed".into()));
-            }
-        }
+    for (key, node_id) in branches {
+        let node = context
+            .get_node(node_id)
+            .ok_or(Error::NodeNotFound(node_id.clone().into()))?
+            .clone();
 
-        Ok(vec.into())
+        // 使用独立的 spawn_task 方法启动任务
+        set.spawn(spawn_task(
+            node_id.clone(),
+            key.clone(),
+            node,
+            input.clone(),
+            context.clone(),
+        ));
     }
+
+    Ok(set)
 }
 
 /// 启动并发任务，执行每个子节点
-fn spawn_task(
+async fn spawn_task(
     node_id: String,
     key: String,
     node: Arc<dyn Executable>,
     input: Option<FlowData>,
     context: Arc<Context>,
-) -> impl std::future::Future<Output = Result<(String, ControlFlow)>> {
-    async move {
-        let result = node.execute(input, context).await;
-        let value = result?; // result: Result<OutputValue, _>
-        let data = value
-            .into_data()
-            .map_err(|e| Error::ExecutionError(e.to_string().into()))?; // now FlowData
+) -> Result<(String, ControlFlow)> {
+    let data = node
+        .execute(input, context.clone())
+        .await
+        .and_then(|output| output.into_data())
+        .map_err(|e| {
+            Error::ExecutionError(
+                format!("Branch `{}` (node `{}`) failed: {}", key, node_id, e).into(),
+            )
+        })?;
+    // 分支节点不经过 Runner，记录输出供下游 Aggregator 收集
+    context.record_output(&node_id, data.clone());
 
-        Ok((key, ControlFlow::new(&node_id, data)))
-    }
+    Ok((key.clone(), ControlFlow::new(&key, data)))
 }
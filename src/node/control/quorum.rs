@@ -0,0 +1,129 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use flow_data::{FlowData, SingleData, output::FlowOutput};
+use serde_json::Value;
+use workflow_error::{Error, Result};
+use workflow_macro::impl_executable;
+
+use crate::{
+    model::{context::Context, node::DataProcessorMapping},
+    node::{
+        Executable, NodeBase,
+        config::{QuorumCombine, QuorumConfig},
+        control::parallel::spawn_branches,
+    },
+};
+
+/// QuorumNode：并发执行各分支，`required` 个分支成功后取消其余分支并合并结果
+#[derive(Debug, Clone)]
+pub struct QuorumNode {
+    pub base: NodeBase,
+    pub branches: BTreeMap<String, String>, // key: 名称, value: 节点ID
+    pub required: usize,
+    pub combine: QuorumCombine,
+}
+
+impl QuorumNode {
+    pub fn new(id: &str, data: Value, processor: &DataProcessorMapping) -> Result<Self> {
+        let config: QuorumConfig = serde_json::from_value(data)
+            .map_err(|_| Error::ExecutionError("Invalid data format for QuorumNode".into()))?;
+        if config.required == 0 || config.required > config.branches.len() {
+            return Err(Error::ExecutionError(
+                format!(
+                    "`required` of QuorumNode must be between 1 and {} (the number of branches)",
+                    config.branches.len()
+                )
+                .into(),
+            ));
+        }
+
+        Ok(Self {
+            base: NodeBase::new(id, processor),
+            branches: config.branches,
+            required: config.required,
+            combine: config.combine,
+        })
+    }
+}
+
+#[impl_executable]
+impl Executable for QuorumNode {
+    async fn core_execute(
+        &self,
+        input: Option<FlowData>,
+        context: Arc<Context>,
+    ) -> Result<FlowOutput> {
+        let mut set = spawn_branches(&self.branches, &input, &context)?;
+        let mut results: Vec<(String, FlowData)> = Vec::new();
+        let mut failures = Vec::new();
+
+        while let Some(res) = set.join_next().await {
+            match res {
+                Ok(Ok((key, output))) => results.push((key, output.data)),
+                Ok(Err(e)) => failures.push(e.to_string()),
+                // 分支 panic 或被取消，计为失败
+                Err(e) => failures.push(e.to_string()),
+            }
+            if results.len() >= self.required
+                && let Some(output) = combine(self.combine, self.required, &results)
+            {
+                set.abort_all();
+                return Ok(output.into());
+            }
+            // 剩余分支全部成功也无法达到法定数量
+            if results.len() + set.len() < self.required {
+                break;
+            }
+        }
+
+        let reason = if results.len() >= self.required {
+            format!("no majority among {} results", results.len())
+        } else {
+            format!("{} succeeded", results.len())
+        };
+        Err(Error::ExecutionError(
+            format!(
+                "Quorum of {} not reached ({}): {}",
+                self.required,
+                reason,
+                failures.join("; ")
+            )
+            .into(),
+        ))
+    }
+}
+
+/// 合并结果，`results` 按完成顺序排列；尚不能得出结果时返回 None
+fn combine(
+    mode: QuorumCombine,
+    required: usize,
+    results: &[(String, FlowData)],
+) -> Option<FlowData> {
+    match mode {
+        QuorumCombine::Majority => {
+            let keys: Vec<String> = results.iter().map(|(_, data)| vote_key(data)).collect();
+            let count = |key: &String| keys.iter().filter(|k| *k == key).count();
+            // 票数需严格过半，平票时等待更多结果
+            keys.iter()
+                .position(|key| count(key) * 2 > keys.len())
+                .map(|winner| results[winner].1.clone())
+        }
+        QuorumCombine::Collect => {
+            let mut results = results[..required].to_vec();
+            results.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Some(
+                results
+                    .into_iter()
+                    .fold(FlowData::new_collection(), |acc, (_, data)| acc.merge(data)),
+            )
+        }
+    }
+}
+
+/// 投票比较用的键：文本去除首尾空白，其余取 JSON 文本
+fn vote_key(data: &FlowData) -> String {
+    match data {
+        FlowData::Single(SingleData::Text(text)) => text.trim().to_string(),
+        other => other.to_json_value().to_string(),
+    }
+}
@@ -0,0 +1,63 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use flow_data::{FlowData, output::FlowOutput};
+use serde_json::Value;
+use workflow_error::{Error, Result};
+use workflow_macro::impl_executable;
+
+use crate::{
+    model::{context::Context, node::DataProcessorMapping},
+    node::{Executable, NodeBase, config::RaceConfig, control::parallel::spawn_branches},
+};
+
+/// RaceNode：并发执行各分支，返回第一个成功的结果并取消其余分支
+#[derive(Debug, Clone)]
+pub struct RaceNode {
+    pub base: NodeBase,
+    pub branches: BTreeMap<String, String>, // key: 名称, value: 节点ID
+}
+
+impl RaceNode {
+    pub fn new(id: &str, data: Value, processor: &DataProcessorMapping) -> Result<Self> {
+        let config: RaceConfig = serde_json::from_value(data)
+            .map_err(|_| Error::ExecutionError("Invalid data format for RaceNode".into()))?;
+        if config.branches.is_empty() {
+            return Err(Error::ExecutionError(
+                "`branches` of RaceNode must not be empty".into(),
+            ));
+        }
+
+        Ok(Self {
+            base: NodeBase::new(id, processor),
+            branches: config.branches,
+        })
+    }
+}
+
+#[impl_executable]
+impl Executable for RaceNode {
+    async fn core_execute(
+        &self,
+        input: Option<FlowData>,
+        context: Arc<Context>,
+    ) -> Result<FlowOutput> {
+        let mut set = spawn_branches(&self.branches, &input, &context)?;
+        let mut failures = Vec::new();
+
+        while let Some(res) = set.join_next().await {
+            match res {
+                Ok(Ok((_key, output))) => {
+                    set.abort_all();
+                    return Ok(output.data.into());
+                }
+                Ok(Err(e)) => failures.push(e.to_string()),
+                // 分支 panic 或被取消，计为失败
+                Err(e) => failures.push(e.to_string()),
+            }
+        }
+
+        Err(Error::ExecutionError(
+            format!("All race branches failed: {}", failures.join("; ")).into(),
+        ))
+    }
+}
@@ -7,25 +7,39 @@ use workflow_macro::impl_executable;
 
 use crate::{
     model::{context::Context, node::DataProcessorMapping},
-    node::{Executable, NodeBase, config::RepeatConfig},
+    node::{
+        Executable, NodeBase,
+        config::{ConditionConfig, RepeatConfig},
+        control::subgraph::{ChildBody, into_single},
+    },
 };
 
 #[derive(Debug, Clone)]
 pub struct RepeatNode {
     pub base: NodeBase,
-    pub child_id: String,
+    pub body: ChildBody,
     pub max_iterations: usize,
+    pub until: Option<ConditionConfig>,
+    pub collect: bool,
 }
 
 impl RepeatNode {
     pub fn new(id: &str, data: Value, processor: &DataProcessorMapping) -> Result<Self> {
-        let config: RepeatConfig = serde_json::from_value(data)
-            .map_err(|_| Error::ExecutionError("Invalid data format for RepeatNode".into()))?;
+        let config: RepeatConfig = serde_json::from_value(data).map_err(|e| {
+            Error::ExecutionError(format!("Invalid data format for RepeatNode: {}", e).into())
+        })?;
+
+        if let Some(until) = &config.until {
+            until.validate()?;
+        }
+        let body = ChildBody::from_config(config.child_id, config.subgraph)?;
 
         Ok(Self {
             base: NodeBase::new(id, processor),
-            child_id: config.child_id,
+            body,
             max_iterations: config.max_iterations,
+            until: config.until,
+            collect: config.collect,
         })
     }
 }
@@ -40,18 +54,29 @@ impl Executable for RepeatNode {
         match input {
             Some(data) => {
                 let mut current_input = data;
-                for _ in 0 .. self.max_iterations {
-                    let child_node = context
-                        .get_node(&self.child_id)
-                        .ok_or_else(|| Error::NodeNotFound(self.child_id.clone().into()))?;
-
-                    let output = child_node
-                        .execute(Some(current_input), context.clone())
-                        .await?;
-                    current_input = output.into_data()?;
+                let mut collected = Vec::new();
+                for _ in 0..self.max_iterations {
+                    current_input = self.body.run(current_input, &context).await?;
+
+                    if self.collect {
+                        // 每轮对应一个元素，Collection 输出转为 JSON 数组
+                        collected.push(into_single(current_input.clone()));
+                    }
+
+                    if self
+                        .until
+                        .as_ref()
+                        .is_some_and(|until| until.matches_data(&current_input))
+                    {
+                        break;
+                    }
                 }
 
-                Ok(current_input.into())
+                if self.collect {
+                    Ok(FlowData::Collection(collected).into())
+                } else {
+                    Ok(current_input.into())
+                }
             }
             None => return Err(Error::ExecutionError("No input data provided".into())),
         }
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use flow_data::{
+    FlowData,
+    output::{ControlFlow, FlowOutput},
+};
+use serde_json::Value;
+use workflow_error::{Error, Result};
+use workflow_macro::impl_executable;
+
+use crate::{
+    model::{context::Context, node::DataProcessorMapping},
+    node::{
+        Executable, NodeBase,
+        config::{BranchPayload, RouterConfig, RouterMode},
+    },
+};
+
+/// RouterNode：按条件激活分支
+///
+/// `all` 模式下激活全部匹配的分支（多个时输出 Parallel），`first` 模式下只激活第一个；
+/// 无匹配时走 `default` 句柄。未激活的分支由 Runner 标记为跳过
+#[derive(Debug, Clone)]
+pub struct RouterNode {
+    pub base: NodeBase,
+    pub branches: Vec<BranchPayload>,
+    pub mode: RouterMode,
+}
+
+impl RouterNode {
+    pub fn new(id: &str, data: Value, processor: &DataProcessorMapping) -> Result<Self> {
+        let config: RouterConfig = serde_json::from_value(data).map_err(|e| {
+            Error::ExecutionError(format!("Invalid data format for RouterNode: {}", e).into())
+        })?;
+        for branch in &config.branches {
+            branch.when.validate()?;
+        }
+
+        Ok(Self {
+            base: NodeBase::new(id, processor),
+            branches: config.branches,
+            mode: config.mode,
+        })
+    }
+}
+
+#[impl_executable]
+impl Executable for RouterNode {
+    async fn core_execute(
+        &self,
+        input: Option<FlowData>,
+        _context: Arc<Context>,
+    ) -> Result<FlowOutput> {
+        let data = input.ok_or_else(|| Error::ExecutionError("No input data provided".into()))?;
+        let value = data.to_json_value();
+
+        let mut matched = self
+            .branches
+            .iter()
+            .filter(|branch| branch.when.matches(&value))
+            .map(|branch| branch.id.as_str());
+
+        let handles: Vec<&str> = match self.mode {
+            RouterMode::First => matched.next().into_iter().collect(),
+            RouterMode::All => matched.collect(),
+        };
+
+        match handles.as_slice() {
+            [] => Ok(("default", data).into()),
+            [handle] => Ok((*handle, data).into()),
+            handles => Ok(handles
+                .iter()
+                .map(|handle| ControlFlow::new(handle, data.clone()))
+                .collect::<Vec<_>>()
+                .into()),
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use flow_data::{FlowData, output::FlowOutput};
+use flow_data::{FlowData, SingleData, output::FlowOutput};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use workflow_error::{Error, Result};
@@ -8,7 +8,7 @@ use workflow_macro::impl_executable;
 
 use crate::{
     graph::Graph,
-    model::{context::Context, node::DataProcessorMapping},
+    model::{context::Context, graph_data::GraphData, node::DataProcessorMapping},
     node::{Executable, NodeBase},
     runner::Runner,
 };
@@ -44,24 +44,74 @@ impl Executable for SubGraphNode {
         input: Option<FlowData>,
         _context: Arc<Context>,
     ) -> Result<FlowOutput> {
-        let mut runner = Runner::new();
-        let start_node =
-            self.subgraph.start_node.as_ref().ok_or_else(|| {
-                Error::ExecutionError("SubGraph start node is not defined".into())
-            })?;
+        let output = run_subgraph(&self.subgraph, input).await?;
+        Ok(output.into())
+    }
+}
 
-        runner.set_input(start_node, input.clone());
-        let mut subgraph = self.subgraph.clone();
-        runner.run(input, &mut subgraph, None).await?;
+/// Repeat / Map 等节点对每个输入执行的内容
+#[derive(Debug, Clone)]
+pub enum ChildBody {
+    /// 图中的单个节点
+    Node(String),
+    /// 独立运行的子图
+    SubGraph(Box<Graph>),
+}
 
-        let end_node = self
-            .subgraph
-            .end_node
-            .as_ref()
-            .ok_or_else(|| Error::ExecutionError("SubGraph end node is not defined".into()))?;
+impl ChildBody {
+    /// 由配置中的 `child_id` / `subgraph` 构建，二者必须且只能设置一个
+    pub fn from_config(child_id: Option<String>, subgraph: Option<GraphData>) -> Result<Self> {
+        match (child_id, subgraph) {
+            (Some(child_id), None) => Ok(ChildBody::Node(child_id)),
+            (None, Some(subgraph)) => {
+                let mut graph = Graph::try_from(subgraph)?;
+                graph.compile()?;
+                Ok(ChildBody::SubGraph(Box::new(graph)))
+            }
+            _ => Err(Error::ExecutionError(
+                "Exactly one of `child_id` or `subgraph` is required".into(),
+            )),
+        }
+    }
+
+    /// 以 `input` 执行一次，返回数据输出
+    pub async fn run(&self, input: FlowData, context: &Arc<Context>) -> Result<FlowData> {
+        match self {
+            ChildBody::Node(child_id) => {
+                let child_node = context
+                    .get_node(child_id)
+                    .ok_or_else(|| Error::NodeNotFound(child_id.clone().into()))?;
 
-        let output = runner.get_output(end_node)?;
+                let output = child_node.execute(Some(input), context.clone()).await?;
+                output.into_data()
+            }
+            ChildBody::SubGraph(graph) => run_subgraph(graph, Some(input)).await,
+        }
+    }
+}
+
+/// 在独立的 Runner 中执行子图：输入交给起始节点，返回结束节点的输出
+pub async fn run_subgraph(subgraph: &Graph, input: Option<FlowData>) -> Result<FlowData> {
+    if subgraph.start_node.is_none() {
+        return Err(Error::ExecutionError(
+            "SubGraph start node is not defined".into(),
+        ));
+    }
+    if subgraph.end_node.is_none() {
+        return Err(Error::ExecutionError(
+            "SubGraph end node is not defined".into(),
+        ));
+    }
+
+    let mut runner = Runner::new();
+    let mut subgraph = subgraph.clone();
+    runner.run(input, &mut subgraph, None).await
+}
 
-        Ok(output.clone().into())
+/// 将一次执行的结果转为单值，Collection 结果转为普通 JSON 数组
+pub(crate) fn into_single(data: FlowData) -> SingleData {
+    match data {
+        FlowData::Single(item) => item,
+        collection => SingleData::Json(collection.to_json_value()),
     }
 }
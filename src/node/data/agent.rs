@@ -0,0 +1,318 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use flow_data::{FlowData, SingleData, output::FlowOutput};
+use mcp_core::protocol::message::{JsonRpcError, JsonRpcMessage, JsonRpcRequest, JsonRpcResponse};
+use model_gateway_rs::model::llm::{ChatMessage, LlmInput};
+use serde_json::{Value, json};
+use workflow_error::{Error, Result};
+use workflow_macro::impl_executable;
+
+use crate::{
+    mcp::registry::MCP_CLIENT_REGISTRY,
+    model::{context::Context, node::DataProcessorMapping},
+    node::{
+        Executable, NodeBase,
+        config::{AgentConfig, AgentTool, HttpConfig, ToolSource},
+        data::{
+            HttpNode,
+            llm::{LlmModelClient, model_client, strip_code_fence},
+        },
+    },
+    processor::TOOL_REGISTRY,
+};
+
+/// AgentNode：循环请求模型并执行模型要求的工具调用，直到模型给出最终回答或达到 `max_iterations`
+///
+/// 模型以 `{"tool": 名称, "arguments": {...}}` 回复表示调用工具，工具结果（或错误）作为消息返回给模型；
+/// 其他回复视为最终回答。输出为 JSON：
+/// `{"answer", "stop_reason", "iterations", "tool_calls", "messages"}`，
+/// `stop_reason` 为 `final_answer` 或 `max_iterations`（此时 `answer` 为 null）
+#[derive(Clone)]
+pub struct AgentNode {
+    base: NodeBase,
+    system_prompt: Option<String>,
+    tools: Vec<AgentTool>,
+    max_iterations: usize,
+    model_client: LlmModelClient,
+}
+
+impl AgentNode {
+    pub fn new(id: &str, data: Value, processor: &DataProcessorMapping) -> Result<Self> {
+        let config: AgentConfig = serde_json::from_value(data).map_err(|e| {
+            Error::ExecutionError(format!("Invalid data format for AgentNode: {}", e).into())
+        })?;
+        if config.max_iterations == 0 {
+            return Err(Error::ExecutionError(
+                "`max_iterations` of AgentNode must be at least 1".into(),
+            ));
+        }
+        // LLM 配置中只有模型设置与系统提示词对 Agent 生效
+        let unsupported = [
+            ("memory", config.llm.memory.is_some()),
+            ("stream", config.llm.stream),
+            ("outputSchema", config.llm.output_schema.is_some()),
+            ("prompt", config.llm.prompt.is_some()),
+        ];
+        if let Some((field, _)) = unsupported.iter().find(|(_, set)| *set) {
+            return Err(Error::ExecutionError(
+                format!("`{}` is not supported by AgentNode", field).into(),
+            ));
+        }
+        let mut names = HashSet::new();
+        for tool in &config.tools {
+            if tool.name.is_empty() || !names.insert(tool.name.as_str()) {
+                return Err(Error::ExecutionError(
+                    format!(
+                        "Tool names of AgentNode must be unique and non-empty: `{}`",
+                        tool.name
+                    )
+                    .into(),
+                ));
+            }
+        }
+
+        Ok(Self {
+            base: NodeBase::new(id, processor),
+            model_client: model_client(&config.llm)?,
+            system_prompt: config.llm.system_prompt,
+            tools: config.tools,
+            max_iterations: config.max_iterations,
+        })
+    }
+
+    /// 替换推理客户端
+    pub fn with_model_client(mut self, client: LlmModelClient) -> Self {
+        self.model_client = client;
+        self
+    }
+
+    /// 系统提示词，附加工具列表与调用约定
+    fn system_message(&self) -> String {
+        let mut prompt = self.system_prompt.clone().unwrap_or_default();
+        if self.tools.is_empty() {
+            return prompt;
+        }
+        if !prompt.is_empty() {
+            prompt.push_str("\n\n");
+        }
+        prompt.push_str(
+            "You can use the tools listed below. To call a tool, reply with only a JSON object \
+             of the form {\"tool\": \"<name>\", \"arguments\": {...}}. The result is sent back \
+             to you in a message starting with \"Tool result\". When you can answer, reply with \
+             the final answer as plain text.\n\nTools:",
+        );
+        for tool in &self.tools {
+            prompt.push_str(&format!("\n- {}: {}", tool.name, tool.description));
+            if let Some(parameters) = &tool.parameters {
+                prompt.push_str(&format!("\n  parameters: {}", parameters));
+            }
+        }
+        prompt
+    }
+
+    /// 执行工具调用
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+        context: &Arc<Context>,
+    ) -> Result<Value> {
+        let tool = self
+            .tools
+            .iter()
+            .find(|tool| tool.name == name)
+            .ok_or_else(|| Error::ExecutionError(format!("unknown tool `{}`", name).into()))?;
+
+        match &tool.source {
+            ToolSource::Node { node_id } => {
+                let node = context
+                    .get_node(node_id)
+                    .ok_or(Error::NodeNotFound(node_id.clone().into()))?
+                    .clone();
+                let output = node
+                    .execute(Some(FlowData::from_json_value(arguments)), context.clone())
+                    .await?;
+                Ok(output.into_data()?.to_json_value())
+            }
+            ToolSource::Function { function } => {
+                let function = TOOL_REGISTRY.get(function).ok_or_else(|| {
+                    Error::ExecutionError(
+                        format!("Tool function `{}` is not registered", function).into(),
+                    )
+                })?;
+                function(arguments)
+            }
+            ToolSource::Http {
+                url,
+                method,
+                headers,
+                timeout_seconds,
+            } => {
+                let config = HttpConfig {
+                    url: url.clone(),
+                    input_data: arguments,
+                    method: method.clone(),
+                    headers: headers.clone(),
+                    timeout_seconds: *timeout_seconds,
+                };
+                let node = HttpNode::new(
+                    &tool.name,
+                    serde_json::to_value(config)?,
+                    &DataProcessorMapping::default(),
+                )?;
+                let output = node.execute(None, context.clone()).await?;
+                Ok(output.into_data()?.to_json_value())
+            }
+            ToolSource::Mcp {
+                server_id,
+                call_name,
+            } => {
+                let client = MCP_CLIENT_REGISTRY.get(server_id)?;
+                let request = JsonRpcRequest::new(
+                    Some(NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed)),
+                    "tools/call",
+                    Some(json!({
+                        "name": call_name.as_deref().unwrap_or(&tool.name),
+                        "arguments": arguments,
+                    })),
+                );
+                mcp_result(&tool.name, client.send_resquest(request).await?)
+            }
+        }
+    }
+}
+
+/// MCP 请求的 JSON-RPC ID，进程内递增
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 取出 `tools/call` 响应中的 `result`；JSON-RPC 错误与 `isError` 结果视为工具失败
+fn mcp_result(name: &str, message: JsonRpcMessage) -> Result<Value> {
+    let failed = |reason: String| {
+        Error::ExecutionError(format!("MCP tool `{}` failed: {}", name, reason).into())
+    };
+    match message {
+        JsonRpcMessage::Response(JsonRpcResponse {
+            error: Some(error), ..
+        })
+        | JsonRpcMessage::Error(JsonRpcError { error, .. }) => {
+            Err(failed(format!("{} (code {})", error.message, error.code)))
+        }
+        JsonRpcMessage::Response(response) => {
+            let result = response.result.unwrap_or(Value::Null);
+            if result.get("isError").and_then(Value::as_bool) == Some(true) {
+                return Err(failed(result["content"].to_string()));
+            }
+            Ok(result)
+        }
+        other => Err(failed(format!("unexpected response {:?}", other))),
+    }
+}
+
+impl std::fmt::Debug for AgentNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AgentNode")
+            .field("base", &self.base)
+            .field("system_prompt", &self.system_prompt)
+            .field("tools", &self.tools)
+            .field("max_iterations", &self.max_iterations)
+            .finish()
+    }
+}
+
+#[impl_executable]
+impl Executable for AgentNode {
+    async fn core_execute(
+        &self,
+        input: Option<FlowData>,
+        context: Arc<Context>,
+    ) -> Result<FlowOutput> {
+        let input =
+            input.ok_or_else(|| Error::ExecutionError("AgentNode requires input data".into()))?;
+        let task = match &input {
+            FlowData::Single(SingleData::Text(text)) => text.clone(),
+            other => other.to_json_value().to_string(),
+        };
+
+        let mut messages = vec![
+            ChatMessage::system(&self.system_message()),
+            ChatMessage::user(&task),
+        ];
+        let mut tool_calls = Vec::new();
+        let mut answer = None;
+        let mut iterations = 0;
+
+        while iterations < self.max_iterations {
+            iterations += 1;
+            let reply = self
+                .model_client
+                .infer(LlmInput {
+                    messages: messages.clone(),
+                    max_tokens: None,
+                })
+                .await?;
+            let content = reply.get_content().to_string();
+            messages.push(ChatMessage::assistant(&content));
+
+            let Some((name, arguments)) = parse_tool_call(&content) else {
+                answer = Some(content);
+                break;
+            };
+            // 工具失败不中断循环，错误交给模型处理
+            let (record, text) = match self.call_tool(&name, arguments.clone(), &context).await {
+                Ok(result) => {
+                    let text = match &result {
+                        Value::String(text) => text.clone(),
+                        other => other.to_string(),
+                    };
+                    (
+                        json!({ "tool": name, "arguments": arguments, "result": result }),
+                        text,
+                    )
+                }
+                Err(e) => (
+                    json!({ "tool": name, "arguments": arguments, "error": e.to_string() }),
+                    format!("error: {}", e),
+                ),
+            };
+            tool_calls.push(record);
+            messages.push(ChatMessage::user(&format!(
+                "Tool result for `{}`:\n{}",
+                name, text
+            )));
+        }
+
+        let stop_reason = if answer.is_some() {
+            "final_answer"
+        } else {
+            "max_iterations"
+        };
+        let output = json!({
+            "answer": answer,
+            "stop_reason": stop_reason,
+            "iterations": iterations,
+            "tool_calls": tool_calls,
+            "messages": messages,
+        });
+
+        Ok(FlowData::from(output).into())
+    }
+}
+
+/// 解析工具调用回复，允许包裹在 ```json 代码块中
+fn parse_tool_call(content: &str) -> Option<(String, Value)> {
+    let content = strip_code_fence(content);
+    let Value::Object(mut call) = serde_json::from_str(content).ok()? else {
+        return None;
+    };
+    let Value::String(name) = call.remove("tool")? else {
+        return None;
+    };
+    let arguments = call.remove("arguments").unwrap_or_else(|| json!({}));
+    Some((name, arguments))
+}
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use flow_data::{FlowData, output::FlowOutput};
+use serde_json::Value;
+use workflow_error::{Error, Result};
+use workflow_macro::impl_executable;
+
+use crate::{
+    expr::{Expression, Variables, input_scope},
+    model::{context::Context, node::DataProcessorMapping},
+    node::{Executable, NodeBase, config::ExpressionConfig},
+};
+
+/// ExpressionNode：对输入求值表达式，结果按 JSON 类型转为对应的 FlowData
+#[derive(Debug, Clone)]
+pub struct ExpressionNode {
+    base: NodeBase,
+    expression: Expression,
+    variables: Variables,
+}
+
+impl ExpressionNode {
+    pub fn new(id: &str, data: Value, processor: &DataProcessorMapping) -> Result<Self> {
+        let config: ExpressionConfig = serde_json::from_value(data)
+            .map_err(|_| Error::ExecutionError("Invalid data format for ExpressionNode".into()))?;
+
+        Ok(Self {
+            base: NodeBase::new(id, processor),
+            expression: Expression::parse(&config.expression)?,
+            variables: config.variables,
+        })
+    }
+}
+
+#[impl_executable]
+impl Executable for ExpressionNode {
+    async fn core_execute(
+        &self,
+        input: Option<FlowData>,
+        _context: Arc<Context>,
+    ) -> Result<FlowOutput> {
+        let input = input.map_or(Value::Null, |data| data.to_json_value());
+        let value = self
+            .expression
+            .eval(&input_scope(&input, &self.variables))?;
+        Ok(FlowData::from_json_value(value).into())
+    }
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use flow_data::{FlowData, output::FlowOutput};
+use serde_json::Value;
+use workflow_error::{Error, Result};
+use workflow_macro::impl_executable;
+
+use crate::{
+    model::{context::Context, node::DataProcessorMapping},
+    node::{
+        Executable, NodeBase,
+        config::{ConditionConfig, FilterConfig},
+    },
+};
+
+/// FilterNode：保留集合中满足条件的元素，单值输入视为单元素集合
+#[derive(Debug, Clone)]
+pub struct FilterNode {
+    base: NodeBase,
+    when: ConditionConfig,
+}
+
+impl FilterNode {
+    pub fn new(id: &str, data: Value, processor: &DataProcessorMapping) -> Result<Self> {
+        let config: FilterConfig = serde_json::from_value(data)
+            .map_err(|_| Error::ExecutionError("Invalid data format for FilterNode".into()))?;
+        config.when.validate()?;
+
+        Ok(Self {
+            base: NodeBase::new(id, processor),
+            when: config.when,
+        })
+    }
+}
+
+#[impl_executable]
+impl Executable for FilterNode {
+    async fn core_execute(
+        &self,
+        input: Option<FlowData>,
+        _context: Arc<Context>,
+    ) -> Result<FlowOutput> {
+        let items = match input {
+            None => return Err(Error::ExecutionError("No input data provided".into())),
+            Some(FlowData::Single(item)) => vec![item],
+            Some(FlowData::Collection(items)) => items,
+        };
+
+        let kept = items
+            .into_iter()
+            .filter(|item| self.when.matches_data(&FlowData::Single(item.clone())))
+            .collect();
+
+        Ok(FlowData::Collection(kept).into())
+    }
+}
@@ -7,6 +7,7 @@ use workflow_error::{Error, Result};
 use workflow_macro::impl_executable;
 
 use crate::{
+    expr::{Template, Variables, input_scope},
     model::{context::Context, node::DataProcessorMapping},
     node::{Executable, NodeBase, config::HttpConfig},
 };
@@ -17,7 +18,8 @@ use crate::{
 /// - 多种 HTTP 方法：GET, POST, PUT, DELETE, PATCH
 /// - 自定义请求头
 /// - 请求超时配置（需要 toolcraft_request 支持）
-/// - 输入数据合并：节点输入数据会与配置数据合并，输入数据优先级更高
+/// - 模板：`url`、请求头的值与 `input_data` 中的字符串按 `{{ expr }}` 渲染，
+///   以 `input` 绑定节点输入，如 `https://api.example.com/users/{{ input.id }}`
 /// - 自动处理 JSON 和纯文本响应
 ///
 /// 配置示例：
@@ -38,6 +40,54 @@ use crate::{
 pub struct HttpNode {
     base: NodeBase,
     config: HttpConfig,
+    url: Template,
+    headers: Vec<(String, Template)>,
+    body: BodyTemplate,
+}
+
+/// 请求体模板：字符串按模板渲染，其余值原样保留
+#[derive(Debug, Clone)]
+enum BodyTemplate {
+    Text(Template),
+    Array(Vec<BodyTemplate>),
+    Object(Vec<(String, BodyTemplate)>),
+    Literal(Value),
+}
+
+impl BodyTemplate {
+    fn parse(value: &Value) -> Result<Self> {
+        Ok(match value {
+            Value::String(text) => Self::Text(Template::parse(text)?),
+            Value::Array(items) => {
+                Self::Array(items.iter().map(Self::parse).collect::<Result<_>>()?)
+            }
+            Value::Object(map) => Self::Object(
+                map.iter()
+                    .map(|(key, value)| Ok((key.clone(), Self::parse(value)?)))
+                    .collect::<Result<_>>()?,
+            ),
+            other => Self::Literal(other.clone()),
+        })
+    }
+
+    fn render(&self, vars: &Variables) -> Result<Value> {
+        Ok(match self {
+            Self::Text(template) => Value::String(template.render(vars)?),
+            Self::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| item.render(vars))
+                    .collect::<Result<_>>()?,
+            ),
+            Self::Object(fields) => Value::Object(
+                fields
+                    .iter()
+                    .map(|(key, value)| Ok((key.clone(), value.render(vars)?)))
+                    .collect::<Result<_>>()?,
+            ),
+            Self::Literal(value) => value.clone(),
+        })
+    }
 }
 
 impl HttpNode {
@@ -51,39 +101,21 @@ impl HttpNode {
             return Err(Error::ExecutionError("HTTP URL cannot be empty".into()));
         }
 
+        let headers = config
+            .headers
+            .iter()
+            .flatten()
+            .map(|(key, value)| Ok((key.clone(), Template::parse(value)?)))
+            .collect::<Result<_>>()?;
+
         Ok(Self {
             base: NodeBase::new(id, processor),
+            url: Template::parse(&config.url)?,
+            headers,
+            body: BodyTemplate::parse(&config.input_data)?,
             config,
         })
     }
-
-    /// Merge input data with config data
-    fn merge_request_data(&self, _input: Option<FlowData>) -> Result<Value> {
-        let config_json = self.config.input_data.clone();
-        Ok(config_json)
-
-        // match input {
-        //     Some(input_data) => {
-        //         let input_json = input_data.into_json()?;
-        //         let config_json = self.config.input_data.clone();
-
-        //         // Merge logic: input data takes precedence over config data
-        //         match (input_json, config_json) {
-        //             (Value::Object(mut input_map), Value::Object(config_map)) => {
-        //                 // Config values are defaults, input values override
-        //                 for (k, v) in config_map {
-        //                     input_map.entry(k).or_insert(v);
-        //                 }
-        //                 Ok(Value::Object(input_map))
-        //             }
-        //             (input, Value::Null) => Ok(input),
-        //             (Value::Null, config) => Ok(config),
-        //             (input, _) => Ok(input), // Input takes precedence
-        //         }
-        //     }
-        //     None => Ok(self.config.input_data.clone()),
-        // }
-    }
 }
 
 #[impl_executable]
@@ -93,8 +125,11 @@ impl Executable for HttpNode {
         input: Option<FlowData>,
         _context: Arc<Context>,
     ) -> Result<FlowOutput> {
-        // Prepare request data by merging input with config
-        let request_data = self.merge_request_data(input)?;
+        // Render url, headers and body templates against the node input
+        let input = input.map_or(Value::Null, |data| data.to_json_value());
+        let vars = input_scope(&input, &Variables::new());
+        let url = self.url.render(&vars)?;
+        let request_data = self.body.render(&vars)?;
 
         // Create HTTP client
         let request = Request::new().map_err(|e| {
@@ -105,22 +140,18 @@ impl Executable for HttpNode {
         let mut headers_vec: Vec<(&'static str, String)> = Vec::new();
 
         // Add custom headers if configured
-        if let Some(ref headers_map) = self.config.headers {
-            for (key, value) in headers_map {
-                // We need to leak the string to get a 'static lifetime
-                // This is not ideal but toolcraft_request requires it
-                let key_static: &'static str = Box::leak(key.clone().into_boxed_str());
-                headers_vec.push((key_static, value.clone()));
-            }
+        for (key, value) in &self.headers {
+            // We need to leak the string to get a 'static lifetime
+            // This is not ideal but toolcraft_request requires it
+            let key_static: &'static str = Box::leak(key.clone().into_boxed_str());
+            headers_vec.push((key_static, value.render(&vars)?));
         }
 
         // Ensure Content-Type is set for JSON requests if not already present
         let has_content_type = self
-            .config
             .headers
-            .as_ref()
-            .map(|h| h.keys().any(|k| k.eq_ignore_ascii_case("content-type")))
-            .unwrap_or(false);
+            .iter()
+            .any(|(k, _)| k.eq_ignore_ascii_case("content-type"));
 
         if !has_content_type {
             headers_vec.push(("Content-Type", "application/json".to_string()));
@@ -150,29 +181,19 @@ impl Executable for HttpNode {
                             format!("Failed to serialize query parameters: {}", e).into(),
                         )
                     })?;
-                    format!("{}?{}", self.config.url, query_string)
+                    format!("{}?{}", url, query_string)
                 } else {
-                    self.config.url.clone()
+                    url
                 };
                 // GET takes url, headers, query_params
                 request.get(&url, None, headers_option).await
             }
-            "POST" => {
-                request
-                    .post(&self.config.url, &request_data, headers_option)
-                    .await
-            }
-            "PUT" => {
-                request
-                    .put(&self.config.url, &request_data, headers_option)
-                    .await
-            }
-            "DELETE" => request.delete(&self.config.url, headers_option).await,
+            "POST" => request.post(&url, &request_data, headers_option).await,
+            "PUT" => request.put(&url, &request_data, headers_option).await,
+            "DELETE" => request.delete(&url, headers_option).await,
             "PATCH" => {
                 // Use POST as a fallback for PATCH since toolcraft_request might not have patch
-                request
-                    .post(&self.config.url, &request_data, headers_option)
-                    .await
+                request.post(&url, &request_data, headers_option).await
             }
             _ => {
                 return Err(Error::ExecutionError(
@@ -1,34 +1,49 @@
 use core::str;
 use std::sync::Arc;
 
-use flow_data::{FlowData, output::FlowOutput};
-use model_gateway_rs::{
-    clients::llm::LlmClient,
-    model::llm::{ChatMessage, LlmInput, LlmOutput},
-    sdk::openai::OpenAiSdk,
-    traits::ModelClient,
+use flow_data::{FlowData, StreamFormat, output::FlowOutput};
+use model_gateway_rs::model::{
+    doubao_vision::DoubaoVisionMessage,
+    llm::{ChatMessage, LlmInput},
+    role::Role,
+    vision::VisionInput,
 };
-use serde::Deserialize;
 use serde_json::Value;
+use toolcraft_request::ByteStream;
 use workflow_error::{Error, Result};
 use workflow_macro::impl_executable;
 
+use super::llm_input::{input_messages, is_multimodal, to_chat_message, to_vision_message};
 use crate::{
+    expr::Template,
+    memory::MEMORY_REGISTRY,
     model::{context::Context, node::DataProcessorMapping},
-    node::{Executable, NodeBase},
+    node::{
+        Executable, NodeBase,
+        config::{CollectionMode, LLMConfig, MemoryConfig, MemoryStrategy, Provider},
+        json_schema,
+    },
+    provider::{ChatClients, MODEL_PROFILES, chat_clients},
 };
 
-#[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct LLMNodeConfig {
-    #[serde(rename = "apiHost")]
-    pub base_url: String,
-    pub api_key: String,
-    #[serde(rename = "modelName")]
-    pub model: String,
-    pub prompt: Option<String>,
-    pub system_prompt: Option<String>,
-    pub temperature: Option<f32>,
+pub use crate::provider::{
+    LlmModelClient, LlmStreamClient, LlmVisionClient, LlmVisionStreamClient,
+};
+
+const DEFAULT_MAX_RETRIES: usize = 2;
+
+/// 按配置（及其引用的模型配置档案）创建推理客户端
+fn clients(config: &LLMConfig) -> Result<(Provider, ChatClients)> {
+    let settings = MODEL_PROFILES.resolve(config.profile.as_deref(), &config.settings)?;
+    Ok((
+        settings.provider.unwrap_or_default(),
+        chat_clients(&settings)?,
+    ))
+}
+
+/// 按配置创建推理客户端
+pub(crate) fn model_client(config: &LLMConfig) -> Result<LlmModelClient> {
+    Ok(clients(config)?.1.model)
 }
 
 #[derive(Clone)]
@@ -38,39 +53,286 @@ pub struct LLMNode {
     /// 系统提示词（system prompt）
     system_prompt: Option<String>,
 
-    prompt: Option<String>,
+    /// 用户消息模板
+    prompt: Option<Template>,
+
+    /// 集合输入的处理方式
+    collection: CollectionMode,
+
+    /// 模型接口的提供方
+    provider: Provider,
+
+    /// 期望输出的 JSON Schema
+    output_schema: Option<Value>,
+
+    /// 结构化输出失败后的重试次数
+    max_retries: usize,
+
+    /// 是否以流的形式输出
+    stream: bool,
+
+    /// 会话记忆
+    memory: Option<MemoryConfig>,
+
+    model_client: LlmModelClient,
+
+    stream_client: LlmStreamClient,
+
+    /// provider 不支持图片与视频输入时为 None
+    vision_client: Option<LlmVisionClient>,
 
-    /// 生成温度
-    temperature: Option<f32>,
+    vision_stream_client: Option<LlmVisionStreamClient>,
 
-    model_client: Arc<dyn ModelClient<LlmInput, LlmOutput> + Send + Sync>,
+    /// 流式输出的格式，由 provider 决定
+    stream_format: StreamFormat,
 }
 
 impl LLMNode {
     pub fn new(id: &str, data: Value, processor: &DataProcessorMapping) -> Result<Self> {
-        let config: LLMNodeConfig = serde_json::from_value(data)
-            .map_err(|_| Error::ExecutionError("Invalid data format for InputNode".into()))?;
+        let config: LLMConfig = serde_json::from_value(data).map_err(|e| {
+            Error::ExecutionError(format!("Invalid data format for LLMNode: {}", e).into())
+        })?;
 
-        let inner = OpenAiSdk::new(&config.api_key, &config.base_url, &config.model)
-            .map_err(|e| Error::ExecutionError(format!("OpenAiSdk error: {}", e).into()))?;
-        let client = LlmClient::new(inner);
+        if let Some(schema) = &config.output_schema {
+            if config.stream {
+                return Err(Error::ExecutionError(
+                    "`stream` of LLMNode cannot be combined with `outputSchema`".into(),
+                ));
+            }
+            json_schema::check(schema)?;
+        }
+        if let Some(memory) = &config.memory {
+            check_memory(memory, config.stream)?;
+        }
+        let (provider, clients) = clients(&config)?;
+        let (vision_client, vision_stream_client) = clients.vision.unzip();
+        let prompt = config.prompt.as_deref().map(Template::parse).transpose()?;
 
         Ok(Self {
             base: NodeBase::new(id, processor),
             system_prompt: config.system_prompt,
-            temperature: config.temperature,
-            model_client: Arc::new(client),
-            prompt: config.prompt,
+            provider,
+            output_schema: config.output_schema,
+            max_retries: config.max_retries.unwrap_or(DEFAULT_MAX_RETRIES),
+            stream: config.stream,
+            memory: config.memory,
+            model_client: clients.model,
+            stream_client: clients.stream,
+            vision_client,
+            vision_stream_client,
+            stream_format: clients.stream_format,
+            prompt,
+            collection: config.collection,
         })
     }
+
+    /// 替换推理客户端
+    pub fn with_model_client(mut self, client: LlmModelClient) -> Self {
+        self.model_client = client;
+        self
+    }
+
+    /// 替换流式推理客户端
+    pub fn with_stream_client(mut self, client: LlmStreamClient) -> Self {
+        self.stream_client = client;
+        self
+    }
+
+    /// 替换多模态推理客户端
+    pub fn with_vision_client(mut self, client: LlmVisionClient) -> Self {
+        self.vision_client = Some(client);
+        self
+    }
+
+    /// 替换多模态流式推理客户端
+    pub fn with_vision_stream_client(mut self, client: LlmVisionStreamClient) -> Self {
+        self.vision_stream_client = Some(client);
+        self
+    }
+
+    /// 请求模型回复：消息含图片或视频时使用多模态客户端
+    async fn infer(&self, messages: Vec<DoubaoVisionMessage>) -> Result<String> {
+        if messages.iter().any(is_multimodal) {
+            let client = self
+                .vision_client
+                .as_ref()
+                .ok_or_else(|| self.no_vision())?;
+            let output = client.infer(VisionInput::new(messages)).await?;
+            return Ok(output.content);
+        }
+        let output = self
+            .model_client
+            .infer(LlmInput {
+                messages: messages.iter().map(to_chat_message).collect(),
+                max_tokens: None,
+            })
+            .await?;
+        Ok(output.get_content().to_string())
+    }
+
+    async fn infer_stream(&self, messages: Vec<DoubaoVisionMessage>) -> Result<ByteStream> {
+        if messages.iter().any(is_multimodal) {
+            let client = self
+                .vision_stream_client
+                .as_ref()
+                .ok_or_else(|| self.no_vision())?;
+            return Ok(client.infer_stream(VisionInput::new(messages)).await?);
+        }
+        Ok(self
+            .stream_client
+            .infer_stream(LlmInput {
+                messages: messages.iter().map(to_chat_message).collect(),
+                max_tokens: None,
+            })
+            .await?)
+    }
+
+    fn no_vision(&self) -> Error {
+        Error::ExecutionError(
+            format!(
+                "Image and video input is not supported by the {:?} provider",
+                self.provider
+            )
+            .into(),
+        )
+    }
+
+    /// 请求结构化输出：回复无法解析或不符合 schema 时附上错误重试，最多 `max_retries` 次
+    async fn infer_json(
+        &self,
+        mut messages: Vec<DoubaoVisionMessage>,
+        schema: &Value,
+    ) -> Result<Value> {
+        if let Some(DoubaoVisionMessage::Text { content, .. }) = messages.first_mut() {
+            if !content.is_empty() {
+                content.push_str("\n\n");
+            }
+            content.push_str(&format!(
+                "Respond with only a JSON value that matches this JSON Schema, without any \
+                 other text:\n{}",
+                schema
+            ));
+        }
+
+        let mut error = String::new();
+        for _ in 0..=self.max_retries {
+            let content = self.infer(messages.clone()).await?;
+            let result = serde_json::from_str::<Value>(strip_code_fence(&content))
+                .map_err(|e| format!("the response is not valid JSON ({})", e))
+                .and_then(|value| json_schema::validate(schema, &value).map(|_| value));
+            match result {
+                Ok(value) => return Ok(value),
+                Err(e) => error = e,
+            }
+
+            messages.push(DoubaoVisionMessage::assistant(content));
+            messages.push(DoubaoVisionMessage::user(format!(
+                "Your response is invalid: {}. Reply again with only JSON that matches the schema.",
+                error
+            )));
+        }
+
+        Err(Error::ExecutionError(
+            format!(
+                "LLM response does not match the output schema after {} attempts: {}",
+                self.max_retries + 1,
+                error
+            )
+            .into(),
+        ))
+    }
+
+    /// 按保留策略裁剪会话历史
+    async fn compact(
+        &self,
+        strategy: &MemoryStrategy,
+        mut history: Vec<ChatMessage>,
+    ) -> Result<Vec<ChatMessage>> {
+        match *strategy {
+            MemoryStrategy::Full => {}
+            MemoryStrategy::Window { max_messages } => {
+                let excess = history.len().saturating_sub(max_messages);
+                history.drain(..excess);
+            }
+            MemoryStrategy::Summarize {
+                max_messages,
+                keep_recent,
+            } if history.len() > max_messages => {
+                let recent = history.split_off(history.len() - keep_recent);
+                let summary = self.summarize(&history).await?;
+                history = vec![ChatMessage::system(&format!(
+                    "Summary of the earlier conversation:\n{}",
+                    summary
+                ))];
+                history.extend(recent);
+            }
+            MemoryStrategy::Summarize { .. } => {}
+        }
+        Ok(history)
+    }
+
+    /// 请模型总结较早的对话，之前的总结作为系统消息一并参与
+    async fn summarize(&self, messages: &[ChatMessage]) -> Result<String> {
+        let transcript = messages
+            .iter()
+            .map(|message| {
+                let role = match message.role {
+                    Role::System => "system",
+                    Role::User => "user",
+                    Role::Assistant => "assistant",
+                };
+                format!("{}: {}", role, message.content)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let reply = self
+            .model_client
+            .infer(LlmInput {
+                messages: vec![
+                    ChatMessage::system(
+                        "Summarize the conversation below in a few sentences. Keep the facts, \
+                         names and decisions needed to continue it.",
+                    ),
+                    ChatMessage::user(&transcript),
+                ],
+                max_tokens: None,
+            })
+            .await?;
+        Ok(reply.get_content().to_string())
+    }
+}
+
+/// 检查会话记忆配置
+fn check_memory(memory: &MemoryConfig, stream: bool) -> Result<()> {
+    let invalid = |reason: &str| Err(Error::ExecutionError(reason.to_string().into()));
+    if stream {
+        return invalid("`stream` of LLMNode cannot be combined with `memory`");
+    }
+    match memory.strategy {
+        MemoryStrategy::Window { max_messages: 0 } => {
+            invalid("`max_messages` of the window memory must be at least 1")
+        }
+        MemoryStrategy::Summarize {
+            max_messages,
+            keep_recent,
+        } if keep_recent >= max_messages => {
+            invalid("`keep_recent` of the summarize memory must be less than `max_messages`")
+        }
+        _ => Ok(()),
+    }
 }
 
 impl std::fmt::Debug for LLMNode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("LLMNode")
             .field("system_prompt", &self.system_prompt)
-            .field("temperature", &self.temperature)
+            .field("provider", &self.provider)
             .field("prompt", &self.prompt)
+            .field("collection", &self.collection)
+            .field("output_schema", &self.output_schema)
+            .field("max_retries", &self.max_retries)
+            .field("stream", &self.stream)
+            .field("memory", &self.memory)
             .finish()
     }
 }
@@ -80,7 +342,7 @@ impl Executable for LLMNode {
     async fn core_execute(
         &self,
         input: Option<FlowData>,
-        _context: Arc<Context>,
+        context: Arc<Context>,
     ) -> Result<FlowOutput> {
         let input = match input {
             Some(data) => data,
@@ -89,29 +351,66 @@ impl Executable for LLMNode {
             }
         };
 
-        let msg = data_payload_to_message(&input, &self.system_prompt, &self.prompt)?;
-        let input = LlmInput {
-            messages: msg,
-            max_tokens: None,
+        let turn = input_messages(&input, self.prompt.as_ref(), self.collection).await?;
+        let system_prompt = self.system_prompt.as_deref().unwrap_or("");
+        let mut messages = vec![DoubaoVisionMessage::system(system_prompt)];
+
+        // 历史消息插在系统提示词与本轮输入之间
+        let session = match &self.memory {
+            Some(memory) => {
+                let session_id = context.session_id().ok_or_else(|| {
+                    Error::ExecutionError(
+                        "LLMNode with `memory` requires a session id, see `Runner::with_session`"
+                            .into(),
+                    )
+                })?;
+                let store = MEMORY_REGISTRY.get(&memory.store)?;
+                // 读取历史到写回之间持有会话锁，避免并发的轮次互相覆盖
+                let guard = MEMORY_REGISTRY
+                    .lock_session(&memory.store, session_id)
+                    .await;
+                let history = store.load(session_id)?;
+                messages.extend(history.iter().cloned().map(to_vision_message));
+                Some((memory, session_id, store, history, guard))
+            }
+            None => None,
+        };
+        messages.extend(turn.iter().cloned());
+
+        let (reply, response) = if let Some(schema) = &self.output_schema {
+            let value = self.infer_json(messages, schema).await?;
+            (value.to_string(), FlowData::from(value))
+        } else if self.stream {
+            // 由 Runner 转发给 StreamSender 并汇总为节点输出
+            let stream = self.infer_stream(messages).await?;
+            return Ok(FlowOutput::new_stream_with_format(
+                stream,
+                self.stream_format,
+            ));
+        } else {
+            let content = self.infer(messages).await?;
+            (content.clone(), FlowData::from(content))
         };
-        let r = self.model_client.infer(input).await?;
-        let content = r.get_content();
-        let response = FlowData::from(content);
+
+        if let Some((memory, session_id, store, mut history, guard)) = session {
+            history.extend(turn.iter().map(to_chat_message));
+            history.push(ChatMessage::assistant(&reply));
+            let history = self.compact(&memory.strategy, history).await?;
+            store.save(session_id, &history)?;
+            drop(guard);
+        }
 
         Ok(response.into())
     }
 }
 
-fn data_payload_to_message(
-    input: &FlowData,
-    system_prompt: &Option<String>,
-    _prompt: &Option<String>,
-) -> Result<Vec<ChatMessage>> {
-    let content = input.as_text()?;
-    let system_prompt = system_prompt.as_deref().unwrap_or("");
-
-    Ok(vec![
-        ChatMessage::system(system_prompt),
-        ChatMessage::user(content),
-    ])
+/// 去掉回复外层的 Markdown 代码块（```json ... ```）
+pub(crate) fn strip_code_fence(content: &str) -> &str {
+    let content = content.trim();
+    content
+        .strip_prefix("```json")
+        .or_else(|| content.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .unwrap_or(content)
+        .trim()
 }
@@ -0,0 +1,235 @@
+use std::path::Path;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use flow_data::{FileType, FileValue, FlowData, SingleData};
+use model_gateway_rs::model::{
+    doubao_vision::{DoubaoVisionMessage, ImageUrl, MessageContent, VideoUrl},
+    llm::ChatMessage,
+    role::Role,
+};
+use serde_json::Value;
+use workflow_error::{Error, Result};
+
+use crate::{
+    expr::{Template, Variables, input_scope},
+    node::config::CollectionMode,
+};
+
+/// 将 LLM 节点的输入转换为本轮的消息（不含系统提示词与会话历史）
+///
+/// - 元素均含 `role` 的 JSON 数组（或 JSON 对象集合）按对话消息列表使用，
+///   `content` 可为文本或 OpenAI 格式的内容片段（`text`、`image_url`、`video_url`）
+/// - 图片与视频文件作为多模态内容片段，本地路径读取后以 data URL 发送
+/// - 其他集合按 `collection` 拼接为一条消息或逐个作为消息
+///
+/// `prompt` 以 `input` 绑定输入渲染：含 `{{ }}` 时替换输入文本，否则放在输入文本之前；
+/// 输入为多条消息时作为最后一条用户消息
+pub(crate) async fn input_messages(
+    input: &FlowData,
+    prompt: Option<&Template>,
+    collection: CollectionMode,
+) -> Result<Vec<DoubaoVisionMessage>> {
+    let rendered = prompt
+        .map(|template| template.render(&input_scope(&input.to_json_value(), &Variables::new())))
+        .transpose()?;
+    let items: Vec<&SingleData> = match input {
+        FlowData::Single(item) => vec![item],
+        FlowData::Collection(items) => items.iter().collect(),
+    };
+
+    if let Some(list) = message_list(&items) {
+        let mut messages = list
+            .iter()
+            .enumerate()
+            .map(|(i, message)| parse_message(i, message))
+            .collect::<Result<Vec<_>>>()?;
+        messages.extend(rendered.map(DoubaoVisionMessage::user));
+        return Ok(messages);
+    }
+
+    if collection == CollectionMode::Messages && matches!(input, FlowData::Collection(_)) {
+        let mut messages = Vec::with_capacity(items.len() + 1);
+        for item in items {
+            let mut parts = Parts::default();
+            parts.add(item).await?;
+            messages.push(parts.into_message());
+        }
+        messages.extend(rendered.map(DoubaoVisionMessage::user));
+        return Ok(messages);
+    }
+
+    let mut parts = Parts::default();
+    for item in items {
+        parts.add(item).await?;
+    }
+    if let (Some(template), Some(rendered)) = (prompt, rendered) {
+        if template.has_expressions() {
+            parts.texts = vec![rendered];
+        } else {
+            parts.texts.insert(0, rendered);
+        }
+    }
+    Ok(vec![parts.into_message()])
+}
+
+/// 转为纯文本消息，图片与视频以占位文本表示，用于会话记忆与纯文本模型
+pub(crate) fn to_chat_message(message: &DoubaoVisionMessage) -> ChatMessage {
+    match message {
+        DoubaoVisionMessage::Text { role, content } => ChatMessage {
+            role: role.clone(),
+            content: content.clone(),
+        },
+        DoubaoVisionMessage::Multimodal { role, content } => {
+            let content = content
+                .iter()
+                .map(|part| match part {
+                    MessageContent::Text { text } => text.as_str(),
+                    MessageContent::ImageUrl { .. } => "[image]",
+                    MessageContent::VideoUrl { .. } => "[video]",
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            ChatMessage {
+                role: role.clone(),
+                content,
+            }
+        }
+    }
+}
+
+pub(crate) fn to_vision_message(message: ChatMessage) -> DoubaoVisionMessage {
+    DoubaoVisionMessage::Text {
+        role: message.role,
+        content: message.content,
+    }
+}
+
+pub(crate) fn is_multimodal(message: &DoubaoVisionMessage) -> bool {
+    matches!(message, DoubaoVisionMessage::Multimodal { .. })
+}
+
+/// 一条用户消息的文本与多模态片段
+#[derive(Default)]
+struct Parts {
+    texts: Vec<String>,
+    media: Vec<MessageContent>,
+}
+
+impl Parts {
+    async fn add(&mut self, item: &SingleData) -> Result<()> {
+        match item {
+            SingleData::Text(text) => self.texts.push(text.clone()),
+            SingleData::Number(n) => self.texts.push(n.to_string()),
+            SingleData::Json(Value::String(text)) => self.texts.push(text.clone()),
+            SingleData::Json(value) => self.texts.push(value.to_string()),
+            SingleData::File(file) => self.media.push(media_part(file).await?),
+        }
+        Ok(())
+    }
+
+    fn into_message(self) -> DoubaoVisionMessage {
+        let text = self.texts.join("\n");
+        if self.media.is_empty() {
+            return DoubaoVisionMessage::user(text);
+        }
+        let mut content = Vec::with_capacity(self.media.len() + 1);
+        if !text.is_empty() {
+            content.push(MessageContent::Text { text });
+        }
+        content.extend(self.media);
+        DoubaoVisionMessage::Multimodal {
+            role: Role::User,
+            content,
+        }
+    }
+}
+
+/// 元素均为含 `role` 的 JSON 对象时视为对话消息列表
+fn message_list<'a>(items: &[&'a SingleData]) -> Option<Vec<&'a Value>> {
+    let list: Vec<&Value> = match items {
+        [SingleData::Json(Value::Array(list))] => list.iter().collect(),
+        items if items.len() > 1 => items
+            .iter()
+            .map(|item| match item {
+                SingleData::Json(value) => Some(value),
+                _ => None,
+            })
+            .collect::<Option<_>>()?,
+        _ => return None,
+    };
+    let is_message = |value: &&Value| value.get("role").is_some_and(Value::is_string);
+    (!list.is_empty() && list.iter().all(is_message)).then_some(list)
+}
+
+fn parse_message(index: usize, message: &Value) -> Result<DoubaoVisionMessage> {
+    let invalid = |reason: String| {
+        Error::ExecutionError(format!("Invalid chat message at index {}: {}", index, reason).into())
+    };
+    let role: Role = serde_json::from_value(message["role"].clone())
+        .map_err(|_| invalid(format!("unknown role {}", message["role"])))?;
+    match &message["content"] {
+        Value::String(content) => Ok(DoubaoVisionMessage::Text {
+            role,
+            content: content.clone(),
+        }),
+        Value::Array(parts) => {
+            let content = serde_json::from_value(Value::Array(parts.clone()))
+                .map_err(|e| invalid(format!("invalid content parts ({})", e)))?;
+            Ok(DoubaoVisionMessage::Multimodal { role, content })
+        }
+        other => Err(invalid(format!(
+            "`content` must be a string or an array, found {}",
+            other
+        ))),
+    }
+}
+
+/// 图片或视频文件的内容片段：URL 原样使用，本地文件读取后编码为 data URL
+async fn media_part(file: &FileValue) -> Result<MessageContent> {
+    if file.file_type == FileType::Audio {
+        return Err(Error::ExecutionError(
+            format!("Audio input is not supported by LLMNode: {}", file.path).into(),
+        ));
+    }
+    let url = if ["http://", "https://", "data:"]
+        .iter()
+        .any(|scheme| file.path.starts_with(scheme))
+    {
+        file.path.clone()
+    } else {
+        format!(
+            "data:{};base64,{}",
+            mime_type(file)?,
+            STANDARD.encode(tokio::fs::read(&file.path).await?)
+        )
+    };
+
+    Ok(match file.file_type {
+        FileType::Video => MessageContent::VideoUrl {
+            video_url: VideoUrl { url },
+        },
+        _ => MessageContent::ImageUrl {
+            image_url: ImageUrl { url, detail: None },
+        },
+    })
+}
+
+fn mime_type(file: &FileValue) -> Result<&'static str> {
+    let extension = Path::new(&file.path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match (&file.file_type, extension.as_deref()) {
+        (FileType::Image, Some("png")) => Ok("image/png"),
+        (FileType::Image, Some("jpg" | "jpeg")) => Ok("image/jpeg"),
+        (FileType::Image, Some("gif")) => Ok("image/gif"),
+        (FileType::Image, Some("webp")) => Ok("image/webp"),
+        (FileType::Image, Some("bmp")) => Ok("image/bmp"),
+        (FileType::Video, Some("mp4")) => Ok("video/mp4"),
+        (FileType::Video, Some("webm")) => Ok("video/webm"),
+        (FileType::Video, Some("mov")) => Ok("video/quicktime"),
+        _ => Err(Error::ExecutionError(
+            format!("Unsupported media file for LLMNode: {}", file.path).into(),
+        )),
+    }
+}
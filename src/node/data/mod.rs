@@ -1,10 +1,21 @@
+pub mod agent;
+pub mod expression;
+pub mod filter;
 pub mod http;
 pub mod indentity;
 pub mod input;
 pub mod llm;
+mod llm_input;
 pub mod mcp;
 pub mod output;
 pub mod prompt;
+pub mod reduce;
+pub mod transform;
 
+pub use agent::AgentNode;
+pub use expression::ExpressionNode;
+pub use filter::FilterNode;
 pub use http::HttpNode;
 pub use prompt::PromptNode;
+pub use reduce::ReduceNode;
+pub use transform::TransformNode;
@@ -6,15 +6,19 @@ use workflow_error::{Error, Result};
 use workflow_macro::impl_executable;
 
 use crate::{
+    expr::{Template, Variables, input_scope},
     model::{context::Context, node::DataProcessorMapping},
     node::{Executable, NodeBase, config::PromptConfig},
 };
 
 /// PromptNode 节点，用于接收输入并返回处理后的输出
+///
+/// 模板中的 `{{ expr }}` 以 `input` 绑定节点输入渲染，如 `Summarize: {{ input.text }}`；
+/// 输入为 JSON 对象时可直接引用其键，如 Parallel 按对象输出后的 `{{ research }}`
 #[derive(Debug, Clone)]
 pub struct PromptNode {
     base: NodeBase,
-    template: String,
+    template: Template,
 }
 
 impl PromptNode {
@@ -32,7 +36,7 @@ impl PromptNode {
 
         Ok(Self {
             base: NodeBase::new(id, processor),
-            template: config.template,
+            template: Template::parse(&config.template)?,
         })
     }
 }
@@ -41,10 +45,13 @@ impl PromptNode {
 impl Executable for PromptNode {
     async fn core_execute(
         &self,
-        _input: Option<FlowData>,
+        input: Option<FlowData>,
         _context: Arc<Context>,
     ) -> Result<FlowOutput> {
-        let data = FlowData::from(self.template.as_str());
-        Ok(data.into())
+        let input = input.map_or(Value::Null, |data| data.to_json_value());
+        let text = self
+            .template
+            .render(&input_scope(&input, &Variables::new()))?;
+        Ok(FlowData::from(text).into())
     }
 }
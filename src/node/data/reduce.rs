@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use flow_data::{FlowData, SingleData, output::FlowOutput};
+use serde_json::{Map, Value};
+use workflow_error::{Error, Result};
+use workflow_macro::impl_executable;
+
+use crate::{
+    model::{context::Context, node::DataProcessorMapping},
+    node::{Executable, NodeBase, config::ReduceConfig},
+    processor::REDUCER_REGISTRY,
+};
+
+/// ReduceNode：将集合归约为单个值，单值输入视为单元素集合
+#[derive(Debug, Clone)]
+pub struct ReduceNode {
+    base: NodeBase,
+    config: ReduceConfig,
+}
+
+impl ReduceNode {
+    pub fn new(id: &str, data: Value, processor: &DataProcessorMapping) -> Result<Self> {
+        let config: ReduceConfig = serde_json::from_value(data)
+            .map_err(|_| Error::ExecutionError("Invalid data format for ReduceNode".into()))?;
+
+        Ok(Self {
+            base: NodeBase::new(id, processor),
+            config,
+        })
+    }
+}
+
+#[impl_executable]
+impl Executable for ReduceNode {
+    async fn core_execute(
+        &self,
+        input: Option<FlowData>,
+        _context: Arc<Context>,
+    ) -> Result<FlowOutput> {
+        let data = input.ok_or_else(|| Error::ExecutionError("No input data provided".into()))?;
+
+        let output = match &self.config {
+            ReduceConfig::Concat { separator } => {
+                FlowData::from(data.as_text_list()?.join(separator))
+            }
+            ReduceConfig::Sum => FlowData::from(data.as_number_list()?.iter().sum::<f64>()),
+            ReduceConfig::MergeJson => merge_json(&data)?,
+            ReduceConfig::Custom { function } => {
+                let reducer = REDUCER_REGISTRY.get(function).ok_or_else(|| {
+                    Error::ExecutionError(
+                        format!("Reducer `{}` is not registered", function).into(),
+                    )
+                })?;
+                reducer(items(&data))?
+            }
+        };
+
+        Ok(output.into())
+    }
+}
+
+/// 集合元素，单值视为单元素集合
+fn items(data: &FlowData) -> &[SingleData] {
+    match data {
+        FlowData::Single(item) => std::slice::from_ref(item),
+        FlowData::Collection(items) => items,
+    }
+}
+
+/// 浅合并集合中的 JSON 对象
+pub(crate) fn merge_json(data: &FlowData) -> Result<FlowData> {
+    let mut merged = Map::new();
+    for item in items(data) {
+        match item {
+            SingleData::Json(Value::Object(object)) => merged.extend(object.clone()),
+            _ => return Err(Error::FlowTypeMismatch),
+        }
+    }
+
+    Ok(FlowData::from(Value::Object(merged)))
+}
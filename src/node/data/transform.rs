@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use flow_data::{FlowData, SingleData, output::FlowOutput};
+use serde_json::{Map, Value};
+use workflow_error::{Error, Result};
+use workflow_macro::impl_executable;
+
+use crate::{
+    expr::{
+        json_path::{self, Segment},
+        to_number, to_text,
+    },
+    model::{context::Context, node::DataProcessorMapping},
+    node::{
+        Executable, NodeBase,
+        config::{TransformConfig, TransformMapping, TransformOutput},
+    },
+};
+
+/// 已解析的选取方式
+#[derive(Debug, Clone)]
+enum Mapping {
+    Select(String, Vec<Segment>),
+    Shape(Shape),
+}
+
+/// 已解析的重组模板
+#[derive(Debug, Clone)]
+enum Shape {
+    Path(Vec<Segment>),
+    Object(Vec<(String, Shape)>),
+    Array(Vec<Shape>),
+    Literal(Value),
+}
+
+/// TransformNode：从 JSON 输入中取路径或按模板重组，结果按 `output` 转为 FlowData
+#[derive(Debug, Clone)]
+pub struct TransformNode {
+    base: NodeBase,
+    mapping: Mapping,
+    default: Option<Value>,
+    output: TransformOutput,
+}
+
+impl TransformNode {
+    pub fn new(id: &str, data: Value, processor: &DataProcessorMapping) -> Result<Self> {
+        let config: TransformConfig = serde_json::from_value(data)
+            .map_err(|_| Error::ExecutionError("Invalid data format for TransformNode".into()))?;
+
+        let mapping = match config.mapping {
+            TransformMapping::Select { select } => {
+                let segments = json_path::parse(&select)?;
+                Mapping::Select(select, segments)
+            }
+            TransformMapping::Shape { shape } => Mapping::Shape(parse_shape(shape)?),
+        };
+
+        Ok(Self {
+            base: NodeBase::new(id, processor),
+            mapping,
+            default: config.default,
+            output: config.output,
+        })
+    }
+
+    fn apply(&self, input: &Value) -> Result<Value> {
+        match &self.mapping {
+            Mapping::Select(path, segments) => json_path::query(input, segments)
+                .or_else(|| self.default.clone())
+                .ok_or_else(|| {
+                    Error::ExecutionError(format!("Path `{}` not found in input", path).into())
+                }),
+            Mapping::Shape(shape) => Ok(self.build(shape, input)),
+        }
+    }
+
+    fn build(&self, shape: &Shape, input: &Value) -> Value {
+        match shape {
+            Shape::Path(segments) => json_path::query(input, segments)
+                .or_else(|| self.default.clone())
+                .unwrap_or(Value::Null),
+            Shape::Object(fields) => Value::Object(
+                fields
+                    .iter()
+                    .map(|(key, field)| (key.clone(), self.build(field, input)))
+                    .collect::<Map<_, _>>(),
+            ),
+            Shape::Array(items) => {
+                Value::Array(items.iter().map(|item| self.build(item, input)).collect())
+            }
+            Shape::Literal(value) => value.clone(),
+        }
+    }
+
+    fn convert(&self, value: Value) -> Result<FlowData> {
+        match self.output {
+            TransformOutput::Auto => Ok(FlowData::from_json_value(value)),
+            TransformOutput::Text => Ok(FlowData::from(to_text(&value))),
+            TransformOutput::Number => to_number(&value).map(FlowData::from).ok_or_else(|| {
+                Error::ExecutionError(format!("Cannot convert {} to a number", value).into())
+            }),
+            TransformOutput::Json => Ok(FlowData::Single(SingleData::Json(value))),
+        }
+    }
+}
+
+/// 构建期解析模板中的全部路径
+fn parse_shape(value: Value) -> Result<Shape> {
+    Ok(match value {
+        Value::String(path) => Shape::Path(json_path::parse(&path)?),
+        Value::Object(map) => Shape::Object(
+            map.into_iter()
+                .map(|(key, field)| Ok((key, parse_shape(field)?)))
+                .collect::<Result<_>>()?,
+        ),
+        Value::Array(items) => {
+            Shape::Array(items.into_iter().map(parse_shape).collect::<Result<_>>()?)
+        }
+        literal => Shape::Literal(literal),
+    })
+}
+
+#[impl_executable]
+impl Executable for TransformNode {
+    async fn core_execute(
+        &self,
+        input: Option<FlowData>,
+        _context: Arc<Context>,
+    ) -> Result<FlowOutput> {
+        let input = match input {
+            None => return Err(Error::ExecutionError("No input data provided".into())),
+            Some(FlowData::Single(SingleData::Json(value))) => value,
+            // 文本形式的 JSON（如未解析的响应体）
+            Some(FlowData::Single(SingleData::Text(text))) => {
+                serde_json::from_str(&text).map_err(|_| Error::FlowTypeMismatch)?
+            }
+            Some(_) => return Err(Error::FlowTypeMismatch),
+        };
+
+        let value = self.apply(&input)?;
+        Ok(self.convert(value)?.into())
+    }
+}
@@ -0,0 +1,204 @@
+use serde_json::{Map, Value};
+use workflow_error::{Error, Result};
+
+const TYPES: [&str; 7] = [
+    "string", "number", "integer", "boolean", "object", "array", "null",
+];
+
+/// 检查 JSON Schema 本身是否可用：必须为对象，`type` 只能取 JSON Schema 的基本类型
+///
+/// 支持的关键字：`type`、`enum`、`const`、`anyOf`、`properties`、`required`、
+/// `additionalProperties`、`items`、`minItems`、`maxItems`、`minLength`、`maxLength`、
+/// `minimum`、`maximum`、`exclusiveMinimum`、`exclusiveMaximum`，其余关键字忽略
+pub fn check(schema: &Value) -> Result<()> {
+    let invalid =
+        |reason: String| Error::ExecutionError(format!("Invalid JSON Schema: {}", reason).into());
+
+    let Value::Object(schema) = schema else {
+        return Err(invalid(format!("expected an object, found {}", schema)));
+    };
+    for name in types(schema) {
+        if !TYPES.contains(&name) {
+            return Err(invalid(format!("unknown type `{}`", name)));
+        }
+    }
+    if let Some(Value::Object(properties)) = schema.get("properties") {
+        properties.values().try_for_each(check)?;
+    }
+    for key in ["items", "additionalProperties"] {
+        if let Some(sub @ Value::Object(_)) = schema.get(key) {
+            check(sub)?;
+        }
+    }
+    if let Some(Value::Array(options)) = schema.get("anyOf") {
+        options.iter().try_for_each(check)?;
+    }
+    Ok(())
+}
+
+/// 按 `schema` 校验 `value`，返回第一处不符合的位置与原因，如 "`$.items[0]`: expected string, found number"
+pub fn validate(schema: &Value, value: &Value) -> std::result::Result<(), String> {
+    validate_at(schema, value, "$")
+}
+
+fn validate_at(schema: &Value, value: &Value, path: &str) -> std::result::Result<(), String> {
+    let Value::Object(schema) = schema else {
+        return Ok(());
+    };
+    let fail = |reason: String| Err(format!("`{}`: {}", path, reason));
+
+    let types = types(schema);
+    if !types.is_empty() && !types.iter().any(|name| is_type(value, name)) {
+        return fail(format!(
+            "expected {}, found {}",
+            types.join(" or "),
+            type_name(value)
+        ));
+    }
+    if let Some(Value::Array(options)) = schema.get("enum")
+        && !options.contains(value)
+    {
+        return fail(format!("expected one of {}", Value::Array(options.clone())));
+    }
+    if let Some(expected) = schema.get("const")
+        && expected != value
+    {
+        return fail(format!("expected {}", expected));
+    }
+    if let Some(Value::Array(options)) = schema.get("anyOf")
+        && !options
+            .iter()
+            .any(|option| validate_at(option, value, path).is_ok())
+    {
+        return fail("does not match any schema in `anyOf`".to_string());
+    }
+
+    match value {
+        Value::Object(object) => validate_object(schema, object, path),
+        Value::Array(items) => {
+            check_bounds(schema, items.len(), "minItems", "maxItems", "items")
+                .map_or(Ok(()), fail)?;
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate_at(item_schema, item, &format!("{}[{}]", path, i))?;
+                }
+            }
+            Ok(())
+        }
+        Value::String(text) => check_bounds(
+            schema,
+            text.chars().count(),
+            "minLength",
+            "maxLength",
+            "characters",
+        )
+        .map_or(Ok(()), fail),
+        Value::Number(number) => {
+            let n = number.as_f64().unwrap_or_default();
+            let limit = |key| schema.get(key).and_then(Value::as_f64);
+            if let Some(min) = limit("minimum")
+                && n < min
+            {
+                return fail(format!("must be at least {}", min));
+            }
+            if let Some(max) = limit("maximum")
+                && n > max
+            {
+                return fail(format!("must be at most {}", max));
+            }
+            if let Some(min) = limit("exclusiveMinimum")
+                && n <= min
+            {
+                return fail(format!("must be greater than {}", min));
+            }
+            if let Some(max) = limit("exclusiveMaximum")
+                && n >= max
+            {
+                return fail(format!("must be less than {}", max));
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn validate_object(
+    schema: &Map<String, Value>,
+    object: &Map<String, Value>,
+    path: &str,
+) -> std::result::Result<(), String> {
+    if let Some(Value::Array(required)) = schema.get("required") {
+        for key in required.iter().filter_map(Value::as_str) {
+            if !object.contains_key(key) {
+                return Err(format!("`{}`: missing required property `{}`", path, key));
+            }
+        }
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (key, item) in object {
+        let item_path = format!("{}.{}", path, key);
+        match (
+            properties.and_then(|p| p.get(key)),
+            schema.get("additionalProperties"),
+        ) {
+            (Some(item_schema), _) => validate_at(item_schema, item, &item_path)?,
+            (None, Some(Value::Bool(false))) => {
+                return Err(format!("`{}`: unexpected property `{}`", path, key));
+            }
+            (None, Some(extra @ Value::Object(_))) => validate_at(extra, item, &item_path)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// 长度或元素数的上下限，不满足时返回原因
+fn check_bounds(
+    schema: &Map<String, Value>,
+    len: usize,
+    min_key: &str,
+    max_key: &str,
+    unit: &str,
+) -> Option<String> {
+    let limit = |key| schema.get(key).and_then(Value::as_u64);
+    if let Some(min) = limit(min_key)
+        && (len as u64) < min
+    {
+        return Some(format!("expected at least {} {}, found {}", min, unit, len));
+    }
+    if let Some(max) = limit(max_key)
+        && (len as u64) > max
+    {
+        return Some(format!("expected at most {} {}, found {}", max, unit, len));
+    }
+    None
+}
+
+/// `type` 可以是单个类型名或类型名数组
+fn types(schema: &Map<String, Value>) -> Vec<&str> {
+    match schema.get("type") {
+        Some(Value::String(name)) => vec![name.as_str()],
+        Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn is_type(value: &Value, name: &str) -> bool {
+    match name {
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "number" => value.is_number(),
+        other => type_name(value) == other,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
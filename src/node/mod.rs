@@ -1,9 +1,11 @@
 pub mod base;
 pub mod builder;
+pub mod condition;
 pub mod config;
 pub mod control;
 pub mod data;
 pub mod executable;
+pub mod json_schema;
 
 pub use base::NodeBase;
 pub use executable::Executable;
@@ -1,6 +1,10 @@
 pub mod implementations;
+pub mod reducer;
 pub mod registry;
+pub mod tool;
 pub mod traits;
 
+pub use reducer::{REDUCER_REGISTRY, Reducer, ReducerRegistry};
 pub use registry::{PROCESSOR_REGISTRY, ProcessorRegistry, register_default_processors};
+pub use tool::{TOOL_REGISTRY, ToolFunction, ToolRegistry};
 pub use traits::{InputProcessor, OutputProcessor, Processor};
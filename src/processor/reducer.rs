@@ -0,0 +1,31 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use flow_data::{FlowData, SingleData};
+use once_cell::sync::Lazy;
+use workflow_error::Result;
+
+/// 自定义归约函数：集合元素 → 归约结果
+pub type Reducer = Arc<dyn Fn(&[SingleData]) -> Result<FlowData> + Send + Sync>;
+
+/// Reducer Registry，供 Reduce 节点的 `custom` 策略按名称查找
+#[derive(Default)]
+pub struct ReducerRegistry {
+    reducers: Mutex<HashMap<String, Reducer>>,
+}
+
+impl ReducerRegistry {
+    pub fn register(&self, name: &str, reducer: Reducer) {
+        let mut reducers = self.reducers.lock().unwrap();
+        reducers.insert(name.to_string(), reducer);
+    }
+
+    pub fn get(&self, name: &str) -> Option<Reducer> {
+        let reducers = self.reducers.lock().unwrap();
+        reducers.get(name).cloned()
+    }
+}
+
+pub static REDUCER_REGISTRY: Lazy<ReducerRegistry> = Lazy::new(ReducerRegistry::default);
@@ -0,0 +1,31 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use workflow_error::Result;
+
+/// 工具函数：JSON 参数 → JSON 结果
+pub type ToolFunction = Arc<dyn Fn(Value) -> Result<Value> + Send + Sync>;
+
+/// Tool Registry，供 Agent 节点的 `function` 工具按名称查找
+#[derive(Default)]
+pub struct ToolRegistry {
+    tools: Mutex<HashMap<String, ToolFunction>>,
+}
+
+impl ToolRegistry {
+    pub fn register(&self, name: &str, tool: ToolFunction) {
+        let mut tools = self.tools.lock().unwrap();
+        tools.insert(name.to_string(), tool);
+    }
+
+    pub fn get(&self, name: &str) -> Option<ToolFunction> {
+        let tools = self.tools.lock().unwrap();
+        tools.get(name).cloned()
+    }
+}
+
+pub static TOOL_REGISTRY: Lazy<ToolRegistry> = Lazy::new(ToolRegistry::default);
@@ -0,0 +1,159 @@
+pub mod ollama;
+pub mod openai;
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use flow_data::StreamFormat;
+use model_gateway_rs::{
+    error::Result as GatewayResult,
+    model::{
+        llm::{LlmInput, LlmOutput},
+        vision::{VisionInput, VisionOutput},
+    },
+    traits::{ModelClient, StreamModelClient},
+};
+use once_cell::sync::Lazy;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use toolcraft_request::{Request, error::Error as RequestError};
+use workflow_error::{Error, Result};
+
+pub use ollama::OllamaChatClient;
+pub use openai::OpenAiChatClient;
+
+use crate::node::config::{ModelSettings, Provider};
+
+/// LLM 推理客户端
+pub type LlmModelClient = Arc<dyn ModelClient<LlmInput, LlmOutput> + Send + Sync>;
+
+/// LLM 流式推理客户端
+pub type LlmStreamClient = Arc<dyn StreamModelClient<LlmInput> + Send + Sync>;
+
+/// 多模态推理客户端，输入含图片或视频时使用
+pub type LlmVisionClient = Arc<dyn ModelClient<VisionInput, VisionOutput> + Send + Sync>;
+
+/// 多模态流式推理客户端
+pub type LlmVisionStreamClient = Arc<dyn StreamModelClient<VisionInput> + Send + Sync>;
+
+/// 按 provider 创建的一组推理客户端
+pub struct ChatClients {
+    pub model: LlmModelClient,
+    pub stream: LlmStreamClient,
+    /// provider 不支持图片与视频输入时为 None
+    pub vision: Option<(LlmVisionClient, LlmVisionStreamClient)>,
+    /// 流式输出的格式
+    pub stream_format: StreamFormat,
+}
+
+/// 按模型设置创建推理客户端
+pub fn chat_clients(settings: &ModelSettings) -> Result<ChatClients> {
+    match settings.provider.unwrap_or_default() {
+        Provider::OpenAi => {
+            let client = Arc::new(OpenAiChatClient::new(settings)?);
+            Ok(ChatClients {
+                model: client.clone(),
+                stream: client.clone(),
+                vision: Some((client.clone(), client)),
+                stream_format: StreamFormat::OpenAiSse,
+            })
+        }
+        Provider::Ollama => {
+            let client = Arc::new(OllamaChatClient::new(settings)?);
+            Ok(ChatClients {
+                model: client.clone(),
+                stream: client,
+                vision: None,
+                stream_format: StreamFormat::OllamaNdjson,
+            })
+        }
+    }
+}
+
+/// Model Profile Registry：按名称保存模型设置，LLM 节点以 `"model": 名称` 引用
+#[derive(Default)]
+pub struct ModelProfileRegistry {
+    profiles: Mutex<HashMap<String, ModelSettings>>,
+}
+
+impl ModelProfileRegistry {
+    pub fn register(&self, name: &str, settings: ModelSettings) {
+        let mut profiles = self.profiles.lock().unwrap();
+        profiles.insert(name.to_string(), settings);
+    }
+
+    pub fn get(&self, name: &str) -> Result<ModelSettings> {
+        let profiles = self.profiles.lock().unwrap();
+        profiles.get(name).cloned().ok_or_else(|| {
+            Error::ExecutionError(format!("Model profile `{}` is not registered", name).into())
+        })
+    }
+
+    /// 取配置档案（未指定时为空设置），再以节点中的设置覆盖
+    pub fn resolve(
+        &self,
+        profile: Option<&str>,
+        overrides: &ModelSettings,
+    ) -> Result<ModelSettings> {
+        let base = match profile {
+            Some(name) => self.get(name)?,
+            None => ModelSettings::default(),
+        };
+        Ok(base.merge(overrides))
+    }
+}
+
+pub static MODEL_PROFILES: Lazy<ModelProfileRegistry> = Lazy::new(ModelProfileRegistry::default);
+
+/// 创建带默认请求头的 HTTP 客户端
+fn http_request(base_url: &str, api_key: Option<&str>) -> Result<Request> {
+    let mut headers = vec![("Content-Type", "application/json".to_string())];
+    if let Some(key) = api_key.filter(|key| !key.is_empty()) {
+        headers.push(("Authorization", format!("Bearer {key}")));
+    }
+    let mut request = Request::new().map_err(request_error)?;
+    request.set_base_url(base_url).map_err(request_error)?;
+    request
+        .set_default_headers(headers)
+        .map_err(request_error)?;
+    Ok(request)
+}
+
+fn request_error(e: toolcraft_request::error::Error) -> Error {
+    Error::ExecutionError(format!("Failed to create LLM client: {}", e).into())
+}
+
+fn require_model(settings: &ModelSettings) -> Result<String> {
+    settings.model.clone().ok_or_else(|| {
+        Error::ExecutionError(
+            "LLM model is not set: set `modelName` or reference a profile with `model`".into(),
+        )
+    })
+}
+
+/// 发送 JSON 请求，非 2xx 响应连同响应体作为错误返回
+async fn post_json<T: DeserializeOwned>(
+    request: &Request,
+    endpoint: &str,
+    body: &Value,
+) -> GatewayResult<T> {
+    let response = request.post(endpoint, body, None).await?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(RequestError::ErrorMessage(
+            format!("Unexpected status {}: {}", status, text).into(),
+        )
+        .into());
+    }
+    Ok(response.json().await?)
+}
+
+/// 值存在时写入请求体
+fn set_option<T: Serialize>(body: &mut Value, key: &str, value: Option<T>) {
+    if let Some(value) = value.and_then(|v| serde_json::to_value(v).ok()) {
+        body[key] = value;
+    }
+}
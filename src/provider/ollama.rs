@@ -0,0 +1,83 @@
+use async_trait::async_trait;
+use model_gateway_rs::{
+    error::Result as GatewayResult,
+    model::{
+        llm::{LlmInput, LlmOutput},
+        ollama::OllamaChatResponse,
+    },
+    traits::{ModelClient, StreamModelClient},
+};
+use serde_json::{Map, Value, json};
+use toolcraft_request::{ByteStream, Request};
+use workflow_error::Result;
+
+use super::{http_request, post_json, require_model, set_option};
+use crate::node::config::ModelSettings;
+
+const DEFAULT_BASE_URL: &str = "http://localhost:11434/api/";
+
+/// Ollama `chat` 接口的客户端，采样参数放在 `options` 中，流式输出为 NDJSON
+pub struct OllamaChatClient {
+    request: Request,
+    model: String,
+    settings: ModelSettings,
+}
+
+impl OllamaChatClient {
+    pub fn new(settings: &ModelSettings) -> Result<Self> {
+        Ok(Self {
+            request: http_request(
+                settings.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL),
+                settings.api_key.as_deref(),
+            )?,
+            model: require_model(settings)?,
+            settings: settings.clone(),
+        })
+    }
+
+    fn body(&self, input: &LlmInput, stream: bool) -> Value {
+        let mut options = Value::Object(Map::new());
+        set_option(&mut options, "temperature", self.settings.temperature);
+        set_option(&mut options, "top_p", self.settings.top_p);
+        set_option(
+            &mut options,
+            "num_predict",
+            input.max_tokens.or(self.settings.max_tokens),
+        );
+        set_option(&mut options, "stop", self.settings.stop.as_ref());
+
+        let mut body = json!({
+            "model": self.model,
+            "messages": input.messages,
+            "stream": stream,
+        });
+        if options.as_object().is_some_and(|o| !o.is_empty()) {
+            body["options"] = options;
+        }
+        body
+    }
+}
+
+#[async_trait]
+impl ModelClient<LlmInput, LlmOutput> for OllamaChatClient {
+    async fn infer(&self, input: LlmInput) -> GatewayResult<LlmOutput> {
+        let body = self.body(&input, false);
+        let response: OllamaChatResponse = post_json(&self.request, "chat", &body).await?;
+        let usage = match (response.prompt_eval_count, response.eval_count) {
+            (None, None) => None,
+            (prompt, eval) => Some(prompt.unwrap_or(0) + eval.unwrap_or(0)),
+        };
+        Ok(LlmOutput {
+            message: Some(response.message),
+            usage,
+        })
+    }
+}
+
+#[async_trait]
+impl StreamModelClient<LlmInput> for OllamaChatClient {
+    async fn infer_stream(&self, input: LlmInput) -> GatewayResult<ByteStream> {
+        let body = self.body(&input, true);
+        Ok(self.request.post_stream("chat", &body, None).await?)
+    }
+}
@@ -0,0 +1,100 @@
+use async_trait::async_trait;
+use model_gateway_rs::{
+    error::Result as GatewayResult,
+    model::{
+        doubao_vision::DoubaoVisionResponse,
+        llm::{LlmInput, LlmOutput},
+        openai::OpenAiChatResponse,
+        vision::{VisionInput, VisionOutput},
+    },
+    traits::{ModelClient, StreamModelClient},
+};
+use serde::Serialize;
+use serde_json::{Value, json};
+use toolcraft_request::{ByteStream, Request};
+use workflow_error::Result;
+
+use super::{http_request, post_json, require_model, set_option};
+use crate::node::config::ModelSettings;
+
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1/";
+
+/// OpenAI 兼容 `chat/completions` 接口的客户端，请求带上采样参数；
+/// 多模态消息以同一接口的内容片段格式发送
+pub struct OpenAiChatClient {
+    request: Request,
+    model: String,
+    settings: ModelSettings,
+}
+
+impl OpenAiChatClient {
+    pub fn new(settings: &ModelSettings) -> Result<Self> {
+        Ok(Self {
+            request: http_request(
+                settings.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL),
+                settings.api_key.as_deref(),
+            )?,
+            model: require_model(settings)?,
+            settings: settings.clone(),
+        })
+    }
+
+    /// 请求体：`LlmInput` 中的 `max_tokens` 优先于设置
+    fn body<M: Serialize>(&self, messages: &[M], max_tokens: Option<u32>, stream: bool) -> Value {
+        let mut body = json!({ "model": self.model, "messages": messages });
+        set_option(&mut body, "temperature", self.settings.temperature);
+        set_option(&mut body, "top_p", self.settings.top_p);
+        set_option(
+            &mut body,
+            "max_tokens",
+            max_tokens.or(self.settings.max_tokens),
+        );
+        set_option(&mut body, "stop", self.settings.stop.as_ref());
+        if stream {
+            body["stream"] = json!(true);
+        }
+        body
+    }
+}
+
+#[async_trait]
+impl ModelClient<LlmInput, LlmOutput> for OpenAiChatClient {
+    async fn infer(&self, input: LlmInput) -> GatewayResult<LlmOutput> {
+        let body = self.body(&input.messages, input.max_tokens, false);
+        let response: OpenAiChatResponse =
+            post_json(&self.request, "chat/completions", &body).await?;
+        Ok(response.into())
+    }
+}
+
+#[async_trait]
+impl StreamModelClient<LlmInput> for OpenAiChatClient {
+    async fn infer_stream(&self, input: LlmInput) -> GatewayResult<ByteStream> {
+        let body = self.body(&input.messages, input.max_tokens, true);
+        Ok(self
+            .request
+            .post_stream("chat/completions", &body, None)
+            .await?)
+    }
+}
+
+#[async_trait]
+impl ModelClient<VisionInput, VisionOutput> for OpenAiChatClient {
+    async fn infer(&self, input: VisionInput) -> GatewayResult<VisionOutput> {
+        let body = self.body(&input.messages, None, false);
+        let response: DoubaoVisionResponse =
+            post_json(&self.request, "chat/completions", &body).await?;
+        Ok(response.into())
+    }
+}
+
+#[async_trait]
+impl StreamModelClient<VisionInput> for OpenAiChatClient {
+    async fn infer_stream(&self, input: VisionInput) -> GatewayResult<ByteStream> {
+        let body = self.body(&input.messages, None, true);
+        Ok(self
+            .request
+            .post_stream("chat/completions", &body, None)
+            .await?)
+    }
+}
@@ -1,16 +1,30 @@
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     sync::Arc,
+    time::{Duration, Instant},
 };
 
 use flow_data::{
     FlowData, FlowOutputType,
-    output::{ControlFlow, FlowOutput},
+    output::{ControlFlow, FlowOutput, FlowOutputValue},
 };
 use workflow_error::{Error, Result};
-use workflow_utils::stream_util::forward_and_collect_stream;
+use workflow_utils::stream_util::{collect_stream, forward_and_collect_stream};
 
-use crate::{graph::Graph, model::Context, types::StreamSender};
+use crate::{
+    graph::Graph,
+    model::Context,
+    node::{base::NodeState, builder::build_node},
+    types::StreamSender,
+};
+
+/// 单个节点在一次运行中的执行记录
+#[derive(Debug, Clone)]
+pub struct NodeRun {
+    pub state: NodeState,
+    /// 执行耗时，节点未执行完成时为 None
+    pub elapsed: Option<Duration>,
+}
 
 /// Runner 负责调度节点执行，管理节点间的数据传递与控制流
 pub struct Runner {
@@ -19,6 +33,20 @@ pub struct Runner {
     input_refs: HashMap<String, String>,
     queue: VecDeque<String>,
     pending_predecessors: HashMap<String, usize>,
+    node_runs: HashMap<String, NodeRun>,
+    loop_counts: HashMap<String, usize>,
+    /// 任一前驱完成即可执行的节点
+    wait_any: HashSet<String>,
+    /// 已被调度过的 `wait_any` 节点
+    triggered: HashSet<String>,
+    /// 运行所属的会话 ID，传给 Context 供会话记忆使用
+    session_id: Option<String>,
+}
+
+impl Default for Runner {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Runner {
@@ -29,16 +57,24 @@ impl Runner {
             input_refs: HashMap::new(),
             queue: VecDeque::new(),
             pending_predecessors: HashMap::new(),
+            node_runs: HashMap::new(),
+            loop_counts: HashMap::new(),
+            wait_any: HashSet::new(),
+            triggered: HashSet::new(),
+            session_id: None,
         }
     }
 
+    /// 指定运行所属的会话，同一会话的多次运行共享 LLM 节点的会话记忆
+    pub fn with_session(mut self, session_id: &str) -> Self {
+        self.session_id = Some(session_id.to_string());
+        self
+    }
+
     /// 设置输入数据
     pub fn set_input(&mut self, node_id: &str, input: Option<FlowData>) {
-        match input {
-            Some(data) => {
-                self.inputs.insert(node_id.to_string(), data);
-            }
-            None => return,
+        if let Some(data) = input {
+            self.inputs.insert(node_id.to_string(), data);
         }
     }
 
@@ -61,14 +97,24 @@ impl Runner {
             .ok_or_else(|| Error::NodeNotFound(node_id.to_string().into()))
     }
 
+    /// 获取本次运行中各节点的执行记录
+    pub fn node_runs(&self) -> &HashMap<String, NodeRun> {
+        &self.node_runs
+    }
+
+    /// 获取本次运行中各循环回边的回跳次数：边 ID → 次数
+    pub fn loop_counts(&self) -> &HashMap<String, usize> {
+        &self.loop_counts
+    }
+
     pub fn get_resolved_input(&self, node_id: &str) -> Option<FlowData> {
         if let Some(data) = self.inputs.get(node_id) {
             return Some(data.clone());
         }
-        if let Some(source_id) = self.input_refs.get(node_id) {
-            if let Some(data) = self.outputs.get(source_id) {
-                return Some(data.clone());
-            }
+        if let Some(source_id) = self.input_refs.get(node_id)
+            && let Some(data) = self.outputs.get(source_id)
+        {
+            return Some(data.clone());
         }
         None
     }
@@ -81,10 +127,20 @@ impl Runner {
         stream_tx: Option<StreamSender>,
     ) -> Result<FlowData> {
         graph.compile()?;
-        let context = Context::from_graph(graph);
+        let context = match &self.session_id {
+            Some(session_id) => Context::with_session(graph, session_id),
+            None => Context::from_graph(graph),
+        };
+        // Context 会略过无法实例化的节点，在此报告构建错误，而不是执行到该节点时才报 NodeNotFound
+        for (id, node) in &graph.nodes {
+            if context.get_node(id).is_none() {
+                build_node(node)?;
+            }
+        }
         self.prepare(graph, input)?;
         self.execute_all_nodes(graph, context, stream_tx).await?;
-        let output = self.get_output("end")?;
+        let end_node = graph.end_node.as_deref().unwrap_or("end");
+        let output = self.get_output(end_node)?;
         Ok(output.clone())
     }
 
@@ -95,13 +151,25 @@ impl Runner {
         self.inputs.clear();
         self.outputs.clear();
         self.input_refs.clear();
+        self.node_runs.clear();
+        self.loop_counts.clear();
+        self.wait_any = graph.wait_any_nodes();
+        self.triggered.clear();
 
+        // 优先把 input 交给起始节点
+        if let Some(start) = &graph.start_node
+            && let Some(data) = input.take()
+        {
+            self.inputs.insert(start.clone(), data);
+        }
+
+        let embedded = graph.embedded_nodes();
         for node_id in graph.nodes.keys() {
             let pred_count = graph.predecessors.get(node_id).map_or(0, |s| s.len());
             self.pending_predecessors
                 .insert(node_id.clone(), pred_count);
 
-            if pred_count == 0 {
+            if pred_count == 0 && !embedded.contains(node_id) {
                 // 只把 input 塞给第一个没有前驱的节点
                 if let Some(data) = input.take() {
                     self.inputs.insert(node_id.clone(), data);
@@ -114,15 +182,56 @@ impl Runner {
         Ok(())
     }
 
+    /// 标记未被激活的分支节点为跳过，并释放其后继对它的等待
+    ///
+    /// 后继的前驱全部被跳过时继续向下跳过；否则在不再等待时入队
     fn mark_branch_skipped(&mut self, node_id: &str, graph: &Graph) {
-        if let Some(successors) = graph.successors.get(node_id) {
-            for succ in successors {
-                if let Some(pred_count) = self.pending_predecessors.get_mut(succ) {
-                    if *pred_count > 0 {
-                        *pred_count -= 1;
-                    }
-                }
+        self.node_runs
+            .entry(node_id.to_string())
+            .or_insert(NodeRun {
+                state: NodeState::Skipped,
+                elapsed: None,
+            });
+        let Some(successors) = graph.successors.get(node_id) else {
+            return;
+        };
+        for succ in successors {
+            let Some(pred_count) = self.pending_predecessors.get_mut(succ) else {
+                continue;
+            };
+            if *pred_count == 0 {
+                continue;
+            }
+            *pred_count -= 1;
+            if *pred_count > 0 {
+                continue;
             }
+
+            let all_skipped = graph.predecessors.get(succ).is_some_and(|preds| {
+                preds.iter().all(|pred| {
+                    self.node_runs
+                        .get(pred)
+                        .is_some_and(|run| matches!(run.state, NodeState::Skipped))
+                })
+            });
+            if all_skipped {
+                self.mark_branch_skipped(succ, graph);
+            } else if !self.queue.contains(succ) {
+                self.schedule(succ, true);
+            }
+        }
+    }
+
+    /// 前驱完成后调度节点：`ready` 表示不再等待其他前驱
+    ///
+    /// `wait_any` 中的节点忽略 `ready`，在首个前驱完成时插到队首立即执行，且只调度一次
+    fn schedule(&mut self, node_id: &str, ready: bool) {
+        if !self.wait_any.contains(node_id) {
+            if ready {
+                self.queue.push_back(node_id.to_string());
+            }
+        } else if self.triggered.insert(node_id.to_string()) {
+            self.queue.push_front(node_id.to_string());
         }
     }
 
@@ -140,10 +249,34 @@ impl Runner {
                 .get_node(&current)
                 .ok_or_else(|| Error::NodeNotFound(current.clone().into()))?;
 
-            let output = node.execute(input_value, context.clone()).await?;
+            self.node_runs.insert(
+                current.clone(),
+                NodeRun {
+                    state: NodeState::Running,
+                    elapsed: None,
+                },
+            );
+            let started = Instant::now();
+            let result = node.execute(input_value, context.clone()).await;
+            let state = if result.is_ok() {
+                NodeState::Completed
+            } else {
+                NodeState::Failed
+            };
+            self.node_runs.insert(
+                current.clone(),
+                NodeRun {
+                    state,
+                    elapsed: Some(started.elapsed()),
+                },
+            );
+            let output = result?;
 
             self.handle_output(&current, output, graph, &context, stream_tx.clone())
                 .await?;
+            if let Some(data) = self.outputs.get(&current) {
+                context.record_output(&current, data.clone());
+            }
         }
 
         Ok(())
@@ -165,13 +298,16 @@ impl Runner {
             }
             FlowOutputType::Data => {
                 let data = output.into_data()?;
-                self.handle_data_output(current, data, graph)?;
+                self.handle_data_output(current, data, graph, context)?;
             }
             FlowOutputType::Parallel => {
-                self.handle_parallel_output();
+                let FlowOutputValue::Parallel(flows) = output.value else {
+                    return Err(Error::FlowTypeMismatch);
+                };
+                self.handle_parallel_output(current, flows, graph, context)?;
             }
             FlowOutputType::Stream => {
-                self.handle_stream_output(stream_tx, current, output)
+                self.handle_stream_output(stream_tx, current, output, graph, context)
                     .await?;
             }
         }
@@ -185,22 +321,13 @@ impl Runner {
         graph: &Graph,
         context: &Arc<Context>,
     ) -> Result<()> {
-        let next_node_id = graph
-            .handle_routes
-            .get(&(current.to_owned(), controll.next_node.clone()))
-            .ok_or_else(|| {
-                Error::ExecutionError(
-                    format!(
-                        "No target node found for source '{}' with handle '{}'",
-                        current, controll.next_node
-                    )
-                    .into(),
-                )
-            })?;
+        let next_node_id = route(current, &controll.next_node, graph)?;
 
         if let Some(successors) = graph.successors.get(current) {
             for succ in successors {
-                if let Some(p) = self.pending_predecessors.get_mut(succ) {
+                if let Some(p) = self.pending_predecessors.get_mut(succ)
+                    && *p > 0
+                {
                     *p -= 1;
                 }
                 if succ != next_node_id {
@@ -210,7 +337,7 @@ impl Runner {
         }
         self.set_output(current, controll.data.clone());
         if context.get_node(next_node_id).is_some() {
-            self.queue.push_back(next_node_id.to_string());
+            self.schedule(next_node_id, true);
             self.input_refs
                 .insert(next_node_id.to_string(), current.to_string());
         }
@@ -222,8 +349,12 @@ impl Runner {
         current: &str,
         data_payload: FlowData,
         graph: &Graph,
+        context: &Arc<Context>,
     ) -> Result<()> {
         self.set_output(current, data_payload.clone());
+        if self.take_loop_edge(current, &data_payload, graph, context) {
+            return Ok(());
+        }
         if let Some(successors) = graph.successors.get(current) {
             for next_node_id in successors {
                 let pred_count = self
@@ -233,35 +364,193 @@ impl Runner {
                 if *pred_count > 0 {
                     *pred_count -= 1;
                 }
+                let ready = *pred_count == 0;
                 self.input_refs
                     .insert(next_node_id.clone(), current.to_string());
-                if *pred_count == 0 {
-                    self.queue.push_back(next_node_id.clone());
-                }
+                self.schedule(next_node_id, ready);
             }
         }
         Ok(())
     }
 
-    fn handle_parallel_output(&self) {
-        todo!()
+    /// 检查 `current` 的循环回边，守卫允许时重新调度循环体并返回 true
+    ///
+    /// 回跳时不触发 `current` 的后继节点，直到守卫不再允许回跳
+    fn take_loop_edge(
+        &mut self,
+        current: &str,
+        output: &FlowData,
+        graph: &Graph,
+        context: &Arc<Context>,
+    ) -> bool {
+        let Some(edge_ids) = graph.loop_edges.get(current) else {
+            return false;
+        };
+
+        for edge_id in edge_ids {
+            let Some(edge) = graph.get_edge(edge_id) else {
+                continue;
+            };
+            let Some(guard) = &edge.loop_guard else {
+                continue;
+            };
+
+            let count = self.loop_counts.get(edge_id).copied().unwrap_or(0);
+            if !guard.should_continue(count, output) {
+                continue;
+            }
+            self.loop_counts.insert(edge_id.clone(), count + 1);
+
+            // 循环体内的节点只等待体内前驱，体外前驱已完成不会再次触发
+            let body = graph.loop_body(current, &edge.target);
+            for node_id in &body {
+                self.clear_skipped(node_id);
+                // 上一轮的输出不再可见，避免 Aggregator 等读到未在本轮执行的分支结果
+                context.clear_output(node_id);
+            }
+            for node_id in body.iter().filter(|id| **id != edge.target) {
+                let pending = graph
+                    .predecessors
+                    .get(node_id)
+                    .map_or(0, |preds| preds.intersection(&body).count());
+                self.pending_predecessors.insert(node_id.clone(), pending);
+                self.triggered.remove(node_id);
+            }
+
+            // 体外后继在上一轮可能已被跳过或递减了等待计数，连同被连带跳过的下游一起恢复
+            let mut stack: Vec<&String> = body
+                .iter()
+                .filter_map(|id| graph.successors.get(id))
+                .flatten()
+                .filter(|id| !body.contains(*id))
+                .collect();
+            let mut outside = HashSet::new();
+            while let Some(node_id) = stack.pop() {
+                if outside.insert(node_id.clone()) && self.clear_skipped(node_id) {
+                    stack.extend(graph.successors.get(node_id).into_iter().flatten());
+                }
+            }
+            for node_id in &outside {
+                let pending = graph.predecessors.get(node_id).map_or(0, |preds| {
+                    preds
+                        .iter()
+                        .filter(|pred| {
+                            body.contains(*pred)
+                                || outside.contains(*pred)
+                                || !self.node_runs.contains_key(*pred)
+                        })
+                        .count()
+                });
+                self.pending_predecessors.insert(node_id.clone(), pending);
+                self.triggered.remove(node_id);
+                context.clear_output(node_id);
+            }
+
+            self.inputs.remove(&edge.target);
+            self.input_refs
+                .insert(edge.target.clone(), current.to_string());
+            self.queue.push_back(edge.target.clone());
+            return true;
+        }
+
+        false
+    }
+
+    /// 清除节点的跳过状态，返回节点此前是否被跳过
+    fn clear_skipped(&mut self, node_id: &str) -> bool {
+        let skipped = self
+            .node_runs
+            .get(node_id)
+            .is_some_and(|run| matches!(run.state, NodeState::Skipped));
+        if skipped {
+            self.node_runs.remove(node_id);
+        }
+        skipped
     }
 
+    /// 多路控制流：调度每个句柄对应的分支，未激活的后继标记为跳过
+    fn handle_parallel_output(
+        &mut self,
+        current: &str,
+        flows: Vec<ControlFlow>,
+        graph: &Graph,
+        context: &Arc<Context>,
+    ) -> Result<()> {
+        let mut activated: Vec<(String, FlowData)> = Vec::new();
+        for flow in flows {
+            let target = route(current, &flow.next_node, graph)?;
+            if !activated.iter().any(|(id, _)| id == target) {
+                activated.push((target.clone(), flow.data));
+            }
+        }
+
+        if let Some(successors) = graph.successors.get(current) {
+            for succ in successors {
+                if let Some(p) = self.pending_predecessors.get_mut(succ)
+                    && *p > 0
+                {
+                    *p -= 1;
+                }
+                if !activated.iter().any(|(id, _)| id == succ) {
+                    self.mark_branch_skipped(succ, graph);
+                }
+            }
+        }
+
+        if let Some((_, data)) = activated.first() {
+            self.set_output(current, data.clone());
+        }
+        for (target, data) in activated {
+            if context.get_node(&target).is_none() {
+                continue;
+            }
+            // 各分支的数据可能不同，直接作为输入
+            self.inputs.insert(target.clone(), data);
+            let ready = self
+                .pending_predecessors
+                .get(&target)
+                .is_none_or(|p| *p == 0);
+            self.schedule(&target, ready);
+        }
+        Ok(())
+    }
+
+    /// 读完流输出（有 `stream_tx` 时同时转发），按流的编码格式解码后作为数据输出继续调度
     async fn handle_stream_output(
-        &self,
+        &mut self,
         stream_tx: Option<StreamSender>,
         current: &str,
         output: FlowOutput,
+        graph: &Graph,
+        context: &Arc<Context>,
     ) -> Result<()> {
-        if let Some(tx) = stream_tx.clone() {
-            let stream = output.into_stream()?;
-            let _r = forward_and_collect_stream(stream, tx, current).await?;
-        }
+        let format = output.stream_format().unwrap_or_default();
+        let stream = output.into_stream()?;
+        let collected = match stream_tx {
+            Some(tx) => forward_and_collect_stream(stream, tx, current).await?,
+            None => collect_stream(stream).await?,
+        };
 
-        Ok(())
+        self.handle_data_output(current, format.decode(&collected)?, graph, context)
     }
 }
 
+/// 查找 `current` 的句柄 `handle` 指向的目标节点
+fn route<'g>(current: &str, handle: &str, graph: &'g Graph) -> Result<&'g String> {
+    graph
+        .handle_routes
+        .get(&(current.to_owned(), handle.to_owned()))
+        .ok_or_else(|| {
+            Error::ExecutionError(
+                format!(
+                    "No target node found for source '{}' with handle '{}'",
+                    current, handle
+                )
+                .into(),
+            )
+        })
+}
+
 // /// 合并两个 `DataPayload` 数据，用于累积多个输入数据。
 // pub fn merge_inputs(existing: DataPayload, new_data: DataPayload) -> DataPayload {
 //     let combined = existing.merge(new_data);
@@ -10,6 +10,12 @@ pub struct MockStorage {
     store: Arc<Mutex<HashMap<String, String>>>,
 }
 
+impl Default for MockStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MockStorage {
     pub fn new() -> Self {
         Self {
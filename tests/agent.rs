@@ -0,0 +1,225 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use model_gateway_rs::{
+    model::llm::{ChatMessage, LlmInput, LlmOutput},
+    traits::ModelClient,
+};
+use serde_json::{Value, json};
+use workflow_rs::{
+    graph::{Graph, GraphBuilder},
+    model::{Context, node::DataProcessorMapping},
+    node::{Executable, data::AgentNode},
+    processor::TOOL_REGISTRY,
+};
+
+/// 依次返回预设回复，并记录每次收到的消息
+#[derive(Default)]
+struct Scripted {
+    replies: Mutex<VecDeque<String>>,
+    received: Mutex<Vec<Vec<ChatMessage>>>,
+}
+
+impl Scripted {
+    fn new(replies: &[&str]) -> Arc<Self> {
+        Arc::new(Self {
+            replies: Mutex::new(replies.iter().map(|r| r.to_string()).collect()),
+            ..Default::default()
+        })
+    }
+}
+
+#[async_trait]
+impl ModelClient<LlmInput, LlmOutput> for Scripted {
+    async fn infer(&self, input: LlmInput) -> model_gateway_rs::error::Result<LlmOutput> {
+        self.received.lock().unwrap().push(input.messages);
+        let reply = self.replies.lock().unwrap().pop_front().unwrap_or_default();
+        Ok(LlmOutput {
+            message: Some(ChatMessage::assistant(&reply)),
+            usage: None,
+        })
+    }
+}
+
+fn agent(tools: Value, max_iterations: usize, model: Arc<Scripted>) -> AgentNode {
+    let data = json!({
+        "apiHost": "http://localhost:1/v1/",
+        "apiKey": "test",
+        "modelName": "test",
+        "systemPrompt": "You are helpful.",
+        "tools": tools,
+        "max_iterations": max_iterations,
+    });
+    AgentNode::new("agent", data, &DataProcessorMapping::default())
+        .unwrap()
+        .with_model_client(model)
+}
+
+async fn run(node: AgentNode, context: Arc<Context>) -> Value {
+    node.execute(Some("What is 1 + 2?".into()), context)
+        .await
+        .unwrap()
+        .into_data()
+        .unwrap()
+        .into_json()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn calls_registered_function_then_answers() {
+    TOOL_REGISTRY.register(
+        "agent_add",
+        Arc::new(|args: Value| {
+            Ok(json!(
+                args["a"].as_i64().unwrap() + args["b"].as_i64().unwrap()
+            ))
+        }),
+    );
+    let model = Scripted::new(&[
+        r#"{"tool": "add", "arguments": {"a": 1, "b": 2}}"#,
+        "The answer is 3.",
+    ]);
+    let node = agent(
+        json!([{ "name": "add", "description": "Add two integers", "type": "function", "function": "agent_add" }]),
+        4,
+        model.clone(),
+    );
+
+    let output = run(node, Context::from_graph(&Graph::new())).await;
+
+    assert_eq!(output["answer"], "The answer is 3.");
+    assert_eq!(output["stop_reason"], "final_answer");
+    assert_eq!(output["iterations"], 2);
+    assert_eq!(
+        output["tool_calls"],
+        json!([{ "tool": "add", "arguments": { "a": 1, "b": 2 }, "result": 3 }])
+    );
+    // system, user, assistant(调用), user(结果), assistant(回答)
+    assert_eq!(output["messages"].as_array().unwrap().len(), 5);
+
+    let received = model.received.lock().unwrap();
+    assert!(received[0][0].content.contains("- add: Add two integers"));
+    assert_eq!(
+        received[1].last().unwrap().content,
+        "Tool result for `add`:\n3"
+    );
+}
+
+#[tokio::test]
+async fn node_tool_runs_graph_node() {
+    let graph = GraphBuilder::new()
+        .expression("shout", "upper(text)")
+        .build()
+        .unwrap();
+    let model = Scripted::new(&[
+        "```json\n{\"tool\": \"shout\", \"arguments\": {\"text\": \"hi\"}}\n```",
+        "done",
+    ]);
+    let node = agent(
+        json!([{ "name": "shout", "type": "node", "node_id": "shout" }]),
+        4,
+        model,
+    );
+
+    let output = run(node, Context::from_graph(&graph)).await;
+
+    assert_eq!(output["tool_calls"][0]["result"], "HI");
+    assert_eq!(output["answer"], "done");
+}
+
+#[tokio::test]
+async fn tool_errors_are_fed_back_to_the_model() {
+    let model = Scripted::new(&[r#"{"tool": "missing"}"#, "sorry"]);
+    let node = agent(json!([]), 4, model.clone());
+
+    let output = run(node, Context::from_graph(&Graph::new())).await;
+
+    assert_eq!(output["answer"], "sorry");
+    assert!(
+        output["tool_calls"][0]["error"]
+            .as_str()
+            .unwrap()
+            .contains("unknown tool `missing`")
+    );
+    let received = model.received.lock().unwrap();
+    assert!(received[1].last().unwrap().content.contains("error:"));
+}
+
+#[tokio::test]
+async fn stops_at_iteration_limit() {
+    TOOL_REGISTRY.register("agent_echo", Arc::new(Ok));
+    let call = r#"{"tool": "echo", "arguments": {}}"#;
+    let model = Scripted::new(&[call, call, call, call]);
+    let node = agent(
+        json!([{ "name": "echo", "type": "function", "function": "agent_echo" }]),
+        3,
+        model,
+    );
+
+    let output = run(node, Context::from_graph(&Graph::new())).await;
+
+    assert_eq!(output["stop_reason"], "max_iterations");
+    assert_eq!(output["answer"], Value::Null);
+    assert_eq!(output["iterations"], 3);
+    assert_eq!(output["tool_calls"].as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn node_tools_are_embedded_and_names_unique() {
+    let config = |tools: Value| {
+        json!({
+            "apiHost": "http://localhost:1/v1/",
+            "apiKey": "test",
+            "modelName": "test",
+            "tools": tools,
+        })
+    };
+    let tool = json!({ "name": "shout", "type": "node", "node_id": "shout" });
+
+    let graph = GraphBuilder::new()
+        .expression("shout", "upper(text)")
+        .agent(
+            "agent",
+            serde_json::from_value(config(json!([tool.clone()]))).unwrap(),
+        )
+        .build()
+        .unwrap();
+    assert!(graph.embedded_nodes().contains("shout"));
+
+    let duplicated = AgentNode::new(
+        "agent",
+        config(json!([tool.clone(), tool])),
+        &DataProcessorMapping::default(),
+    );
+    assert!(duplicated.is_err());
+}
+
+#[test]
+fn rejects_llm_options_the_agent_ignores() {
+    let options = [
+        ("memory", json!({ "sessionId": "s" })),
+        ("stream", json!(true)),
+        ("outputSchema", json!({ "type": "object" })),
+        ("prompt", json!("Answer: {{input}}")),
+    ];
+    for (field, value) in options {
+        let data = json!({
+            "apiHost": "http://localhost:1/v1/",
+            "apiKey": "test",
+            "modelName": "test",
+            "tools": [],
+            field: value,
+        });
+        let error = AgentNode::new("agent", data, &DataProcessorMapping::default()).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains(&format!("`{}` is not supported by AgentNode", field)),
+            "{}",
+            error
+        );
+    }
+}
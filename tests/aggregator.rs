@@ -0,0 +1,319 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use async_trait::async_trait;
+use flow_data::FlowData;
+use serde_json::{Value, json};
+use workflow_rs::{
+    edge::LoopGuard,
+    graph::{Graph, GraphBuilder},
+    model::{
+        Context,
+        node::{DataNode, DataProcessorMapping, Node, NodeType},
+    },
+    node::{
+        Executable,
+        condition::{ConditionConfig, Operator},
+        config::{
+            AggregateStrategy, AggregateWait, AggregatorConfig, BranchOutput, BranchPayload,
+            ParallelConfig, RouterConfig, RouterMode,
+        },
+        control::AggregatorNode,
+    },
+    processor::{PROCESSOR_REGISTRY, Processor},
+    runner::Runner,
+};
+
+/// 统计执行次数，返回 `<prefix><input>`
+struct Tagged {
+    prefix: &'static str,
+    runs: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl Processor<FlowData> for Tagged {
+    async fn process(&self, data: FlowData) -> Option<FlowData> {
+        self.runs.fetch_add(1, Ordering::SeqCst);
+        Some(format!("{}{}", self.prefix, data.as_text().ok()?).into())
+    }
+}
+
+/// 带计数处理器的 Identity 节点
+fn tagged(id: &str, prefix: &'static str, runs: &Arc<AtomicUsize>) -> Node {
+    let processor = format!("aggregator_{}_{}", id, prefix);
+    PROCESSOR_REGISTRY.register_input(
+        &processor,
+        Some(Arc::new(Tagged {
+            prefix,
+            runs: runs.clone(),
+        })),
+    );
+    Node::new(
+        id,
+        NodeType::Data(DataNode::Identity),
+        Value::Null,
+        DataProcessorMapping {
+            input: Some(processor),
+            output: None,
+        },
+        None,
+        None,
+    )
+}
+
+fn branches(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(key, id)| (key.to_string(), id.to_string()))
+        .collect()
+}
+
+/// start ─┬─ a ─┬─ agg → end
+///        └─ b ─┘
+fn fan_in(a: &str, b: &str, config: AggregatorConfig) -> Graph {
+    GraphBuilder::new()
+        .input("start", "x")
+        .expression("a", a)
+        .expression("b", b)
+        .aggregator("agg", config)
+        .end("end")
+        .edge("start", "a")
+        .edge("start", "b")
+        .edge("a", "agg")
+        .edge("b", "agg")
+        .edge("agg", "end")
+        .build()
+        .unwrap()
+}
+
+async fn run(mut graph: Graph) -> (Runner, FlowData) {
+    let mut runner = Runner::new();
+    let output = runner.run(None, &mut graph, None).await.unwrap();
+    (runner, output)
+}
+
+#[tokio::test]
+async fn collects_upstream_outputs_without_rerunning_branches() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let graph = GraphBuilder::new()
+        .input("start", "x")
+        .node(tagged("research", "R:", &runs))
+        .node(tagged("summary", "S:", &runs))
+        .aggregator(
+            "agg",
+            AggregatorConfig {
+                branches: branches(&[("research", "research"), ("summary", "summary")]),
+                output: BranchOutput::Object,
+                ..Default::default()
+            },
+        )
+        .end("end")
+        .edge("start", "research")
+        .edge("start", "summary")
+        .edge("research", "agg")
+        .edge("summary", "agg")
+        .edge("agg", "end")
+        .build()
+        .unwrap();
+
+    let (_, output) = run(graph).await;
+
+    assert_eq!(
+        output.into_json().unwrap(),
+        json!({ "research": "R:x", "summary": "S:x" })
+    );
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn collects_branches_run_by_parallel_node() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let graph = GraphBuilder::new()
+        .input("start", "x")
+        .node(tagged("investigate", "R:", &runs))
+        .node(tagged("summarize", "S:", &runs))
+        .parallel(
+            "fan_out",
+            ParallelConfig {
+                branches: branches(&[("research", "investigate"), ("summary", "summarize")]),
+                output: Some(BranchOutput::Collection),
+            },
+        )
+        .aggregator(
+            "agg",
+            AggregatorConfig {
+                branches: branches(&[("research", "investigate"), ("summary", "summarize")]),
+                strategy: AggregateStrategy::Concat,
+                separator: " | ".into(),
+                ..Default::default()
+            },
+        )
+        .end("end")
+        .edge("start", "fan_out")
+        .edge("fan_out", "agg")
+        .edge("agg", "end")
+        .build()
+        .unwrap();
+
+    let (_, output) = run(graph).await;
+
+    assert_eq!(output.as_text().unwrap(), "R:x | S:x");
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn first_non_empty_follows_branch_order() {
+    let config = AggregatorConfig {
+        branches: branches(&[("first", "a"), ("second", "b")]),
+        strategy: AggregateStrategy::FirstNonEmpty,
+        ..Default::default()
+    };
+
+    let (_, output) = run(fan_in("'  '", "'B:' + input", config.clone())).await;
+    assert_eq!(output.as_text().unwrap(), "B:x");
+
+    let (_, output) = run(fan_in("'A:' + input", "'B:' + input", config)).await;
+    assert_eq!(output.as_text().unwrap(), "A:x");
+}
+
+#[tokio::test]
+async fn merge_json_later_branch_wins() {
+    let config = AggregatorConfig {
+        branches: branches(&[("first", "a"), ("second", "b")]),
+        strategy: AggregateStrategy::MergeJson,
+        ..Default::default()
+    };
+
+    let (_, output) = run(fan_in(
+        "{ name: input, score: 1 }",
+        "{ score: 2, ok: true }",
+        config,
+    ))
+    .await;
+
+    assert_eq!(
+        output.into_json().unwrap(),
+        json!({ "name": "x", "score": 2, "ok": true })
+    );
+}
+
+#[tokio::test]
+async fn wait_any_runs_once_with_first_finished_branch() {
+    // start ─┬─ fast ────────── agg → end
+    //        └─ slow → slower ─┘
+    let mut graph = GraphBuilder::new()
+        .input("start", "x")
+        .expression("fast", "'fast'")
+        .expression("slow", "input")
+        .expression("slower", "'slow'")
+        .aggregator(
+            "agg",
+            AggregatorConfig {
+                branches: branches(&[("fast", "fast"), ("slow", "slower")]),
+                wait: AggregateWait::Any,
+                ..Default::default()
+            },
+        )
+        .end("end")
+        .edge("start", "fast")
+        .edge("start", "slow")
+        .edge("slow", "slower")
+        .edge("fast", "agg")
+        .edge("slower", "agg")
+        .edge("agg", "end")
+        .build()
+        .unwrap();
+
+    let mut runner = Runner::new();
+    let output = runner.run(None, &mut graph, None).await.unwrap();
+
+    assert_eq!(output.as_text_list().unwrap(), vec!["fast"]);
+    assert!(runner.node_runs().contains_key("slower"));
+    assert_eq!(
+        runner.get_output("agg").unwrap().as_text_list().unwrap(),
+        vec!["fast"]
+    );
+}
+
+#[tokio::test]
+async fn loop_iterations_do_not_see_stale_branch_outputs() {
+    // start → draft → route ─┬─ a ─┬─ agg → check → end，check 回跳到 draft 一次
+    //                        └─ b ─┘
+    // 第一轮走 a，第二轮走 b
+    let route = |id: &str, condition: Operator| BranchPayload {
+        id: id.into(),
+        when: ConditionConfig::compare(condition, "x!"),
+    };
+    let mut graph = GraphBuilder::new()
+        .input("start", "x")
+        .expression("draft", "input + '!'")
+        .router(
+            "route",
+            RouterConfig {
+                branches: vec![route("a", Operator::Eq), route("b", Operator::Ne)],
+                mode: RouterMode::First,
+            },
+        )
+        .expression("a", "'A'")
+        .expression("b", "'B'")
+        .aggregator(
+            "agg",
+            AggregatorConfig {
+                branches: branches(&[("a", "a"), ("b", "b")]),
+                output: BranchOutput::Object,
+                ..Default::default()
+            },
+        )
+        .expression("check", "'y'")
+        .end("end")
+        .edge("start", "draft")
+        .edge("draft", "route")
+        .handle_edge("route", "a", "a")
+        .handle_edge("route", "b", "b")
+        .edge("a", "agg")
+        .edge("b", "agg")
+        .edge("agg", "check")
+        .edge("check", "end")
+        .loop_edge(
+            "check",
+            "draft",
+            LoopGuard {
+                max_iterations: Some(1),
+                until: None,
+            },
+        )
+        .build()
+        .unwrap();
+
+    let mut runner = Runner::new();
+    runner.run(None, &mut graph, None).await.unwrap();
+
+    assert_eq!(runner.get_output("draft").unwrap().as_text().unwrap(), "y!");
+    assert_eq!(
+        runner
+            .get_output("agg")
+            .unwrap()
+            .clone()
+            .into_json()
+            .unwrap(),
+        json!({ "b": "B" })
+    );
+}
+
+#[tokio::test]
+async fn fails_without_any_branch_output() {
+    let node = AggregatorNode::new(
+        "agg",
+        json!({ "branches": { "a": "a" } }),
+        &DataProcessorMapping::default(),
+    )
+    .unwrap();
+    let context = Context::from_graph(&Graph::new());
+
+    assert!(node.execute(Some("x".into()), context).await.is_err());
+}
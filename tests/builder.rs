@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+
+use serde_json::json;
+use workflow_rs::{
+    graph::GraphBuilder,
+    node::config::{BranchConfig, BranchOutput, BranchPayload, ParallelConfig},
+};
+
+/// start → check（句柄 yes / default）
+fn branch() -> GraphBuilder {
+    let yes = BranchPayload {
+        id: "yes".into(),
+        when: serde_json::from_value(json!({ "condition": "==", "value": "ok" })).unwrap(),
+    };
+    GraphBuilder::new()
+        .input("start", "ok")
+        .branch(
+            "check",
+            BranchConfig {
+                branches: vec![yes],
+                default: None,
+            },
+        )
+        .identity("end")
+        .edge("start", "check")
+}
+
+/// start → fan_out（句柄 a / b，分别执行 left / right）
+fn parallel(output: Option<BranchOutput>) -> GraphBuilder {
+    GraphBuilder::new()
+        .input("start", "ok")
+        .identity("left")
+        .identity("right")
+        .parallel(
+            "fan_out",
+            ParallelConfig {
+                branches: BTreeMap::from([
+                    ("a".to_string(), "left".to_string()),
+                    ("b".to_string(), "right".to_string()),
+                ]),
+                output,
+            },
+        )
+        .identity("end")
+        .edge("start", "fan_out")
+}
+
+fn error(builder: GraphBuilder) -> String {
+    builder.build().unwrap_err().to_string()
+}
+
+#[test]
+fn branch_edges_use_declared_handles() {
+    branch()
+        .handle_edge("check", "yes", "end")
+        .handle_edge("check", "default", "end")
+        .build()
+        .unwrap();
+
+    let unknown = error(branch().handle_edge("check", "no", "end"));
+    assert!(unknown.contains("Node `check` has no handle `no`"));
+
+    let missing = error(branch().edge("check", "end"));
+    assert!(missing.contains("Edge from node `check` requires a handle"));
+}
+
+#[test]
+fn parallel_edges_use_branch_names() {
+    parallel(None)
+        .handle_edge("fan_out", "a", "end")
+        .handle_edge("fan_out", "b", "end")
+        .build()
+        .unwrap();
+
+    let unknown = error(parallel(None).handle_edge("fan_out", "c", "end"));
+    assert!(unknown.contains("Node `fan_out` has no handle `c`"));
+
+    let missing = error(parallel(None).edge("fan_out", "end"));
+    assert!(missing.contains("Edge from node `fan_out` requires a handle"));
+}
+
+#[test]
+fn merged_parallel_and_data_nodes_reject_handles() {
+    parallel(Some(BranchOutput::Object))
+        .edge("fan_out", "end")
+        .build()
+        .unwrap();
+
+    let merged = error(parallel(Some(BranchOutput::Object)).handle_edge("fan_out", "a", "end"));
+    assert!(merged.contains("Node `fan_out` does not route by handle `a`"));
+
+    let data = error(branch().handle_edge("start", "yes", "end"));
+    assert!(data.contains("Node `start` does not route by handle `yes`"));
+}
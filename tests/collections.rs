@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use flow_data::{FlowData, SingleData};
+use serde_json::{Value, json};
+use workflow_rs::{
+    graph::Graph,
+    model::{Context, node::DataProcessorMapping},
+    node::{
+        Executable,
+        data::{FilterNode, ReduceNode},
+    },
+    processor::REDUCER_REGISTRY,
+};
+
+fn texts(items: &[&str]) -> FlowData {
+    FlowData::Collection(
+        items
+            .iter()
+            .map(|item| SingleData::Text(item.to_string()))
+            .collect(),
+    )
+}
+
+fn numbers(items: &[f64]) -> FlowData {
+    FlowData::Collection(items.iter().copied().map(SingleData::Number).collect())
+}
+
+async fn filter(config: Value, input: FlowData) -> FlowData {
+    let node = FilterNode::new("filter", config, &DataProcessorMapping::default()).unwrap();
+    let context = Context::from_graph(&Graph::new());
+    node.execute(Some(input), context)
+        .await
+        .unwrap()
+        .into_data()
+        .unwrap()
+}
+
+async fn reduce(config: Value, input: FlowData) -> workflow_error::Result<FlowData> {
+    let node = ReduceNode::new("reduce", config, &DataProcessorMapping::default())?;
+    let context = Context::from_graph(&Graph::new());
+    node.execute(Some(input), context).await?.into_data()
+}
+
+#[tokio::test]
+async fn filter_keeps_matching_text() {
+    let config = json!({ "condition": "contains", "value": "rust", "valueType": "string" });
+    let output = filter(config, texts(&["rust-lang", "go", "trust"])).await;
+
+    assert_eq!(output.as_text_list().unwrap(), vec!["rust-lang", "trust"]);
+}
+
+#[tokio::test]
+async fn filter_compares_numbers() {
+    let config = json!({ "condition": ">", "value": "2", "valueType": "number" });
+    let output = filter(config, numbers(&[1.0, 3.0, 2.0, 5.0])).await;
+
+    assert_eq!(output.as_number_list().unwrap(), vec![3.0, 5.0]);
+}
+
+#[tokio::test]
+async fn reduce_concat_with_separator() {
+    let config = json!({ "strategy": "concat", "separator": ", " });
+    let output = reduce(config, texts(&["a", "b", "c"])).await.unwrap();
+
+    assert_eq!(output.as_text().unwrap(), "a, b, c");
+}
+
+#[tokio::test]
+async fn reduce_sum() {
+    let config = json!({ "strategy": "sum" });
+    let output = reduce(config, numbers(&[1.5, 2.5, 3.0])).await.unwrap();
+
+    assert_eq!(output.as_number().unwrap(), 7.0);
+}
+
+#[tokio::test]
+async fn reduce_merge_json_later_keys_win() {
+    let input = FlowData::Collection(vec![
+        SingleData::Json(json!({ "a": 1, "b": 1 })),
+        SingleData::Json(json!({ "b": 2 })),
+    ]);
+    let output = reduce(json!({ "strategy": "merge_json" }), input)
+        .await
+        .unwrap();
+
+    assert!(
+        matches!(output, FlowData::Single(SingleData::Json(value)) if value == json!({ "a": 1, "b": 2 }))
+    );
+}
+
+#[tokio::test]
+async fn reduce_rejects_mismatched_items() {
+    let config = json!({ "strategy": "sum" });
+    assert!(reduce(config, texts(&["1"])).await.is_err());
+}
+
+#[tokio::test]
+async fn reduce_custom_uses_registered_function() {
+    REDUCER_REGISTRY.register(
+        "count",
+        Arc::new(|items: &[SingleData]| Ok(FlowData::from(items.len() as f64))),
+    );
+
+    let config = json!({ "strategy": "custom", "function": "count" });
+    let output = reduce(config, texts(&["a", "b"])).await.unwrap();
+    assert_eq!(output.as_number().unwrap(), 2.0);
+
+    let missing = json!({ "strategy": "custom", "function": "missing" });
+    assert!(reduce(missing, texts(&["a"])).await.is_err());
+}
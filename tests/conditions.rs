@@ -0,0 +1,173 @@
+use serde_json::{Value, json};
+use workflow_rs::{
+    graph::GraphBuilder,
+    model::node::DataProcessorMapping,
+    node::{
+        condition::ConditionConfig,
+        config::{BranchConfig, BranchPayload},
+        control::{BranchNode, RepeatNode, RouterNode},
+    },
+};
+
+fn condition(value: Value) -> ConditionConfig {
+    let condition: ConditionConfig = serde_json::from_value(value).unwrap();
+    condition.validate().unwrap();
+    condition
+}
+
+#[test]
+fn legacy_format_still_works() {
+    let c = condition(json!({ "condition": "==", "value": "yes", "valueType": "string" }));
+    assert!(c.matches(&json!("yes")));
+    assert!(!c.matches(&json!("no")));
+
+    let c = condition(json!({ "condition": ">=", "value": "10", "valueType": "number" }));
+    assert!(c.matches(&json!(10)));
+    assert!(c.matches(&json!("12.5")));
+    assert!(!c.matches(&json!("abc")));
+}
+
+#[test]
+fn path_selects_inside_json() {
+    let input = json!({ "user": { "age": 20, "tags": ["admin", "ops"] } });
+
+    assert!(
+        condition(json!({ "path": "$.user.age", "condition": ">", "value": 18 })).matches(&input)
+    );
+    assert!(
+        condition(json!({ "path": "user.tags[1]", "condition": "==", "value": "ops" }))
+            .matches(&input)
+    );
+    assert!(
+        condition(json!({ "path": "/user/tags", "condition": "contains", "value": "admin" }))
+            .matches(&input)
+    );
+    assert!(
+        !condition(json!({ "path": "$.user.name", "condition": "==", "value": "x" }))
+            .matches(&input)
+    );
+}
+
+#[test]
+fn string_operators() {
+    let input = json!("error: timeout");
+
+    assert!(condition(json!({ "condition": "starts_with", "value": "error" })).matches(&input));
+    assert!(condition(json!({ "condition": "ends_with", "value": "timeout" })).matches(&input));
+    assert!(condition(json!({ "condition": "regex", "value": "^error: \\w+$" })).matches(&input));
+    assert!(
+        condition(json!({ "condition": "in", "value": ["ok", "error: timeout"] })).matches(&input)
+    );
+}
+
+#[test]
+fn regex_is_compiled_once_and_not_serialized() {
+    let raw = json!({ "path": "code", "condition": "regex", "value": "^E\\d{3}$" });
+    let c = condition(raw.clone());
+
+    // 校验时编译的正则随条件一起克隆，重复匹配不再编译
+    let cloned = c.clone();
+    for code in ["E001", "E404", "E999"] {
+        assert!(cloned.matches(&json!({ "code": code })));
+    }
+    assert!(!cloned.matches(&json!({ "code": "W001" })));
+    assert_eq!(serde_json::to_value(&cloned).unwrap(), raw);
+    assert_eq!(cloned, c);
+}
+
+#[test]
+fn exists_and_is_empty() {
+    let input = json!({ "a": 1, "b": null, "c": [], "d": "" });
+
+    let exists = |path: &str| condition(json!({ "path": path, "condition": "exists" }));
+    let empty = |path: &str| condition(json!({ "path": path, "condition": "is_empty" }));
+
+    assert!(exists("a").matches(&input));
+    assert!(!exists("b").matches(&input));
+    assert!(!exists("missing").matches(&input));
+    assert!(empty("b").matches(&input));
+    assert!(empty("c").matches(&input));
+    assert!(empty("d").matches(&input));
+    assert!(empty("missing").matches(&input));
+    assert!(!empty("a").matches(&input));
+}
+
+#[test]
+fn boolean_combinations() {
+    let c = condition(json!({
+        "all": [
+            { "path": "score", "condition": ">=", "value": 60 },
+            { "not": { "path": "flags", "condition": "contains", "value": "banned" } },
+            { "any": [
+                { "path": "role", "condition": "==", "value": "admin" },
+                { "path": "role", "condition": "==", "value": "owner" }
+            ] }
+        ]
+    }));
+
+    assert!(c.matches(&json!({ "score": 70, "flags": [], "role": "owner" })));
+    assert!(!c.matches(&json!({ "score": 70, "flags": ["banned"], "role": "owner" })));
+    assert!(!c.matches(&json!({ "score": 70, "flags": [], "role": "guest" })));
+}
+
+#[test]
+fn invalid_conditions_are_rejected() {
+    let unknown: Result<ConditionConfig, _> =
+        serde_json::from_value(json!({ "condition": "~=", "value": "x" }));
+    assert!(unknown.is_err());
+
+    for invalid in [
+        json!({ "condition": "regex", "value": "(" }),
+        json!({ "condition": "in", "value": "x" }),
+        json!({ "condition": ">", "value": "abc" }),
+        json!({ "condition": "==" }),
+        json!({ "path": "a..b", "condition": "exists" }),
+        json!({ "all": [] }),
+    ] {
+        let parsed: ConditionConfig = serde_json::from_value(invalid.clone()).unwrap();
+        assert!(parsed.validate().is_err(), "{} should be invalid", invalid);
+    }
+}
+
+#[test]
+fn branch_validates_conditions_at_build_time() {
+    let branch = BranchPayload {
+        id: "yes".into(),
+        when: serde_json::from_value(json!({ "condition": "regex", "value": "(" })).unwrap(),
+    };
+    let result = GraphBuilder::new()
+        .input("start", "x")
+        .branch(
+            "route",
+            BranchConfig {
+                branches: vec![branch],
+                default: None,
+            },
+        )
+        .end("end")
+        .edge("start", "route")
+        .handle_edge("route", "yes", "end")
+        .build();
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn invalid_node_config_reports_serde_error() {
+    let mapping = DataProcessorMapping::default();
+    let message = |result: workflow_error::Result<()>| result.unwrap_err().to_string();
+
+    // 未知运算符与字段拼写错误给出具体原因
+    let data = json!({ "branches": [{ "id": "yes", "condition": "~=", "value": "x" }] });
+    let error = message(BranchNode::new("route", data, &mapping).map(|_| ()));
+    assert!(error.contains("Invalid data format for BranchNode: unknown variant `~=`"));
+
+    let data = json!({ "branches": [{ "id": "yes", "conditon": "==", "value": "x" }] });
+    let error = message(RouterNode::new("route", data, &mapping).map(|_| ()));
+    assert!(error.contains("Invalid data format for RouterNode: missing field `condition`"));
+
+    let data =
+        json!({ "child_id": "a", "max_iterations": 3, "until": { "not": { "condition": "=" } } });
+    let error = message(RepeatNode::new("repeat", data, &mapping).map(|_| ()));
+    assert!(error.contains("Invalid data format for RepeatNode: unknown variant `=`"));
+}
@@ -0,0 +1,254 @@
+use serde_json::Value;
+use workflow_error::Error;
+use workflow_rs::{
+    edge::{EdgeType, LoopGuard},
+    graph::Graph,
+    model::node::{ControlNode, DataNode, DataProcessorMapping, Node, NodeType},
+};
+
+fn node(id: &str, node_type: NodeType) -> Node {
+    Node::new(
+        id,
+        node_type,
+        Value::Null,
+        DataProcessorMapping::default(),
+        None,
+        None,
+    )
+}
+
+/// 默认 start / end 节点，数据节点 a / b，控制节点 route / gate，无边
+fn graph() -> Graph {
+    let mut graph = Graph::new_with_default_nodes().unwrap();
+    for id in ["a", "b"] {
+        graph
+            .add_node(node(id, NodeType::Data(DataNode::Identity)))
+            .unwrap();
+    }
+    for id in ["route", "gate"] {
+        graph
+            .add_node(node(id, NodeType::Control(ControlNode::Branch)))
+            .unwrap();
+    }
+    graph
+}
+
+#[test]
+fn add_edge_rejects_end_node_as_source() {
+    let mut graph = graph();
+    assert!(graph.add_edge("end", "a", None, None).is_err());
+}
+
+#[test]
+fn add_edge_rejects_start_node_as_target() {
+    let mut graph = graph();
+    assert!(graph.add_edge("a", "start", None, None).is_err());
+}
+
+#[test]
+fn add_edge_rejects_missing_nodes() {
+    let mut graph = graph();
+    assert!(matches!(
+        graph.add_edge("missing", "a", None, None),
+        Err(Error::NodeNotFound(_))
+    ));
+    assert!(matches!(
+        graph.add_edge("a", "missing", None, None),
+        Err(Error::NodeNotFound(_))
+    ));
+}
+
+#[test]
+fn add_edge_rejects_control_to_control() {
+    let mut graph = graph();
+    assert!(graph.add_edge("route", "gate", None, None).is_err());
+}
+
+#[test]
+fn add_edge_infers_edge_type() {
+    let mut graph = graph();
+    let data = graph.add_edge("a", "route", None, None).unwrap();
+    let control = graph
+        .add_edge("route", "b", Some("yes".into()), None)
+        .unwrap();
+
+    assert_eq!(graph.get_edge(&data).unwrap().edge_type, EdgeType::Data);
+    assert_eq!(
+        graph.get_edge(&control).unwrap().edge_type,
+        EdgeType::Control
+    );
+}
+
+#[test]
+fn edge_ids_distinguish_handles() {
+    let mut graph = graph();
+    let yes = graph
+        .add_edge("route", "a", Some("yes".into()), None)
+        .unwrap();
+    let no = graph
+        .add_edge("route", "a", Some("no".into()), None)
+        .unwrap();
+    assert_ne!(yes, no);
+
+    let removed = graph.remove_edge_by_id(&yes).unwrap();
+    assert_eq!(removed.source_handle.as_deref(), Some("yes"));
+    assert!(graph.get_edge(&yes).is_none());
+    assert!(graph.get_edge(&no).is_some());
+}
+
+#[test]
+fn remove_edge_by_id_rejects_unknown_id() {
+    let mut graph = graph();
+    assert!(graph.remove_edge_by_id("a->b").is_err());
+}
+
+#[test]
+fn retarget_edge_moves_endpoints_and_keeps_handles() {
+    let mut graph = graph();
+    let id = graph
+        .add_edge("route", "a", Some("yes".into()), Some("in".into()))
+        .unwrap();
+
+    graph.retarget_edge(&id, "route", "b").unwrap();
+    // 边 ID 保持不变，持有旧 ID 的调用方仍可继续编辑
+    let edge = graph.get_edge(&id).unwrap();
+
+    assert_eq!(edge.target, "b");
+    assert_eq!(edge.source_handle.as_deref(), Some("yes"));
+    assert_eq!(edge.target_handle.as_deref(), Some("in"));
+    assert_eq!(graph.edges.len(), 1);
+    graph.retarget_edge(&id, "route", "a").unwrap();
+    assert_eq!(graph.get_edge(&id).unwrap().target, "a");
+}
+
+#[test]
+fn retarget_edge_updates_edge_type() {
+    let mut graph = graph();
+    let id = graph.add_edge("a", "b", None, None).unwrap();
+
+    graph.retarget_edge(&id, "route", "b").unwrap();
+
+    assert_eq!(graph.get_edge(&id).unwrap().edge_type, EdgeType::Control);
+}
+
+#[test]
+fn set_edge_handles_changes_source_handle_and_target_port() {
+    let mut graph = graph();
+    let id = graph
+        .add_edge("route", "a", Some("yes".into()), None)
+        .unwrap();
+
+    graph
+        .set_edge_handles(&id, Some("no".into()), Some("port".into()))
+        .unwrap();
+    let edge = graph.get_edge(&id).unwrap();
+
+    assert_eq!(edge.source, "route");
+    assert_eq!(edge.target, "a");
+    assert_eq!(edge.source_handle.as_deref(), Some("no"));
+    assert_eq!(edge.target_handle.as_deref(), Some("port"));
+}
+
+#[test]
+fn update_edge_enforces_add_edge_rules() {
+    let mut graph = graph();
+    let id = graph.add_edge("a", "b", None, None).unwrap();
+
+    assert!(graph.retarget_edge(&id, "end", "b").is_err());
+    assert!(graph.retarget_edge(&id, "a", "start").is_err());
+    assert!(graph.retarget_edge(&id, "a", "missing").is_err());
+    assert!(graph.retarget_edge(&id, "route", "gate").is_err());
+
+    // 失败时边保持不变
+    let edge = graph.get_edge(&id).unwrap();
+    assert_eq!((edge.source.as_str(), edge.target.as_str()), ("a", "b"));
+}
+
+#[test]
+fn loop_edges_cannot_gain_handles() {
+    let mut graph = graph();
+    graph.add_edge("a", "b", None, None).unwrap();
+    let guard = LoopGuard {
+        max_iterations: Some(2),
+        until: None,
+    };
+    let id = graph.add_loop_edge("b", "a", guard).unwrap();
+
+    let error = graph
+        .set_edge_handles(&id, Some("yes".into()), None)
+        .unwrap_err();
+    assert!(error.to_string().contains("cannot have handles"));
+    let edge = graph.get_edge(&id).unwrap();
+    assert!(edge.source_handle.is_none() && edge.target_handle.is_none());
+}
+
+#[test]
+fn update_edge_rejects_unknown_id() {
+    let mut graph = graph();
+    assert!(graph.update_edge("a->b", "a", "b", None, None).is_err());
+}
+
+/// start → a → b → end 序列化后的 JSON 文档
+fn linear_doc() -> Value {
+    let mut graph = graph();
+    graph.remove_node("route").unwrap();
+    graph.remove_node("gate").unwrap();
+    for (source, target) in [("start", "a"), ("a", "b"), ("b", "end")] {
+        graph.add_edge(source, target, None, None).unwrap();
+    }
+    serde_json::from_str(&graph.to_json()).unwrap()
+}
+
+#[test]
+fn edge_ids_survive_json_round_trip() {
+    let mut doc = linear_doc();
+    doc["edges"][1]["id"] = "review-link".into();
+
+    let graph = Graph::from_json(&doc.to_string()).unwrap();
+    let edge = graph.get_edge("review-link").unwrap();
+    assert_eq!((edge.source.as_str(), edge.target.as_str()), ("a", "b"));
+    assert!(graph.get_edge("a->b").is_none());
+
+    let reloaded = Graph::from_json(&graph.to_json()).unwrap();
+    assert!(reloaded.get_edge("review-link").is_some());
+    assert!(graph.diff(&reloaded).is_empty());
+
+    doc["edges"][2]["id"] = "review-link".into();
+    let duplicate = Graph::from_json(&doc.to_string()).unwrap_err();
+    assert!(
+        duplicate
+            .to_string()
+            .contains("Edge `review-link` already exists")
+    );
+}
+
+#[test]
+fn loading_checks_start_and_end_edge_rules() {
+    let mut doc = linear_doc();
+    doc["edges"][1]["target"] = "start".into();
+    let error = Graph::from_json(&doc.to_string()).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("Start node cannot have incoming edges")
+    );
+
+    let mut doc = linear_doc();
+    doc["edges"][1]["source"] = "end".into();
+    let error = Graph::from_json(&doc.to_string()).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("End node cannot have outgoing edges")
+    );
+}
+
+#[test]
+fn control_nodes_can_feed_an_aggregator() {
+    let mut graph = graph();
+    graph
+        .add_node(node("agg", NodeType::Control(ControlNode::Aggregator)))
+        .unwrap();
+    let id = graph.add_edge("route", "agg", None, None).unwrap();
+    assert_eq!(graph.get_edge(&id).unwrap().edge_type, EdgeType::Control);
+}
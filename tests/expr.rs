@@ -0,0 +1,270 @@
+use flow_data::FlowData;
+use serde_json::{Value, json};
+use workflow_error::Error;
+use workflow_rs::{
+    Workflow,
+    expr::{Expression, Template, Variables, input_scope},
+    graph::GraphBuilder,
+    model::node::{DataNode, DataProcessorMapping, Node, NodeType},
+    node::condition::{ConditionConfig, ExprCondition},
+    runner::Runner,
+};
+
+fn eval(source: &str, input: Value) -> Value {
+    Expression::parse(source)
+        .unwrap()
+        .eval_input(&input)
+        .unwrap()
+}
+
+fn parse_error(source: &str) -> String {
+    match Expression::parse(source) {
+        Err(Error::ExpressionError(message)) => message.to_string(),
+        other => panic!("expected a parse error for `{}`, got {:?}", source, other),
+    }
+}
+
+#[test]
+fn arithmetic_and_precedence() {
+    assert_eq!(eval("1 + 2 * 3", Value::Null), json!(7));
+    assert_eq!(eval("(1 + 2) * 3", Value::Null), json!(9));
+    assert_eq!(eval("7 % 4 - -1", Value::Null), json!(4));
+    assert_eq!(eval("1 / 4", Value::Null), json!(0.25));
+    assert_eq!(eval("'a' + 1 + 2", Value::Null), json!("a12"));
+    assert_eq!(eval("[1] + [2]", Value::Null), json!([1, 2]));
+}
+
+#[test]
+fn logic_access_and_defaults() {
+    let input = json!({ "user": { "name": "ada", "age": 36 }, "tags": ["a", "b"] });
+
+    assert_eq!(
+        eval("input.user.age >= 18 and not input.banned", input.clone()),
+        json!(true)
+    );
+    assert_eq!(eval("input.tags[-1]", input.clone()), json!("b"));
+    assert_eq!(eval("input.tags.0", input.clone()), json!("a"));
+    assert_eq!(eval("input['user'].name", input.clone()), json!("ada"));
+    assert_eq!(
+        eval("input.missing.deep ?? 'none'", input.clone()),
+        json!("none")
+    );
+    assert_eq!(
+        eval("input.user.age > 40 ? 'old' : 'young'", input.clone()),
+        json!("young")
+    );
+    assert_eq!(
+        eval(
+            "{ name: upper(input.user.name), n: len(input.tags) }",
+            input
+        ),
+        json!({ "name": "ADA", "n": 2 })
+    );
+}
+
+#[test]
+fn builtin_functions() {
+    let input = json!({ "text": " Hello, World ", "price": 12.3456, "items": [3, 1, 2] });
+
+    assert_eq!(
+        eval("lower(trim(input.text))", input.clone()),
+        json!("hello, world")
+    );
+    assert_eq!(
+        eval("split(trim(input.text), ', ')", input.clone()),
+        json!(["Hello", "World"])
+    );
+    assert_eq!(
+        eval("join(input.items, '-')", input.clone()),
+        json!("3-1-2")
+    );
+    assert_eq!(eval("round(input.price, 2)", input.clone()), json!(12.35));
+    assert_eq!(
+        eval("max(input.items) + min(4, 5)", input.clone()),
+        json!(7)
+    );
+    assert_eq!(eval("num('42') + 1", input.clone()), json!(43));
+    assert_eq!(eval("path(input, '$.items[1]')", input.clone()), json!(1));
+    assert_eq!(
+        eval("matches(input.text, 'W\\\\w+')", input.clone()),
+        json!(true)
+    );
+    assert_eq!(eval("json('{\"a\": 1}').a", input.clone()), json!(1));
+    assert_eq!(eval("keys({ b: 1 })", input), json!(["b"]));
+}
+
+#[test]
+fn long_chains_are_rejected_instead_of_overflowing() {
+    // 左结合链与访问链不经过括号，同样受深度限制
+    assert!(parse_error(&vec!["1"; 1000].join("+")).contains("nested too deeply"));
+    assert!(parse_error(&format!("input{}", ".a".repeat(1000))).contains("nested too deeply"));
+    assert!(parse_error(&format!("input{}", "[0]".repeat(1000))).contains("nested too deeply"));
+    assert!(parse_error(&vec!["1"; 10_000].join("+")).contains("too long"));
+
+    let sum = Expression::parse(&vec!["1"; 100].join(" + ")).unwrap();
+    assert_eq!(sum.eval(&Variables::new()).unwrap(), json!(100));
+}
+
+#[test]
+fn errors_point_at_the_problem() {
+    let message = parse_error("upper(input");
+    assert!(
+        message.contains("expected `)`, found end of expression"),
+        "{}",
+        message
+    );
+    assert!(message.contains("column 12"), "{}", message);
+    assert!(
+        message.ends_with("\n  upper(input\n             ^"),
+        "{}",
+        message
+    );
+
+    assert!(parse_error("system('rm -rf /')").contains("unknown function `system`"));
+    assert!(parse_error("upper(1, 2)").contains("`upper` expects 1 argument, got 2"));
+    assert!(parse_error("1 +").contains("expected a value"));
+    assert!(parse_error("'open").contains("unterminated string"));
+    assert!(parse_error(&"(".repeat(200)).contains("nested too deeply"));
+
+    let runtime = Expression::parse("input.a * 2")
+        .unwrap()
+        .eval_input(&json!({ "a": "x" }))
+        .unwrap_err();
+    assert!(
+        runtime
+            .to_string()
+            .contains("cannot apply `*` to string and number")
+    );
+
+    let unknown = Expression::parse("other").unwrap().eval(&Variables::new());
+    assert!(
+        unknown
+            .unwrap_err()
+            .to_string()
+            .contains("unknown variable `other`")
+    );
+}
+
+#[test]
+fn templates_render_expressions() {
+    let template =
+        Template::parse("Hi {{ input.name }}, {{ len(input.items) }} new{{ input.none }}").unwrap();
+    let vars = input_scope(
+        &json!({ "name": "ada", "items": [1, 2] }),
+        &Variables::new(),
+    );
+
+    assert_eq!(template.render(&vars).unwrap(), "Hi ada, 2 new");
+    assert!(Template::parse("Hi {{ input.name").is_err());
+}
+
+#[test]
+fn templates_keep_literal_braces() {
+    let vars = input_scope(&json!({ "name": "ada" }), &Variables::new());
+    let render = |source: &str| Template::parse(source).unwrap().render(&vars).unwrap();
+
+    assert_eq!(
+        render(r#"Return {{"answer": 1}} as JSON for {{ name }}"#),
+        r#"Return {{"answer": 1}} as JSON for ada"#
+    );
+    assert_eq!(
+        render(r#"Example: {{"a": {"b": 1}}}"#),
+        r#"Example: {{"a": {"b": 1}}}"#
+    );
+    assert_eq!(
+        render(r"Use \{{ name }} for {{ name }}"),
+        "Use {{ name }} for ada"
+    );
+    assert!(
+        !Template::parse(r#"{{"answer": 1}}"#)
+            .unwrap()
+            .has_expressions()
+    );
+}
+
+#[test]
+fn expression_condition() {
+    let condition: ConditionConfig =
+        serde_json::from_value(json!({ "expr": "input.score >= 60 && len(input.errors) == 0" }))
+            .unwrap();
+    condition.validate().unwrap();
+
+    assert!(condition.matches(&json!({ "score": 70, "errors": [] })));
+    assert!(!condition.matches(&json!({ "score": 70, "errors": ["x"] })));
+    // 求值出错视为不满足
+    assert!(!condition.matches(&json!("text")));
+
+    let invalid: ConditionConfig = serde_json::from_value(json!({ "expr": "1 +" })).unwrap();
+    assert!(invalid.validate().is_err());
+
+    // 解析结果缓存在条件中，不影响序列化与比较
+    let built = ConditionConfig::Expr(ExprCondition::new(
+        "input.score >= 60 && len(input.errors) == 0",
+    ));
+    assert_eq!(built, condition);
+    assert_eq!(
+        serde_json::to_value(&condition).unwrap(),
+        json!({ "expr": "input.score >= 60 && len(input.errors) == 0" })
+    );
+}
+
+#[tokio::test]
+async fn expression_and_prompt_nodes_in_a_graph() {
+    let graph = GraphBuilder::new()
+        .input(
+            "start",
+            FlowData::try_from_json(json!({ "name": "ada", "score": 41 })).unwrap(),
+        )
+        .expression(
+            "calc",
+            "{ name: upper(input.name), score: input.score + 1 }",
+        )
+        .prompt("prompt", "{{ input.name }} scored {{ input.score }}")
+        .end("end")
+        .edge("start", "calc")
+        .edge("calc", "prompt")
+        .edge("prompt", "end")
+        .build()
+        .unwrap();
+
+    let output = Workflow::start(graph).await.unwrap();
+    assert_eq!(output.as_text().unwrap(), "ADA scored 42");
+}
+
+#[test]
+fn invalid_expression_fails_at_build_time() {
+    let result = GraphBuilder::new()
+        .input("start", "x")
+        .expression("calc", "len(")
+        .end("end")
+        .edge("start", "calc")
+        .edge("calc", "end")
+        .build();
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn node_build_errors_are_reported_by_the_runner() {
+    let mut graph = GraphBuilder::new()
+        .input("start", "x")
+        .end("end")
+        .edge("start", "end")
+        .build()
+        .unwrap();
+    // 绕过 GraphBuilder 的构建期校验
+    graph
+        .add_node(Node::new(
+            "prompt",
+            NodeType::Data(DataNode::Prompt),
+            json!({ "template": "Hi {{ input.name" }),
+            DataProcessorMapping::default(),
+            None,
+            None,
+        ))
+        .unwrap();
+
+    let result = Runner::new().run(None, &mut graph, None).await;
+
+    assert!(result.is_err_and(|e| e.to_string().contains("Unclosed `{{`")));
+}
@@ -0,0 +1,51 @@
+use workflow_rs::graph::{Graph, GraphBuilder};
+
+fn forward() -> Graph {
+    GraphBuilder::new()
+        .input("start", "hi")
+        .prompt("prompt", "Say: {{ input }}")
+        .end("end")
+        .edge("start", "prompt")
+        .edge("prompt", "end")
+        .build()
+        .unwrap()
+}
+
+fn backward() -> Graph {
+    GraphBuilder::new()
+        .end("end")
+        .prompt("prompt", "Say: {{ input }}")
+        .input("start", "hi")
+        .edge("prompt", "end")
+        .edge("start", "prompt")
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn insertion_order_does_not_change_fingerprint() {
+    let (forward, backward) = (forward(), backward());
+
+    assert_eq!(forward.to_canonical_json(), backward.to_canonical_json());
+    assert_eq!(forward.fingerprint(), backward.fingerprint());
+    // 经 JSON 往返（HashMap 迭代顺序不同）后保持不变
+    let reloaded = Graph::from_json(&backward.to_json()).unwrap();
+    assert_eq!(reloaded.fingerprint(), forward.fingerprint());
+}
+
+#[test]
+fn fingerprint_is_pinned_and_tracks_changes() {
+    let graph = forward();
+    // 规范化格式或哈希算法变化会导致已保存的指纹失效
+    assert_eq!(graph.fingerprint(), 10032848442118522493);
+
+    let changed = GraphBuilder::new()
+        .input("start", "hi")
+        .prompt("prompt", "Shout: {{ input }}")
+        .end("end")
+        .edge("start", "prompt")
+        .edge("prompt", "end")
+        .build()
+        .unwrap();
+    assert_ne!(changed.fingerprint(), graph.fingerprint());
+}
@@ -0,0 +1,163 @@
+use serde_json::json;
+use workflow_error::Error;
+use workflow_rs::graph::Graph;
+
+const YAML: &str = r#"
+start: start
+end: end
+nodes:
+  start:
+    type: Input
+    data:
+      input: hello
+  count:
+    type: Input
+    data:
+      input: 3
+  check:
+    type: Branch
+    data:
+      branches:
+        - id: yes
+          when: { condition: "==", value: hello }
+  draft:
+    type: Identity
+  end:
+    type: Identity
+edges:
+  - start -> check
+  - count -> draft
+  - check.yes -> draft
+  - check.default -> end
+  - draft -> end
+"#;
+
+/// 边按 (source, source_handle, target) 排序，忽略自动生成的 ID
+fn edges(graph: &Graph) -> Vec<(String, Option<String>, String)> {
+    let mut edges: Vec<_> = graph
+        .edges
+        .iter()
+        .map(|edge| {
+            (
+                edge.source.clone(),
+                edge.source_handle.clone(),
+                edge.target.clone(),
+            )
+        })
+        .collect();
+    edges.sort();
+    edges
+}
+
+fn assert_doc_graph(graph: &Graph) {
+    assert_eq!(graph.start_node.as_deref(), Some("start"));
+    assert_eq!(graph.end_node.as_deref(), Some("end"));
+    assert_eq!(
+        edges(graph),
+        vec![
+            ("check".into(), Some("default".into()), "end".into()),
+            ("check".into(), Some("yes".into()), "draft".into()),
+            ("count".into(), None, "draft".into()),
+            ("draft".into(), None, "end".into()),
+            ("start".into(), None, "check".into()),
+        ]
+    );
+    // 简写输入展开为 FlowData 的标签格式
+    assert_eq!(
+        graph.nodes["start"].data["input"],
+        json!({ "type": "Single", "value": { "type": "Text", "value": "hello" } })
+    );
+    assert_eq!(
+        graph.nodes["count"].data["input"],
+        json!({ "type": "Single", "value": { "type": "Number", "value": 3 } })
+    );
+}
+
+#[test]
+fn yaml_short_edges_and_inputs_round_trip() {
+    let graph = Graph::from_yaml(YAML).unwrap();
+    assert_doc_graph(&graph);
+
+    let yaml = graph.to_yaml().unwrap();
+    assert!(yaml.contains("- start -> check\n"));
+    assert!(yaml.contains("- check.yes -> draft\n"));
+    assert!(yaml.contains("input: hello\n"));
+    assert!(yaml.contains("input: 3\n"));
+
+    assert_doc_graph(&Graph::from_yaml(&yaml).unwrap());
+}
+
+#[test]
+fn toml_short_edges_and_inputs_round_trip() {
+    let graph = Graph::from_yaml(YAML).unwrap();
+
+    let toml = graph.to_toml().unwrap();
+    assert!(toml.contains("\"start -> check\""));
+    assert!(toml.contains("\"check.yes -> draft\""));
+    assert!(toml.contains("input = \"hello\"\n"));
+    assert!(toml.contains("input = 3\n"));
+
+    assert_doc_graph(&Graph::from_toml(&toml).unwrap());
+}
+
+#[test]
+fn rich_inputs_are_kept_as_is() {
+    let yaml = r#"
+nodes:
+  start:
+    type: Input
+    data:
+      input: { type: Collection, value: [{ type: Text, value: a }] }
+"#;
+    let graph = Graph::from_yaml(yaml).unwrap();
+    let input = json!({ "type": "Collection", "value": [{ "type": "Text", "value": "a" }] });
+    assert_eq!(graph.nodes["start"].data["input"], input);
+
+    let again = Graph::from_yaml(&graph.to_yaml().unwrap()).unwrap();
+    assert_eq!(again.nodes["start"].data["input"], input);
+}
+
+#[test]
+fn unknown_node_type_is_a_deserialization_error() {
+    let yaml = "nodes:\n  a:\n    type: Teleport\n";
+    let error = Graph::from_yaml(yaml).unwrap_err();
+    assert!(matches!(error, Error::SerdeYamlError(_)));
+    assert!(error.to_string().contains("unknown node type `Teleport`"));
+
+    let toml = "[nodes.a]\ntype = \"Teleport\"\n";
+    let error = Graph::from_toml(toml).unwrap_err();
+    assert!(matches!(error, Error::TomlDeError(_)));
+    assert!(error.to_string().contains("unknown node type `Teleport`"));
+}
+
+#[test]
+fn invalid_short_edge_is_an_error() {
+    let yaml = "nodes:\n  a:\n    type: Identity\nedges:\n  - a => b\n";
+    let error = Graph::from_yaml(yaml).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("Invalid edge definition: `a => b`")
+    );
+}
+
+#[test]
+fn loop_edge_with_handles_is_an_error() {
+    let yaml = r#"
+nodes:
+  a: { type: Identity }
+  b: { type: Identity }
+edges:
+  - a -> b
+  - source: b
+    target: a
+    source_handle: yes
+    loop_guard: { max_iterations: 2 }
+"#;
+    let error = Graph::from_yaml(yaml).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("Loop edge b -> a cannot have handles")
+    );
+}
@@ -0,0 +1,97 @@
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+use workflow_rs::{
+    graph::Graph,
+    model::{Context, node::DataProcessorMapping},
+    node::{Executable, data::HttpNode},
+};
+
+/// 接收一个请求，回复 `{"ok": true}`，返回收到的原始请求
+async fn serve_once(listener: TcpListener) -> String {
+    let (mut socket, _) = listener.accept().await.unwrap();
+    let mut request = Vec::new();
+    let mut buffer = [0; 4096];
+    loop {
+        let n = socket.read(&mut buffer).await.unwrap();
+        request.extend_from_slice(&buffer[..n]);
+        let text = String::from_utf8_lossy(&request);
+        if let Some((head, body)) = text.split_once("\r\n\r\n") {
+            let length = head
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse::<usize>().ok())?
+                })
+                .unwrap_or(0);
+            if body.len() >= length {
+                break;
+            }
+        }
+    }
+    let body = r#"{"ok":true}"#;
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    socket.write_all(response.as_bytes()).await.unwrap();
+    String::from_utf8(request).unwrap()
+}
+
+#[tokio::test]
+async fn renders_url_headers_and_body_templates() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = tokio::spawn(serve_once(listener));
+
+    let node = HttpNode::new(
+        "http",
+        json!({
+            "url": format!("http://{}/users/{{{{ input.id }}}}", address),
+            "method": "POST",
+            "headers": { "X-User": "{{ upper(name) }}" },
+            "input_data": { "greeting": "hi {{ name }}", "tags": ["{{ input.id * 2 }}"], "n": 1 },
+        }),
+        &DataProcessorMapping::default(),
+    )
+    .unwrap();
+    let output = node
+        .execute(
+            Some(json!({ "id": 7, "name": "ada" }).into()),
+            Context::from_graph(&Graph::new()),
+        )
+        .await
+        .unwrap()
+        .into_data()
+        .unwrap()
+        .into_json()
+        .unwrap();
+    assert_eq!(output, json!({ "ok": true }));
+
+    let request = server.await.unwrap();
+    assert!(request.starts_with("POST /users/7 "), "{}", request);
+    assert!(
+        request.to_lowercase().contains("x-user: ada"),
+        "{}",
+        request
+    );
+    let body: Value = serde_json::from_str(request.split_once("\r\n\r\n").unwrap().1).unwrap();
+    assert_eq!(
+        body,
+        json!({ "greeting": "hi ada", "tags": ["14"], "n": 1 })
+    );
+}
+
+#[test]
+fn invalid_templates_fail_at_build_time() {
+    let result = HttpNode::new(
+        "http",
+        json!({ "url": "http://localhost/{{ input.id", "input_data": {} }),
+        &DataProcessorMapping::default(),
+    );
+    assert!(result.is_err());
+}
@@ -0,0 +1,227 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use flow_data::{FileType, FileValue, FlowData, SingleData};
+use model_gateway_rs::{
+    model::{
+        llm::{ChatMessage, LlmInput, LlmOutput},
+        vision::{VisionInput, VisionOutput},
+    },
+    traits::ModelClient,
+};
+use serde_json::{Value, json};
+use workflow_rs::{
+    graph::Graph,
+    model::{Context, node::DataProcessorMapping},
+    node::{Executable, data::llm::LLMNode},
+};
+
+/// 记录收到的消息（序列化为 JSON），回复固定文本
+#[derive(Default)]
+struct Recorder {
+    received: Mutex<Vec<Value>>,
+}
+
+#[async_trait]
+impl ModelClient<LlmInput, LlmOutput> for Recorder {
+    async fn infer(&self, input: LlmInput) -> model_gateway_rs::error::Result<LlmOutput> {
+        self.received
+            .lock()
+            .unwrap()
+            .push(serde_json::to_value(&input.messages).unwrap());
+        Ok(LlmOutput {
+            message: Some(ChatMessage::assistant("text reply")),
+            usage: None,
+        })
+    }
+}
+
+#[async_trait]
+impl ModelClient<VisionInput, VisionOutput> for Recorder {
+    async fn infer(&self, input: VisionInput) -> model_gateway_rs::error::Result<VisionOutput> {
+        self.received
+            .lock()
+            .unwrap()
+            .push(serde_json::to_value(&input.messages).unwrap());
+        Ok(VisionOutput {
+            content: "vision reply".to_string(),
+            reasoning_content: None,
+            usage: None,
+        })
+    }
+}
+
+impl Recorder {
+    fn last(&self) -> Value {
+        self.received.lock().unwrap().last().cloned().unwrap()
+    }
+}
+
+fn node(extra: Value, model: Arc<Recorder>) -> LLMNode {
+    let mut data = json!({
+        "apiHost": "http://localhost:1/v1/",
+        "apiKey": "test",
+        "modelName": "test",
+        "systemPrompt": "Be brief.",
+    });
+    data.as_object_mut()
+        .unwrap()
+        .extend(extra.as_object().unwrap().clone());
+    LLMNode::new("llm", data, &DataProcessorMapping::default())
+        .unwrap()
+        .with_model_client(model.clone())
+        .with_vision_client(model)
+}
+
+async fn run(node: &LLMNode, input: FlowData) -> workflow_error::Result<String> {
+    let output = node
+        .execute(Some(input), Context::from_graph(&Graph::new()))
+        .await?;
+    Ok(output.into_data()?.as_text()?.to_string())
+}
+
+fn file(path: &str, file_type: FileType) -> SingleData {
+    SingleData::File(FileValue {
+        path: path.to_string(),
+        file_type,
+    })
+}
+
+#[tokio::test]
+async fn accepts_chat_message_list() {
+    let model = Arc::new(Recorder::default());
+    let node = node(json!({}), model.clone());
+    let input = FlowData::from(json!([
+        { "role": "user", "content": "I am Ann." },
+        { "role": "assistant", "content": "Hi Ann." },
+        { "role": "user", "content": "Who am I?" },
+    ]));
+
+    assert_eq!(run(&node, input).await.unwrap(), "text reply");
+    assert_eq!(
+        model.last(),
+        json!([
+            { "role": "system", "content": "Be brief." },
+            { "role": "user", "content": "I am Ann." },
+            { "role": "assistant", "content": "Hi Ann." },
+            { "role": "user", "content": "Who am I?" },
+        ])
+    );
+
+    let invalid = FlowData::from(json!([{ "role": "robot", "content": "beep" }]));
+    let error = run(&node, invalid).await.unwrap_err();
+    assert!(error.to_string().contains("index 0"));
+}
+
+#[tokio::test]
+async fn joins_or_splits_collections() {
+    let items = || {
+        FlowData::Collection(vec![
+            SingleData::Text("first".into()),
+            SingleData::Number(2.0),
+        ])
+    };
+
+    let model = Arc::new(Recorder::default());
+    run(&node(json!({}), model.clone()), items()).await.unwrap();
+    assert_eq!(
+        model.last()[1],
+        json!({ "role": "user", "content": "first\n2" })
+    );
+
+    run(
+        &node(json!({ "collection": "messages" }), model.clone()),
+        items(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        model.last(),
+        json!([
+            { "role": "system", "content": "Be brief." },
+            { "role": "user", "content": "first" },
+            { "role": "user", "content": "2" },
+        ])
+    );
+}
+
+#[tokio::test]
+async fn renders_prompt_template() {
+    let model = Arc::new(Recorder::default());
+
+    let templated = node(
+        json!({ "prompt": "Translate to French: {{ input.text }}" }),
+        model.clone(),
+    );
+    run(&templated, FlowData::from(json!({ "text": "hello" })))
+        .await
+        .unwrap();
+    assert_eq!(model.last()[1]["content"], "Translate to French: hello");
+
+    let plain = node(json!({ "prompt": "Summarize:" }), model.clone());
+    run(&plain, "a long text".into()).await.unwrap();
+    assert_eq!(model.last()[1]["content"], "Summarize:\na long text");
+}
+
+#[tokio::test]
+async fn sends_images_as_content_parts() {
+    let path = std::env::temp_dir().join(format!("llm-input-{}.png", uuid::Uuid::new_v4()));
+    std::fs::write(&path, [0x89, b'P', b'N', b'G']).unwrap();
+    let model = Arc::new(Recorder::default());
+    let node = node(json!({ "prompt": "Describe the images." }), model.clone());
+
+    let input = FlowData::Collection(vec![
+        file(path.to_str().unwrap(), FileType::Image),
+        file("https://example.com/cat.jpg", FileType::Image),
+    ]);
+    assert_eq!(run(&node, input).await.unwrap(), "vision reply");
+    assert_eq!(
+        model.last()[1],
+        json!({
+            "role": "user",
+            "content": [
+                { "type": "text", "text": "Describe the images." },
+                { "type": "image_url", "image_url": { "url": "data:image/png;base64,iVBORw==" } },
+                { "type": "image_url", "image_url": { "url": "https://example.com/cat.jpg" } },
+            ],
+        })
+    );
+    std::fs::remove_file(path).unwrap();
+
+    let audio = FlowData::Single(file("speech.mp3", FileType::Audio));
+    let error = run(&node, audio).await.unwrap_err();
+    assert!(error.to_string().contains("Audio input is not supported"));
+}
+
+#[tokio::test]
+async fn message_list_accepts_multimodal_parts() {
+    let model = Arc::new(Recorder::default());
+    let node = node(json!({}), model.clone());
+    let input = FlowData::from(json!([{
+        "role": "user",
+        "content": [
+            { "type": "text", "text": "What is this?" },
+            { "type": "image_url", "image_url": { "url": "https://example.com/dog.png" } },
+        ],
+    }]));
+
+    assert_eq!(run(&node, input).await.unwrap(), "vision reply");
+    assert_eq!(
+        model.last()[1]["content"][1]["image_url"]["url"],
+        "https://example.com/dog.png"
+    );
+}
+
+#[test]
+fn invalid_config_names_the_node_and_the_field() {
+    let data =
+        json!({ "apiHost": "http://localhost:1/v1/", "modelName": "test", "maxTokens": "many" });
+    let error = LLMNode::new("llm", data, &DataProcessorMapping::default()).unwrap_err();
+
+    assert!(
+        error
+            .to_string()
+            .contains("Invalid data format for LLMNode")
+    );
+    assert!(error.to_string().contains("invalid type: string \"many\""));
+}
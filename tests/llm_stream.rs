@@ -0,0 +1,215 @@
+use std::sync::{Arc, Mutex};
+
+use flow_data::{FlowOutputType, StreamFormat};
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+use workflow_rs::{
+    graph::{Graph, GraphBuilder},
+    model::{Context, node::DataProcessorMapping},
+    node::{Executable, config::LLMConfig, data::llm::LLMNode},
+    runner::Runner,
+};
+
+/// 只处理一次请求的 OpenAI 兼容服务：记录请求体并以 SSE 返回 `deltas`
+async fn serve_once(deltas: &[&str]) -> (String, Arc<Mutex<Option<Value>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let body = Arc::new(Mutex::new(None));
+
+    let mut events: Vec<String> = deltas
+        .iter()
+        .map(|delta| {
+            let chunk = json!({ "choices": [{ "index": 0, "delta": { "content": delta } }] });
+            format!("data: {}\n\n", chunk)
+        })
+        .collect();
+    events.push("data: [DONE]\n\n".to_string());
+
+    let received = body.clone();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0u8; 4096];
+        // 读完请求头与 Content-Length 指定的请求体
+        loop {
+            let n = socket.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some(end) = text.find("\r\n\r\n") {
+                let length = text[..end]
+                    .lines()
+                    .find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or(0);
+                if request.len() >= end + 4 + length {
+                    *received.lock().unwrap() =
+                        serde_json::from_slice(&request[end + 4..end + 4 + length]).ok();
+                    break;
+                }
+            }
+        }
+
+        socket
+            .write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        for event in events {
+            socket.write_all(event.as_bytes()).await.unwrap();
+            socket.flush().await.unwrap();
+        }
+        socket.shutdown().await.unwrap();
+    });
+
+    (format!("http://{}/v1/", address), body)
+}
+
+fn config(base_url: &str) -> LLMConfig {
+    serde_json::from_value(json!({
+        "apiHost": base_url,
+        "apiKey": "test",
+        "modelName": "test",
+        "stream": true,
+    }))
+    .unwrap()
+}
+
+/// start → llm → shout → end
+fn graph(base_url: &str) -> Graph {
+    GraphBuilder::new()
+        .input("start", "hi")
+        .llm("llm", config(base_url))
+        .expression("shout", "upper(input)")
+        .end("end")
+        .edge("start", "llm")
+        .edge("llm", "shout")
+        .edge("shout", "end")
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn runner_forwards_chunks_and_continues_with_text() {
+    let (base_url, body) = serve_once(&["Hello", ", ", "world"]).await;
+    let mut graph = graph(&base_url);
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut runner = Runner::new();
+    let output = runner.run(None, &mut graph, Some(tx)).await.unwrap();
+
+    assert_eq!(output.as_text().unwrap(), "HELLO, WORLD");
+    assert_eq!(
+        runner.get_output("llm").unwrap().as_text().unwrap(),
+        "Hello, world"
+    );
+
+    let mut forwarded = Vec::new();
+    while let Ok((node, bytes)) = rx.try_recv() {
+        assert_eq!(node, "llm");
+        forwarded.extend_from_slice(&bytes);
+    }
+    assert_eq!(
+        StreamFormat::OpenAiSse
+            .decode(&forwarded)
+            .unwrap()
+            .as_text()
+            .unwrap(),
+        "Hello, world"
+    );
+
+    let body = body.lock().unwrap().clone().unwrap();
+    assert_eq!(body["stream"], true);
+    assert_eq!(body["messages"][1]["content"], "hi");
+}
+
+#[tokio::test]
+async fn runner_drains_stream_without_sender() {
+    let (base_url, _) = serve_once(&["quiet"]).await;
+    let mut graph = graph(&base_url);
+
+    let mut runner = Runner::new();
+    let output = runner.run(None, &mut graph, None).await.unwrap();
+
+    assert_eq!(output.as_text().unwrap(), "QUIET");
+}
+
+#[tokio::test]
+async fn node_returns_stream_output() {
+    let (base_url, _) = serve_once(&["ok"]).await;
+    let node = LLMNode::new(
+        "llm",
+        serde_json::to_value(config(&base_url)).unwrap(),
+        &DataProcessorMapping::default(),
+    )
+    .unwrap();
+
+    let output = node
+        .execute(Some("hi".into()), Context::from_graph(&Graph::new()))
+        .await
+        .unwrap();
+
+    assert!(matches!(output.get_type(), FlowOutputType::Stream));
+    assert_eq!(output.stream_format(), Some(StreamFormat::OpenAiSse));
+}
+
+#[test]
+fn stream_cannot_be_combined_with_output_schema() {
+    let mut data = serde_json::to_value(config("http://localhost:1/v1/")).unwrap();
+    data["outputSchema"] = json!({ "type": "object" });
+
+    assert!(LLMNode::new("llm", data, &DataProcessorMapping::default()).is_err());
+}
+
+#[test]
+fn decodes_by_stream_format() {
+    let decode = |format: StreamFormat, bytes: &str| format.decode(bytes.as_bytes());
+
+    let ollama = concat!(
+        r#"{"message": {"role": "assistant", "content": "Hi"}, "done": false}"#,
+        "\n",
+        r#"{"message": {"role": "assistant", "content": " there"}, "done": true}"#,
+        "\n",
+    );
+    assert_eq!(
+        decode(StreamFormat::OllamaNdjson, ollama)
+            .unwrap()
+            .as_text()
+            .unwrap(),
+        "Hi there"
+    );
+
+    let sse = ": keep-alive\nevent: message\ndata: {\"choices\": [{\"delta\": {\"role\": \"assistant\"}}]}\n\ndata: {\"choices\": [{\"delta\": {\"content\": \"ok\"}}]}\n\ndata: [DONE]\n\n";
+    assert_eq!(
+        decode(StreamFormat::OpenAiSse, sse)
+            .unwrap()
+            .as_text()
+            .unwrap(),
+        "ok"
+    );
+
+    let failed = "data: {\"error\": {\"message\": \"rate limited\"}}\n\n";
+    let error = decode(StreamFormat::OpenAiSse, failed).unwrap_err();
+    assert!(error.to_string().contains("rate limited"));
+
+    assert_eq!(
+        decode(StreamFormat::Json, r#"{"a": 1}"#)
+            .unwrap()
+            .into_json()
+            .unwrap(),
+        json!({ "a": 1 })
+    );
+    assert_eq!(
+        decode(StreamFormat::Text, "plain bytes")
+            .unwrap()
+            .as_text()
+            .unwrap(),
+        "plain bytes"
+    );
+}
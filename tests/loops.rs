@@ -0,0 +1,248 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use flow_data::FlowData;
+use serde_json::{Value, json};
+use workflow_rs::{
+    edge::LoopGuard,
+    graph::{Graph, GraphBuilder},
+    model::node::{DataNode, DataProcessorMapping, Node, NodeType},
+    node::{
+        base::NodeState,
+        config::{BranchConfig, BranchPayload, ConditionConfig},
+    },
+    processor::{PROCESSOR_REGISTRY, Processor},
+    runner::Runner,
+};
+
+/// 在文本输入后追加 `x`
+struct AppendX;
+
+#[async_trait]
+impl Processor<FlowData> for AppendX {
+    async fn process(&self, data: FlowData) -> Option<FlowData> {
+        let text = data.as_text().ok()?;
+        Some(FlowData::from(format!("{}x", text).as_str()))
+    }
+}
+
+fn identity(id: &str, input_processor: Option<&str>) -> Node {
+    Node::new(
+        id,
+        NodeType::Data(DataNode::Identity),
+        Value::Null,
+        DataProcessorMapping {
+            input: input_processor.map(str::to_string),
+            output: None,
+        },
+        None,
+        None,
+    )
+}
+
+/// start("A") → draft → review → end，draft 每次执行在输入后追加 `x`
+fn graph() -> Graph {
+    PROCESSOR_REGISTRY.register_input("append_x", Some(Arc::new(AppendX)));
+
+    let mut graph = Graph::new_with_default_nodes().unwrap();
+    graph.add_node(identity("draft", Some("append_x"))).unwrap();
+    graph.add_node(identity("review", None)).unwrap();
+    graph.add_edge("start", "draft", None, None).unwrap();
+    graph.add_edge("draft", "review", None, None).unwrap();
+    graph.add_edge("review", "end", None, None).unwrap();
+    graph
+}
+
+fn until_equals(value: &str) -> ConditionConfig {
+    serde_json::from_value(json!({ "condition": "==", "value": value, "valueType": "string" }))
+        .unwrap()
+}
+
+#[test]
+fn plain_cycle_is_still_rejected() {
+    let mut graph = graph();
+    graph.add_edge("review", "draft", None, None).unwrap();
+    assert!(graph.compile().is_err());
+}
+
+#[test]
+fn loop_edge_is_excluded_from_cycle_detection() {
+    let mut graph = graph();
+    let guard = LoopGuard {
+        max_iterations: Some(2),
+        until: None,
+    };
+    graph.add_loop_edge("review", "draft", guard).unwrap();
+
+    graph.compile().unwrap();
+    assert_eq!(
+        graph.loop_edges["review"],
+        vec!["review->draft".to_string()]
+    );
+    assert!(!graph.successors["review"].contains("draft"));
+}
+
+#[test]
+fn loop_edge_requires_a_guard() {
+    let mut graph = graph();
+    let guard = LoopGuard {
+        max_iterations: None,
+        until: None,
+    };
+    assert!(graph.add_loop_edge("review", "draft", guard).is_err());
+}
+
+#[test]
+fn invalid_loop_edge_in_json_is_an_error() {
+    let mut graph = graph();
+    let guard = LoopGuard {
+        max_iterations: Some(2),
+        until: None,
+    };
+    graph.add_loop_edge("review", "draft", guard).unwrap();
+    let mut doc: Value = serde_json::from_str(&graph.to_json()).unwrap();
+    let edge = doc["edges"]
+        .as_array_mut()
+        .unwrap()
+        .iter_mut()
+        .find(|edge| edge["loopGuard"].is_object())
+        .unwrap();
+    edge["loopGuard"] = json!({});
+
+    let result = Graph::from_json(&doc.to_string());
+
+    assert!(result.is_err_and(|e| e.to_string().contains("requires max_iterations or until")));
+}
+
+#[test]
+fn loop_edge_with_handles_in_json_is_an_error() {
+    let mut graph = graph();
+    let guard = LoopGuard {
+        max_iterations: Some(2),
+        until: None,
+    };
+    graph.add_loop_edge("review", "draft", guard).unwrap();
+    let mut doc: Value = serde_json::from_str(&graph.to_json()).unwrap();
+    let edge = doc["edges"]
+        .as_array_mut()
+        .unwrap()
+        .iter_mut()
+        .find(|edge| edge["loopGuard"].is_object())
+        .unwrap();
+    edge["sourceHandle"] = json!("yes");
+
+    let result = Graph::from_json(&doc.to_string());
+
+    assert!(result.is_err_and(|e| e.to_string().contains("cannot have handles")));
+}
+
+#[test]
+fn loop_edge_must_point_upstream() {
+    let mut graph = graph();
+    graph.add_node(identity("side", None)).unwrap();
+    let guard = LoopGuard {
+        max_iterations: Some(1),
+        until: None,
+    };
+    graph.add_loop_edge("review", "side", guard).unwrap();
+
+    assert!(graph.compile().is_err());
+}
+
+#[tokio::test]
+async fn loop_stops_at_max_iterations() {
+    let mut graph = graph();
+    let guard = LoopGuard {
+        max_iterations: Some(3),
+        until: None,
+    };
+    let edge = graph.add_loop_edge("review", "draft", guard).unwrap();
+
+    let mut runner = Runner::new();
+    let output = runner.run(None, &mut graph, None).await.unwrap();
+
+    // 首次执行加 3 次回跳，draft 共执行 4 次
+    assert_eq!(output.as_text().unwrap(), "Axxxx");
+    assert_eq!(runner.loop_counts()[&edge], 3);
+}
+
+#[tokio::test]
+async fn loop_stops_when_until_condition_holds() {
+    let mut graph = graph();
+    let guard = LoopGuard {
+        max_iterations: Some(10),
+        until: Some(until_equals("Axxx")),
+    };
+    let edge = graph.add_loop_edge("review", "draft", guard).unwrap();
+
+    let mut runner = Runner::new();
+    let output = runner.run(None, &mut graph, None).await.unwrap();
+
+    assert_eq!(output.as_text().unwrap(), "Axxx");
+    assert_eq!(runner.loop_counts()[&edge], 2);
+}
+
+#[tokio::test]
+async fn branch_inside_loop_body_exits_the_loop() {
+    PROCESSOR_REGISTRY.register_input("append_x", Some(Arc::new(AppendX)));
+    // draft → check：不满足时经 revise 回到 draft，满足时走 default 到 end
+    let bad = BranchPayload {
+        id: "bad".into(),
+        when: serde_json::from_value(json!({ "condition": "regex", "value": "^Ax{1,2}$" }))
+            .unwrap(),
+    };
+    let guard = LoopGuard {
+        max_iterations: Some(3),
+        until: None,
+    };
+    let mut graph = GraphBuilder::new()
+        .input("start", "A")
+        .node(identity("draft", Some("append_x")))
+        .branch(
+            "check",
+            BranchConfig {
+                branches: vec![bad],
+                default: None,
+            },
+        )
+        .identity("revise")
+        .end("end")
+        .edge("start", "draft")
+        .edge("draft", "check")
+        .handle_edge("check", "bad", "revise")
+        .handle_edge("check", "default", "end")
+        .loop_edge("revise", "draft", guard)
+        .build()
+        .unwrap();
+
+    let mut runner = Runner::new();
+    let output = runner.run(None, &mut graph, None).await.unwrap();
+
+    assert_eq!(output.as_text().unwrap(), "Axxx");
+    assert_eq!(runner.loop_counts()["revise->draft"], 2);
+    assert!(matches!(
+        runner.node_runs()["end"].state,
+        NodeState::Completed
+    ));
+}
+
+#[test]
+fn loop_guard_survives_json_and_yaml() {
+    let mut graph = graph();
+    let guard = LoopGuard {
+        max_iterations: Some(5),
+        until: Some(until_equals("done")),
+    };
+    let edge = graph
+        .add_loop_edge("review", "draft", guard.clone())
+        .unwrap();
+
+    let from_json = Graph::from_json(&graph.to_json()).unwrap();
+    assert_eq!(
+        from_json.get_edge(&edge).unwrap().loop_guard,
+        Some(guard.clone())
+    );
+
+    let from_yaml = Graph::from_yaml(&graph.to_yaml().unwrap()).unwrap();
+    assert_eq!(from_yaml.get_edge(&edge).unwrap().loop_guard, Some(guard));
+}
@@ -0,0 +1,194 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use flow_data::{FlowData, SingleData};
+use serde_json::{Value, json};
+use workflow_rs::{
+    graph::{Graph, GraphBuilder},
+    model::{
+        Context,
+        graph_data::GraphData,
+        node::{DataNode, DataProcessorMapping, Node, NodeType},
+    },
+    node::{Executable, config::MapConfig, control::MapNode},
+    processor::{PROCESSOR_REGISTRY, Processor},
+    runner::Runner,
+};
+
+/// 文本为数字时先休眠对应毫秒数，再追加 `!`；文本为 `bad` 时返回 None 使节点失败
+struct Tag;
+
+#[async_trait]
+impl Processor<FlowData> for Tag {
+    async fn process(&self, data: FlowData) -> Option<FlowData> {
+        let text = data.as_text().ok()?.to_string();
+        if text == "bad" {
+            return None;
+        }
+        if let Ok(ms) = text.parse::<u64>() {
+            tokio::time::sleep(Duration::from_millis(ms)).await;
+        }
+        Some(FlowData::from(format!("{}!", text)))
+    }
+}
+
+fn tag_node() -> Node {
+    PROCESSOR_REGISTRY.register_input("tag", Some(Arc::new(Tag)));
+
+    Node::new(
+        "tag",
+        NodeType::Data(DataNode::Identity),
+        Value::Null,
+        DataProcessorMapping {
+            input: Some("tag".into()),
+            output: None,
+        },
+        None,
+        None,
+    )
+}
+
+fn context() -> Arc<Context> {
+    let mut graph = Graph::new();
+    graph.add_node(tag_node()).unwrap();
+    Context::from_graph(&graph)
+}
+
+fn texts(items: &[&str]) -> FlowData {
+    FlowData::Collection(
+        items
+            .iter()
+            .map(|item| SingleData::Text(item.to_string()))
+            .collect(),
+    )
+}
+
+async fn run(config: Value, input: FlowData) -> workflow_error::Result<FlowData> {
+    let node = MapNode::new("map", config, &DataProcessorMapping::default())?;
+    node.execute(Some(input), context()).await?.into_data()
+}
+
+#[tokio::test]
+async fn preserves_input_order_under_concurrency() {
+    let config = json!({ "child_id": "tag", "concurrency": 3 });
+    let output = run(config, texts(&["30", "10", "0"])).await.unwrap();
+
+    assert_eq!(output.as_text_list().unwrap(), vec!["30!", "10!", "0!"]);
+}
+
+#[tokio::test]
+async fn maps_json_array_input() {
+    let config = json!({ "child_id": "tag" });
+    let output = run(config, FlowData::from(json!(["a", "b"])))
+        .await
+        .unwrap();
+
+    assert_eq!(output.as_text_list().unwrap(), vec!["a!", "b!"]);
+}
+
+#[tokio::test]
+async fn fail_fast_returns_first_error() {
+    let config = json!({ "child_id": "tag", "concurrency": 2 });
+    let result = run(config, texts(&["a", "bad", "c"])).await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn collect_mode_keeps_errors_in_place() {
+    let config = json!({ "child_id": "tag", "on_error": "collect" });
+    let output = run(config, texts(&["a", "bad", "c"])).await.unwrap();
+    let items = output.as_collection().unwrap();
+
+    assert!(matches!(&items[0], SingleData::Text(text) if text == "a!"));
+    assert!(matches!(&items[1], SingleData::Json(value) if value.get("error").is_some()));
+    assert!(matches!(&items[2], SingleData::Text(text) if text == "c!"));
+}
+
+/// 返回 `[text, text 的长度]` 两个元素的 Collection
+struct Pair;
+
+#[async_trait]
+impl Processor<FlowData> for Pair {
+    async fn process(&self, data: FlowData) -> Option<FlowData> {
+        let text = data.as_text().ok()?.to_string();
+        let length = text.len() as f64;
+        Some(FlowData::Collection(vec![
+            SingleData::Text(text),
+            SingleData::Number(length),
+        ]))
+    }
+}
+
+#[tokio::test]
+async fn collection_results_become_plain_json_arrays() {
+    PROCESSOR_REGISTRY.register_input("pair", Some(Arc::new(Pair)));
+    let mut graph = Graph::new();
+    let mut pair = tag_node();
+    pair.id = "pair".to_string();
+    pair.processors.input = Some("pair".into());
+    graph.add_node(pair).unwrap();
+
+    let node = MapNode::new(
+        "map",
+        json!({ "child_id": "pair" }),
+        &DataProcessorMapping::default(),
+    )
+    .unwrap();
+    let output = node
+        .execute(Some(texts(&["ab", "c"])), Context::from_graph(&graph))
+        .await
+        .unwrap()
+        .into_data()
+        .unwrap();
+
+    // 每个元素的结果为普通 JSON 数组，而非 FlowData 的标签格式
+    assert_eq!(output.to_json_value(), json!([["ab", 2.0], ["c", 1.0]]));
+}
+
+#[tokio::test]
+async fn rejects_single_input() {
+    let config = json!({ "child_id": "tag" });
+    assert!(run(config, FlowData::from("a")).await.is_err());
+}
+
+#[tokio::test]
+async fn child_runs_only_inside_map_in_a_graph() {
+    let config = MapConfig {
+        child_id: Some("tag".into()),
+        subgraph: None,
+        concurrency: Some(2),
+        on_error: Default::default(),
+    };
+    let mut graph = GraphBuilder::new()
+        .input("start", texts(&["a", "b"]))
+        .map("map", config)
+        .node(tag_node())
+        .end("end")
+        .edge("start", "map")
+        .edge("map", "end")
+        .build()
+        .unwrap();
+
+    let mut runner = Runner::new();
+    let output = runner.run(None, &mut graph, None).await.unwrap();
+
+    assert_eq!(output.as_text_list().unwrap(), vec!["a!", "b!"]);
+    // 子节点只由 Map 执行，不作为独立的起始节点调度
+    assert!(!runner.node_runs().contains_key("tag"));
+}
+
+#[test]
+fn rejects_zero_concurrency() {
+    let config = json!({ "child_id": "tag", "concurrency": 0 });
+    assert!(MapNode::new("map", config, &DataProcessorMapping::default()).is_err());
+}
+
+#[test]
+fn rejects_invalid_subgraph() {
+    let mut subgraph = GraphData::new();
+    subgraph.add_edge("in", "out", None, None);
+    let config = json!({ "subgraph": subgraph });
+
+    assert!(MapNode::new("map", config, &DataProcessorMapping::default()).is_err());
+}
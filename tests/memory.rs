@@ -0,0 +1,362 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use model_gateway_rs::{
+    model::llm::{ChatMessage, LlmInput, LlmOutput},
+    traits::ModelClient,
+};
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+use workflow_rs::{
+    graph::{Graph, GraphBuilder},
+    memory::{FileMemoryStore, InMemoryStore, MEMORY_REGISTRY, MemoryStore},
+    model::{Context, node::DataProcessorMapping},
+    node::{Executable, data::llm::LLMNode},
+    runner::Runner,
+};
+
+/// 依次返回预设回复，并记录每次收到的消息
+#[derive(Default)]
+struct Scripted {
+    replies: Mutex<VecDeque<String>>,
+    received: Mutex<Vec<Vec<ChatMessage>>>,
+}
+
+impl Scripted {
+    fn new(replies: &[&str]) -> Arc<Self> {
+        Arc::new(Self {
+            replies: Mutex::new(replies.iter().map(|r| r.to_string()).collect()),
+            ..Default::default()
+        })
+    }
+
+    fn contents(&self, request: usize) -> Vec<String> {
+        self.received.lock().unwrap()[request]
+            .iter()
+            .map(|message| message.content.clone())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl ModelClient<LlmInput, LlmOutput> for Scripted {
+    async fn infer(&self, input: LlmInput) -> model_gateway_rs::error::Result<LlmOutput> {
+        self.received.lock().unwrap().push(input.messages);
+        let reply = self.replies.lock().unwrap().pop_front().unwrap_or_default();
+        Ok(LlmOutput {
+            message: Some(ChatMessage::assistant(&reply)),
+            usage: None,
+        })
+    }
+}
+
+fn data(base_url: &str, memory: Value) -> Value {
+    json!({
+        "apiHost": base_url,
+        "apiKey": "test",
+        "modelName": "test",
+        "systemPrompt": "Be brief.",
+        "memory": memory,
+    })
+}
+
+fn node(memory: Value, model: Arc<Scripted>) -> LLMNode {
+    LLMNode::new(
+        "llm",
+        data("http://localhost:1/v1/", memory),
+        &DataProcessorMapping::default(),
+    )
+    .unwrap()
+    .with_model_client(model)
+}
+
+async fn ask(node: &LLMNode, session_id: &str, text: &str) -> String {
+    node.execute(
+        Some(text.into()),
+        Context::with_session(&Graph::new(), session_id),
+    )
+    .await
+    .unwrap()
+    .into_data()
+    .unwrap()
+    .as_text()
+    .unwrap()
+    .to_string()
+}
+
+fn contents(messages: &[ChatMessage]) -> Vec<&str> {
+    messages.iter().map(|m| m.content.as_str()).collect()
+}
+
+#[tokio::test]
+async fn carries_history_within_a_session() {
+    let model = Scripted::new(&["Hi Ann.", "You are Ann.", "I don't know."]);
+    let node = node(json!({}), model.clone());
+
+    assert_eq!(ask(&node, "memory-a", "I am Ann.").await, "Hi Ann.");
+    ask(&node, "memory-a", "Who am I?").await;
+    ask(&node, "memory-b", "Who am I?").await;
+
+    assert_eq!(
+        model.contents(1),
+        ["Be brief.", "I am Ann.", "Hi Ann.", "Who am I?"]
+    );
+    // 其他会话看不到这段历史
+    assert_eq!(model.contents(2), ["Be brief.", "Who am I?"]);
+
+    let history = MEMORY_REGISTRY.get("default").unwrap().load("memory-a");
+    assert_eq!(history.unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn requires_a_session_id() {
+    let node = node(json!({}), Scripted::new(&["ok"]));
+
+    let result = node
+        .execute(Some("hi".into()), Context::from_graph(&Graph::new()))
+        .await;
+
+    assert!(result.is_err_and(|e| e.to_string().contains("session id")));
+}
+
+#[tokio::test]
+async fn window_keeps_the_latest_messages() {
+    let store = Arc::new(InMemoryStore::default());
+    MEMORY_REGISTRY.register("memory_window", store.clone());
+    let model = Scripted::new(&["a1", "a2", "a3"]);
+    let node = node(
+        json!({ "store": "memory_window", "strategy": { "type": "window", "max_messages": 3 } }),
+        model.clone(),
+    );
+
+    for question in ["q1", "q2", "q3"] {
+        ask(&node, "s", question).await;
+    }
+
+    assert_eq!(model.contents(2), ["Be brief.", "a1", "q2", "a2", "q3"]);
+    assert_eq!(contents(&store.load("s").unwrap()), ["a2", "q3", "a3"]);
+}
+
+#[tokio::test]
+async fn summarizes_older_messages() {
+    let store = Arc::new(InMemoryStore::default());
+    MEMORY_REGISTRY.register("memory_summarize", store.clone());
+    let model = Scripted::new(&[
+        "a1",
+        "a2",
+        "a3",
+        "Ann asked twice.",
+        "a4",
+        "Ann asked again.",
+    ]);
+    let node = node(
+        json!({
+            "store": "memory_summarize",
+            "strategy": { "type": "summarize", "max_messages": 4, "keep_recent": 2 },
+        }),
+        model.clone(),
+    );
+
+    for question in ["q1", "q2", "q3", "q4"] {
+        ask(&node, "s", question).await;
+    }
+
+    // 第三轮后历史为 6 条，超过 4 条时总结前 4 条
+    assert!(model.contents(3)[1].contains("user: q1\nassistant: a1"));
+    assert_eq!(
+        model.contents(4),
+        [
+            "Be brief.",
+            "Summary of the earlier conversation:\nAnn asked twice.",
+            "q3",
+            "a3",
+            "q4",
+        ]
+    );
+    // 第四轮后再次超过，之前的总结一并参与总结
+    assert!(model.contents(5)[1].starts_with("system: Summary of the earlier conversation"));
+    assert_eq!(
+        contents(&store.load("s").unwrap()),
+        [
+            "Summary of the earlier conversation:\nAnn asked again.",
+            "q4",
+            "a4"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn file_store_persists_sessions() {
+    let dir = std::env::temp_dir().join(format!("workflow-memory-{}", uuid::Uuid::new_v4()));
+    MEMORY_REGISTRY.register("memory_file", Arc::new(FileMemoryStore::new(&dir)));
+    let node = node(json!({ "store": "memory_file" }), Scripted::new(&["hello"]));
+
+    ask(&node, "chat-1", "hi").await;
+
+    // 新的存储实例读取同一目录
+    let reopened = FileMemoryStore::new(&dir);
+    assert_eq!(contents(&reopened.load("chat-1").unwrap()), ["hi", "hello"]);
+    assert!(reopened.load("missing").unwrap().is_empty());
+    assert!(reopened.load("../escape").is_err());
+
+    reopened.clear("chat-1").unwrap();
+    assert!(reopened.load("chat-1").unwrap().is_empty());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+/// 稍作等待后回复 `re: <最后一条消息>`，让并发的轮次交错执行
+struct Slow;
+
+#[async_trait]
+impl ModelClient<LlmInput, LlmOutput> for Slow {
+    async fn infer(&self, input: LlmInput) -> model_gateway_rs::error::Result<LlmOutput> {
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        let last = input.messages.last().unwrap().content.clone();
+        Ok(LlmOutput {
+            message: Some(ChatMessage::assistant(&format!("re: {}", last))),
+            usage: None,
+        })
+    }
+}
+
+#[tokio::test]
+async fn concurrent_turns_of_a_session_are_serialized() {
+    let node = LLMNode::new(
+        "llm",
+        data("http://localhost:1/v1/", json!({})),
+        &DataProcessorMapping::default(),
+    )
+    .unwrap()
+    .with_model_client(Arc::new(Slow));
+
+    tokio::join!(
+        ask(&node, "memory-concurrent", "one"),
+        ask(&node, "memory-concurrent", "two")
+    );
+
+    // 后一轮读到前一轮写入的历史，两轮都被保留
+    let history = MEMORY_REGISTRY
+        .get("default")
+        .unwrap()
+        .load("memory-concurrent")
+        .unwrap();
+    assert_eq!(contents(&history), ["one", "re: one", "two", "re: two"]);
+}
+
+#[test]
+fn rejects_invalid_memory_config() {
+    let build = |mut data: Value| {
+        LLMNode::new("llm", data.take(), &DataProcessorMapping::default()).is_err()
+    };
+    let base = "http://localhost:1/v1/";
+
+    let mut streaming = data(base, json!({}));
+    streaming["stream"] = json!(true);
+    assert!(build(streaming));
+    assert!(build(data(
+        base,
+        json!({ "strategy": { "type": "window", "max_messages": 0 } })
+    )));
+    assert!(build(data(
+        base,
+        json!({ "strategy": { "type": "summarize", "max_messages": 2, "keep_recent": 2 } })
+    )));
+}
+
+/// OpenAI 兼容服务：依次以 JSON 返回 `replies`，并记录每次的请求体
+async fn serve(replies: &[&str]) -> (String, Arc<Mutex<Vec<Value>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let bodies = Arc::new(Mutex::new(Vec::new()));
+
+    let replies: Vec<String> = replies.iter().map(|r| r.to_string()).collect();
+    let received = bodies.clone();
+    tokio::spawn(async move {
+        for reply in replies {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            // 读完请求头与 Content-Length 指定的请求体
+            loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some(end) = text.find("\r\n\r\n") {
+                    let length = text[..end]
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= end + 4 + length {
+                        received.lock().unwrap().push(
+                            serde_json::from_slice(&request[end + 4..end + 4 + length]).unwrap(),
+                        );
+                        break;
+                    }
+                }
+            }
+
+            let body = json!({
+                "id": "chat",
+                "object": "chat.completion",
+                "created": 0,
+                "model": "test",
+                "choices": [{
+                    "index": 0,
+                    "message": { "role": "assistant", "content": reply },
+                    "finish_reason": "stop",
+                }],
+            })
+            .to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            socket.shutdown().await.unwrap();
+        }
+    });
+
+    (format!("http://{}/v1/", address), bodies)
+}
+
+#[tokio::test]
+async fn runner_session_carries_memory_across_runs() {
+    let (base_url, bodies) = serve(&["first", "second"]).await;
+    let mut graph = GraphBuilder::new()
+        .input("start", "hi")
+        .llm(
+            "llm",
+            serde_json::from_value(data(&base_url, json!({}))).unwrap(),
+        )
+        .end("end")
+        .edge("start", "llm")
+        .edge("llm", "end")
+        .build()
+        .unwrap();
+
+    let mut runner = Runner::new().with_session("memory-runner");
+    let first = runner.run(None, &mut graph, None).await.unwrap();
+    let second = runner.run(None, &mut graph, None).await.unwrap();
+
+    assert_eq!(first.as_text().unwrap(), "first");
+    assert_eq!(second.as_text().unwrap(), "second");
+    let bodies = bodies.lock().unwrap();
+    let messages: Vec<&str> = bodies[1]["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["content"].as_str().unwrap())
+        .collect();
+    assert_eq!(messages, ["Be brief.", "hi", "first", "hi"]);
+}
@@ -0,0 +1,145 @@
+use flow_data::FlowData;
+use serde_json::{Value, json};
+use workflow_error::Error;
+use workflow_rs::{
+    Workflow,
+    graph::Graph,
+    model::migration::{CURRENT_SCHEMA_VERSION, migrate},
+};
+
+#[test]
+fn v0_nodes_map_becomes_list() {
+    let doc = migrate(json!({
+        "nodes": {
+            "end": { "id": "end", "node_type": { "Data": "Identity" }, "data": null }
+        },
+        "edges": []
+    }))
+    .unwrap();
+
+    assert_eq!(doc["schema_version"], json!(1));
+    assert_eq!(doc["nodes"][0]["id"], json!("end"));
+}
+
+#[test]
+fn v0_edge_fields_are_renamed_and_ids_filled() {
+    let doc = migrate(json!({
+        "nodes": [],
+        "edges": [
+            { "start": "a", "end": "b", "edge_type": "Data" },
+            { "source": "b", "target": "c" },
+            { "source": "b", "target": "c" },
+            { "id": "b->d", "source": "x", "target": "y" },
+            { "source": "b", "target": "d" }
+        ]
+    }))
+    .unwrap();
+
+    assert_eq!(
+        doc["edges"][0],
+        json!({ "id": "a->b", "source": "a", "target": "b" })
+    );
+    assert_eq!(doc["edges"][1]["id"], json!("b->c"));
+    assert_eq!(doc["edges"][2]["id"], json!("b->c#2"));
+    assert_eq!(doc["edges"][3]["id"], json!("b->d"));
+    assert_eq!(doc["edges"][4]["id"], json!("b->d#2"));
+}
+
+#[test]
+fn v0_input_flow_data_is_retagged() {
+    let doc = migrate(json!({
+        "nodes": [
+            {
+                "id": "single",
+                "node_type": { "Data": "Input" },
+                "data": { "input": { "Single": { "Text": "A" } } }
+            },
+            {
+                "id": "collection",
+                "node_type": { "Data": "Input" },
+                "data": { "input": { "Collection": [{ "Number": 1.0 }, { "Text": "b" }] } }
+            }
+        ],
+        "edges": []
+    }))
+    .unwrap();
+
+    let single: FlowData =
+        serde_json::from_value(doc["nodes"][0]["data"]["input"].clone()).unwrap();
+    assert_eq!(single.as_text().unwrap(), "A");
+
+    let collection: FlowData =
+        serde_json::from_value(doc["nodes"][1]["data"]["input"].clone()).unwrap();
+    assert_eq!(collection.as_collection().unwrap().len(), 2);
+}
+
+#[test]
+fn v0_branch_map_becomes_payload_list_with_handles() {
+    let doc = migrate(json!({
+        "nodes": [{
+            "id": "route",
+            "node_type": { "Control": "Branch" },
+            "data": { "branches": { "yes": "a" }, "default": "b" }
+        }],
+        "edges": [
+            { "start": "route", "end": "a", "edge_type": "Control" },
+            { "start": "route", "end": "b", "edge_type": "Control" }
+        ]
+    }))
+    .unwrap();
+
+    assert_eq!(
+        doc["nodes"][0]["data"]["branches"],
+        json!([{ "id": "a", "condition": "==", "value": "yes", "valueType": "string" }])
+    );
+    assert_eq!(doc["edges"][0]["sourceHandle"], json!("a"));
+    assert_eq!(doc["edges"][1]["sourceHandle"], json!("default"));
+    // 补齐的边 ID 包含迁移时加上的句柄
+    assert_eq!(doc["edges"][0]["id"], json!("route.a->a"));
+    assert_eq!(doc["edges"][1]["id"], json!("route.default->b"));
+}
+
+#[test]
+fn current_version_is_unchanged() {
+    let graph = Graph::new_with_default_nodes().unwrap();
+    let doc: Value = serde_json::from_str(&graph.to_json()).unwrap();
+
+    assert_eq!(doc["schema_version"], json!(CURRENT_SCHEMA_VERSION));
+    assert_eq!(migrate(doc.clone()).unwrap(), doc);
+}
+
+#[test]
+fn newer_version_is_rejected() {
+    let result = migrate(json!({ "schema_version": CURRENT_SCHEMA_VERSION + 1 }));
+
+    assert!(matches!(result, Err(Error::UnsupportedSchemaVersion(_))));
+}
+
+#[test]
+fn out_of_range_version_is_rejected() {
+    // 2^32 截断后为 0，不能被当作 v0 文档迁移
+    let result = migrate(json!({ "schema_version": 1u64 << 32, "nodes": {}, "edges": [] }));
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("`schema_version` 4294967296 is out of range")
+    );
+
+    let result = migrate(json!({ "schema_version": -1 }));
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("must be an integer")
+    );
+}
+
+#[tokio::test]
+async fn v0_document_loads_and_runs() {
+    let json = std::fs::read_to_string("data/g.json").unwrap();
+    let graph = Graph::from_json(&json).unwrap();
+
+    let output = Workflow::start(graph).await.unwrap();
+    assert_eq!(output.as_text().unwrap(), "Node A Data");
+}
@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+
+use flow_data::FlowData;
+use serde_json::{Value, json};
+use workflow_rs::{
+    graph::{Graph, GraphBuilder},
+    model::{Context, node::DataProcessorMapping},
+    node::{
+        Executable,
+        config::{BranchOutput, ParallelConfig},
+        control::ParallelNode,
+    },
+    runner::Runner,
+};
+
+fn config(output: Option<BranchOutput>) -> ParallelConfig {
+    ParallelConfig {
+        branches: BTreeMap::from([
+            ("summary".to_string(), "summarize".to_string()),
+            ("research".to_string(), "investigate".to_string()),
+        ]),
+        output,
+    }
+}
+
+/// 两个分支节点：investigate 输出 `R:<input>`，summarize 输出 `S:<input>`
+fn builder() -> GraphBuilder {
+    GraphBuilder::new()
+        .input("start", "topic")
+        .expression("investigate", "'R:' + input")
+        .expression("summarize", "'S:' + input")
+}
+
+fn branch_context() -> std::sync::Arc<Context> {
+    let graph = builder().build().unwrap();
+    Context::from_graph(&graph)
+}
+
+async fn execute(node: impl Executable, input: &str) -> FlowData {
+    node.execute(Some(input.into()), branch_context())
+        .await
+        .unwrap()
+        .into_data()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn object_output_feeds_prompt_by_branch_name() {
+    let mut graph = builder()
+        .parallel("fan_out", config(Some(BranchOutput::Object)))
+        .prompt("prompt", "{{ research }} | {{ summary }}")
+        .end("end")
+        .edge("start", "fan_out")
+        .edge("fan_out", "prompt")
+        .edge("prompt", "end")
+        .build()
+        .unwrap();
+
+    let mut runner = Runner::new();
+    let output = runner.run(None, &mut graph, None).await.unwrap();
+
+    assert_eq!(output.as_text().unwrap(), "R:topic | S:topic");
+}
+
+#[tokio::test]
+async fn default_output_routes_each_branch_by_name() {
+    let mut graph = builder()
+        .parallel("fan_out", config(None))
+        .identity("after_research")
+        .identity("after_summary")
+        .end("end")
+        .edge("start", "fan_out")
+        .handle_edge("fan_out", "research", "after_research")
+        .handle_edge("fan_out", "summary", "after_summary")
+        .edge("after_research", "end")
+        .edge("after_summary", "end")
+        .build()
+        .unwrap();
+
+    let mut runner = Runner::new();
+    runner.run(None, &mut graph, None).await.unwrap();
+
+    assert_eq!(
+        runner
+            .get_output("after_research")
+            .unwrap()
+            .as_text()
+            .unwrap(),
+        "R:topic"
+    );
+    assert_eq!(
+        runner
+            .get_output("after_summary")
+            .unwrap()
+            .as_text()
+            .unwrap(),
+        "S:topic"
+    );
+}
+
+#[tokio::test]
+async fn collection_and_keyed_outputs_are_ordered_by_name() {
+    let data = |output| json!({ "branches": config(None).branches, "output": output });
+    let mapping = DataProcessorMapping::default();
+
+    let node = ParallelNode::new("p", data("collection"), &mapping).unwrap();
+    assert_eq!(
+        execute(node, "x").await.as_text_list().unwrap(),
+        vec!["R:x", "S:x"]
+    );
+
+    let node = ParallelNode::new("p", data("keyed"), &mapping).unwrap();
+    let keyed: Vec<Value> = execute(node, "x")
+        .await
+        .into_collection()
+        .unwrap()
+        .into_iter()
+        .map(|item| FlowData::Single(item).into_json().unwrap())
+        .collect();
+    assert_eq!(
+        keyed,
+        vec![
+            json!({ "branch": "research", "output": "R:x" }),
+            json!({ "branch": "summary", "output": "S:x" }),
+        ]
+    );
+}
+
+#[test]
+fn flow_output_requires_branch_handles() {
+    let build = |handle: Option<&str>| {
+        let builder = builder()
+            .parallel("fan_out", config(None))
+            .end("end")
+            .edge("start", "fan_out");
+        match handle {
+            Some(handle) => builder.handle_edge("fan_out", handle, "end"),
+            None => builder.edge("fan_out", "end"),
+        }
+        .build()
+    };
+
+    assert!(build(Some("research")).is_ok());
+    assert!(build(Some("other")).is_err());
+    assert!(build(None).is_err());
+
+    let merged: Result<Graph, _> = builder()
+        .parallel("fan_out", config(Some(BranchOutput::Collection)))
+        .end("end")
+        .edge("start", "fan_out")
+        .edge("fan_out", "end")
+        .build();
+    assert!(merged.is_ok());
+}
@@ -0,0 +1,183 @@
+use serde_json::{Value, json};
+use workflow_rs::{
+    edge::LoopGuard,
+    graph::{Graph, GraphBuilder, GraphOp},
+    model::{
+        graph_data::EdgeData,
+        node::{DataNode, DataProcessorMapping, Node, NodeType},
+    },
+};
+
+/// start → draft → review → end
+fn graph() -> Graph {
+    GraphBuilder::new()
+        .input("start", "hi")
+        .identity("draft")
+        .identity("review")
+        .end("end")
+        .edge("start", "draft")
+        .edge("draft", "review")
+        .edge("review", "end")
+        .build()
+        .unwrap()
+}
+
+fn identity(id: &str, data: Value) -> Node {
+    Node::new(
+        id,
+        NodeType::Data(DataNode::Identity),
+        data,
+        DataProcessorMapping::default(),
+        None,
+        None,
+    )
+}
+
+fn guard() -> LoopGuard {
+    LoopGuard {
+        max_iterations: Some(2),
+        until: None,
+    }
+}
+
+#[test]
+fn diff_and_apply_patch_round_trip() {
+    let mut before = graph();
+    let mut after = graph();
+    after
+        .update_node(identity("draft", json!({ "note": "v2" })))
+        .unwrap();
+    after.add_node(identity("polish", Value::Null)).unwrap();
+    after.remove_edge("review", "end").unwrap();
+    after.add_edge("review", "polish", None, None).unwrap();
+    after.add_edge("polish", "end", None, None).unwrap();
+    after.add_loop_edge("review", "draft", guard()).unwrap();
+
+    let ops = before.diff(&after);
+    // 操作列表可序列化后再应用
+    let ops: Vec<GraphOp> = serde_json::from_str(&serde_json::to_string(&ops).unwrap()).unwrap();
+    before.apply_patch(ops).unwrap();
+
+    assert!(before.compiled);
+    assert_eq!(before.to_canonical_json(), after.to_canonical_json());
+    assert!(before.diff(&after).is_empty());
+    assert!(graph().diff(&graph()).is_empty());
+}
+
+#[test]
+fn loop_edges_are_added_and_removed() {
+    let plain = graph();
+    let mut looped = graph();
+    let id = looped.add_loop_edge("review", "draft", guard()).unwrap();
+
+    let mut patched = graph();
+    patched.apply_patch(plain.diff(&looped)).unwrap();
+    assert_eq!(patched.get_edge(&id).unwrap().loop_guard, Some(guard()));
+
+    // 守卫变化时先删后加
+    let mut relaxed = looped.clone();
+    relaxed.remove_edge_by_id(&id).unwrap();
+    let relaxed_guard = LoopGuard {
+        max_iterations: Some(5),
+        until: None,
+    };
+    relaxed
+        .add_loop_edge("review", "draft", relaxed_guard.clone())
+        .unwrap();
+    assert_eq!(
+        looped.diff(&relaxed),
+        vec![
+            GraphOp::RemoveEdge { id: id.clone() },
+            GraphOp::AddEdge {
+                edge: relaxed.get_edge(&id).cloned().unwrap().into(),
+            },
+        ]
+    );
+    patched.apply_patch(looped.diff(&relaxed)).unwrap();
+    assert_eq!(
+        patched.get_edge(&id).unwrap().loop_guard,
+        Some(relaxed_guard)
+    );
+
+    patched.apply_patch(relaxed.diff(&plain)).unwrap();
+    assert!(patched.get_edge(&id).is_none());
+    assert_eq!(patched.to_canonical_json(), plain.to_canonical_json());
+}
+
+#[test]
+fn added_edges_keep_their_ids() {
+    let before = graph();
+    let mut after = graph();
+    after.add_edge("draft", "review", None, None).unwrap();
+    after.remove_edge_by_id("draft->review").unwrap();
+    assert!(after.get_edge("draft->review#2").is_some());
+
+    let mut patched = graph();
+    patched.apply_patch(before.diff(&after)).unwrap();
+    assert!(patched.get_edge("draft->review").is_none());
+    assert!(patched.get_edge("draft->review#2").is_some());
+    assert!(patched.diff(&after).is_empty());
+
+    // 与已有边 ID 冲突时拒绝
+    let duplicate = GraphOp::AddEdge {
+        edge: patched.get_edge("draft->review#2").cloned().unwrap().into(),
+    };
+    let error = patched.apply_patch(vec![duplicate]).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("Edge `draft->review#2` already exists")
+    );
+}
+
+#[test]
+fn patched_edge_ids_survive_reload() {
+    let mut graph = graph();
+    let ops = vec![
+        GraphOp::RemoveEdge {
+            id: "review->end".to_string(),
+        },
+        GraphOp::AddEdge {
+            edge: EdgeData {
+                id: "approve".to_string(),
+                source: "review".to_string(),
+                target: "end".to_string(),
+                source_handle: None,
+                target_handle: None,
+                loop_guard: None,
+            },
+        },
+    ];
+    graph.apply_patch(ops).unwrap();
+    assert!(graph.get_edge("approve").is_some());
+
+    let reloaded = Graph::from_json(&graph.to_json()).unwrap();
+    assert!(reloaded.get_edge("approve").is_some());
+    assert!(graph.diff(&reloaded).is_empty());
+    assert_eq!(reloaded.fingerprint(), graph.fingerprint());
+}
+
+#[test]
+fn failed_patch_is_rolled_back() {
+    let mut graph = graph();
+    let before = graph.to_canonical_json();
+
+    // 操作本身失败：后续操作不再执行，已执行的操作被撤销
+    let ops = vec![
+        GraphOp::AddNode {
+            node: identity("polish", Value::Null),
+        },
+        GraphOp::RemoveNode {
+            id: "missing".to_string(),
+        },
+    ];
+    assert!(graph.apply_patch(ops).is_err());
+    assert_eq!(graph.to_canonical_json(), before);
+
+    // 操作均成功但编译失败（普通边成环）
+    let mut cyclic = graph.clone();
+    cyclic.add_edge("review", "draft", None, None).unwrap();
+    assert!(graph.apply_patch(graph.diff(&cyclic)).is_err());
+    assert_eq!(graph.to_canonical_json(), before);
+    assert!(graph.compiled);
+}
@@ -0,0 +1,268 @@
+use std::sync::{Arc, Mutex};
+
+use flow_data::{FileType, FileValue, FlowData, SingleData};
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+use workflow_rs::{
+    graph::{Graph, GraphBuilder},
+    model::{Context, node::DataProcessorMapping},
+    node::{Executable, config::ModelSettings, data::llm::LLMNode},
+    provider::MODEL_PROFILES,
+    runner::Runner,
+};
+
+/// 收到的请求：请求行与请求头、JSON 请求体
+type Requests = Arc<Mutex<Vec<(String, Value)>>>;
+
+/// 依次以 `(status, content_type, body)` 响应请求，并记录每次的请求
+async fn serve(responses: Vec<(u16, &'static str, String)>) -> (String, Requests) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let requests = Requests::default();
+
+    let received = requests.clone();
+    tokio::spawn(async move {
+        for (status, content_type, body) in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            // 读完请求头与 Content-Length 指定的请求体
+            loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some(end) = text.find("\r\n\r\n") {
+                    let length = text[..end]
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= end + 4 + length {
+                        let json = serde_json::from_slice(&request[end + 4..end + 4 + length]);
+                        received
+                            .lock()
+                            .unwrap()
+                            .push((text[..end].to_string(), json.unwrap()));
+                        break;
+                    }
+                }
+            }
+
+            let response = format!(
+                "HTTP/1.1 {} OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                content_type,
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            socket.shutdown().await.unwrap();
+        }
+    });
+
+    (format!("http://{}/v1/", address), requests)
+}
+
+fn openai_reply(content: &str) -> (u16, &'static str, String) {
+    let body = json!({
+        "id": "chat",
+        "object": "chat.completion",
+        "created": 0,
+        "model": "test",
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": content },
+            "finish_reason": "stop",
+        }],
+    });
+    (200, "application/json", body.to_string())
+}
+
+fn ollama_reply(content: &str) -> (u16, &'static str, String) {
+    let body = json!({
+        "model": "llama3",
+        "created_at": "2024-01-01T00:00:00Z",
+        "message": { "role": "assistant", "content": content },
+        "done": true,
+        "prompt_eval_count": 3,
+        "eval_count": 2,
+    });
+    (200, "application/json", body.to_string())
+}
+
+fn node(data: Value) -> workflow_error::Result<LLMNode> {
+    LLMNode::new("llm", data, &DataProcessorMapping::default())
+}
+
+async fn ask(node: &LLMNode, input: FlowData) -> workflow_error::Result<String> {
+    let output = node
+        .execute(Some(input), Context::from_graph(&Graph::new()))
+        .await?;
+    Ok(output.into_data()?.as_text()?.to_string())
+}
+
+#[tokio::test]
+async fn openai_sends_sampling_params() {
+    let (base_url, requests) = serve(vec![openai_reply("ok")]).await;
+    let node = node(json!({
+        "apiHost": base_url,
+        "apiKey": "secret",
+        "modelName": "gpt-test",
+        "temperature": 0.5,
+        "maxTokens": 64,
+        "topP": 0.9,
+        "stop": ["\n\n"],
+    }))
+    .unwrap();
+
+    assert_eq!(ask(&node, "hi".into()).await.unwrap(), "ok");
+
+    let (head, body) = requests.lock().unwrap()[0].clone();
+    assert!(head.starts_with("POST /v1/chat/completions"));
+    assert!(head.contains("authorization: Bearer secret"));
+    assert_eq!(body["model"], "gpt-test");
+    assert_eq!(body["temperature"], 0.5);
+    assert_eq!(body["max_tokens"], 64);
+    assert!((body["top_p"].as_f64().unwrap() - 0.9).abs() < 1e-6);
+    assert_eq!(body["stop"], json!(["\n\n"]));
+    assert!(body.get("stream").is_none());
+}
+
+#[tokio::test]
+async fn nodes_reference_model_profiles() {
+    let (base_url, requests) = serve(vec![openai_reply("fast reply")]).await;
+    let profile: ModelSettings = serde_json::from_value(json!({
+        "apiHost": base_url,
+        "modelName": "small-model",
+        "temperature": 0.7,
+        "maxTokens": 32,
+    }))
+    .unwrap();
+    MODEL_PROFILES.register("provider_fast", profile);
+
+    let mut graph = GraphBuilder::new()
+        .input("start", "hi")
+        .llm(
+            "llm",
+            serde_json::from_value(json!({ "model": "provider_fast", "temperature": 0.1 }))
+                .unwrap(),
+        )
+        .end("end")
+        .edge("start", "llm")
+        .edge("llm", "end")
+        .build()
+        .unwrap();
+    let output = Runner::new().run(None, &mut graph, None).await.unwrap();
+
+    assert_eq!(output.as_text().unwrap(), "fast reply");
+    let (head, body) = requests.lock().unwrap()[0].clone();
+    // 未设置 apiKey 时不发送 Authorization
+    assert!(!head.to_lowercase().contains("authorization"));
+    assert_eq!(body["model"], "small-model");
+    assert!((body["temperature"].as_f64().unwrap() - 0.1).abs() < 1e-6);
+    assert_eq!(body["max_tokens"], 32);
+
+    let missing = node(json!({ "model": "provider_missing" })).unwrap_err();
+    assert!(
+        missing
+            .to_string()
+            .contains("`provider_missing` is not registered")
+    );
+    let unset = node(json!({ "apiHost": "http://localhost:1/v1/" })).unwrap_err();
+    assert!(unset.to_string().contains("model is not set"));
+}
+
+#[tokio::test]
+async fn ollama_puts_sampling_params_in_options() {
+    let (base_url, requests) = serve(vec![ollama_reply("hello")]).await;
+    let base_url = base_url.replace("/v1/", "/api/");
+    let node = node(json!({
+        "provider": "ollama",
+        "apiHost": base_url,
+        "modelName": "llama3",
+        "temperature": 0.2,
+        "maxTokens": 16,
+        "stop": ["END"],
+    }))
+    .unwrap();
+
+    assert_eq!(ask(&node, "hi".into()).await.unwrap(), "hello");
+
+    let (head, body) = requests.lock().unwrap()[0].clone();
+    assert!(head.starts_with("POST /api/chat"));
+    assert_eq!(body["model"], "llama3");
+    assert_eq!(body["stream"], false);
+    assert_eq!(
+        body["messages"][1],
+        json!({ "role": "user", "content": "hi" })
+    );
+    assert_eq!(body["options"]["num_predict"], 16);
+    assert_eq!(body["options"]["stop"], json!(["END"]));
+    assert!(body.get("temperature").is_none());
+
+    let image = FlowData::Single(SingleData::File(FileValue {
+        path: "https://example.com/cat.png".to_string(),
+        file_type: FileType::Image,
+    }));
+    let error = ask(&node, image).await.unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("not supported by the Ollama provider")
+    );
+}
+
+#[tokio::test]
+async fn ollama_stream_is_decoded_as_ndjson() {
+    let chunks = concat!(
+        r#"{"model": "llama3", "message": {"role": "assistant", "content": "Hi"}, "done": false}"#,
+        "\n",
+        r#"{"model": "llama3", "message": {"role": "assistant", "content": " there"}, "done": true}"#,
+        "\n",
+    );
+    let (base_url, requests) = serve(vec![(200, "application/x-ndjson", chunks.to_string())]).await;
+    let mut graph = GraphBuilder::new()
+        .input("start", "hi")
+        .llm(
+            "llm",
+            serde_json::from_value(json!({
+                "provider": "ollama",
+                "apiHost": base_url.replace("/v1/", "/api/"),
+                "modelName": "llama3",
+                "stream": true,
+            }))
+            .unwrap(),
+        )
+        .end("end")
+        .edge("start", "llm")
+        .edge("llm", "end")
+        .build()
+        .unwrap();
+
+    let output = Runner::new().run(None, &mut graph, None).await.unwrap();
+
+    assert_eq!(output.as_text().unwrap(), "Hi there");
+    assert_eq!(requests.lock().unwrap()[0].1["stream"], true);
+}
+
+#[tokio::test]
+async fn error_status_is_reported_with_body() {
+    let (base_url, _) = serve(vec![(
+        401,
+        "application/json",
+        r#"{"error": {"message": "invalid api key"}}"#.to_string(),
+    )])
+    .await;
+    let node = node(json!({ "apiHost": base_url, "modelName": "gpt-test" })).unwrap();
+
+    let error = ask(&node, "hi".into()).await.unwrap_err();
+
+    assert!(error.to_string().contains("401"));
+    assert!(error.to_string().contains("invalid api key"));
+}
@@ -0,0 +1,371 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use flow_data::FlowData;
+use serde_json::{Value, json};
+use workflow_rs::{
+    graph::{Graph, GraphBuilder},
+    model::{
+        Context,
+        node::{DataNode, DataProcessorMapping, Node, NodeType},
+    },
+    node::{
+        Executable,
+        config::{QuorumCombine, QuorumConfig, RaceConfig},
+        control::{QuorumNode, RaceNode},
+    },
+    processor::{PROCESSOR_REGISTRY, Processor},
+    runner::Runner,
+};
+
+/// 延迟后返回固定文本；`reply` 为 None 时返回 None，使节点因无输入而失败，
+/// 为 `panic` 时 panic
+struct Reply {
+    delay_ms: u64,
+    reply: Option<&'static str>,
+    finished: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl Processor<FlowData> for Reply {
+    async fn process(&self, _data: FlowData) -> Option<FlowData> {
+        tokio::time::sleep(Duration::from_millis(self.delay_ms)).await;
+        self.finished.fetch_add(1, Ordering::SeqCst);
+        if self.reply == Some("panic") {
+            panic!("branch panicked");
+        }
+        self.reply.map(FlowData::from)
+    }
+}
+
+/// 每个分支节点挂一个 `Reply` 处理器，完成时累加 `finished`
+fn branch_nodes(
+    prefix: &str,
+    branches: &[(&str, u64, Option<&'static str>)],
+    finished: &Arc<AtomicUsize>,
+) -> Vec<Node> {
+    branches
+        .iter()
+        .map(|(id, delay_ms, reply)| {
+            let processor = format!("{}_{}", prefix, id);
+            PROCESSOR_REGISTRY.register_input(
+                &processor,
+                Some(Arc::new(Reply {
+                    delay_ms: *delay_ms,
+                    reply: *reply,
+                    finished: finished.clone(),
+                })),
+            );
+            Node::new(
+                id,
+                NodeType::Data(DataNode::Identity),
+                Value::Null,
+                DataProcessorMapping {
+                    input: Some(processor),
+                    output: None,
+                },
+                None,
+                None,
+            )
+        })
+        .collect()
+}
+
+/// 只含分支节点的上下文与已完成分支计数
+fn context(
+    prefix: &str,
+    branches: &[(&str, u64, Option<&'static str>)],
+) -> (Arc<Context>, Arc<AtomicUsize>) {
+    let finished = Arc::new(AtomicUsize::new(0));
+    let mut graph = Graph::new();
+    for node in branch_nodes(prefix, branches, &finished) {
+        graph.add_node(node).unwrap();
+    }
+
+    (Context::from_graph(&graph), finished)
+}
+
+fn branch_map(ids: &[&str]) -> Value {
+    let map: HashMap<&str, &str> = ids.iter().map(|id| (*id, *id)).collect();
+    json!(map)
+}
+
+#[tokio::test]
+async fn race_returns_first_success_and_cancels_the_rest() {
+    let (context, finished) = context(
+        "race_first",
+        &[("fast", 10, Some("fast")), ("slow", 300, Some("slow"))],
+    );
+    let node = RaceNode::new(
+        "race",
+        json!({ "branches": branch_map(&["fast", "slow"]) }),
+        &DataProcessorMapping::default(),
+    )
+    .unwrap();
+
+    let output = node.execute(Some("q".into()), context).await.unwrap();
+    assert_eq!(output.into_data().unwrap().as_text().unwrap(), "fast");
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert_eq!(
+        finished.load(Ordering::SeqCst),
+        1,
+        "slow branch should be cancelled"
+    );
+}
+
+#[tokio::test]
+async fn race_skips_failures() {
+    let (context, _) = context("race_fail", &[("broken", 5, None), ("ok", 30, Some("ok"))]);
+    let node = RaceNode::new(
+        "race",
+        json!({ "branches": branch_map(&["broken", "ok"]) }),
+        &DataProcessorMapping::default(),
+    )
+    .unwrap();
+
+    let output = node.execute(Some("q".into()), context).await.unwrap();
+    assert_eq!(output.into_data().unwrap().as_text().unwrap(), "ok");
+}
+
+#[tokio::test]
+async fn race_fails_when_every_branch_fails() {
+    let (context, _) = context("race_all_fail", &[("a", 5, None), ("b", 5, None)]);
+    let node = RaceNode::new(
+        "race",
+        json!({ "branches": branch_map(&["a", "b"]) }),
+        &DataProcessorMapping::default(),
+    )
+    .unwrap();
+
+    assert!(node.execute(Some("q".into()), context).await.is_err());
+}
+
+#[tokio::test]
+async fn race_counts_a_panicking_branch_as_failed() {
+    let (context, _) = context(
+        "race_panic",
+        &[("boom", 5, Some("panic")), ("ok", 30, Some("ok"))],
+    );
+    let node = RaceNode::new(
+        "race",
+        json!({ "branches": branch_map(&["boom", "ok"]) }),
+        &DataProcessorMapping::default(),
+    )
+    .unwrap();
+
+    let output = node.execute(Some("q".into()), context).await.unwrap();
+    assert_eq!(output.into_data().unwrap().as_text().unwrap(), "ok");
+}
+
+#[tokio::test]
+async fn quorum_majority_vote() {
+    let (context, finished) = context(
+        "quorum_vote",
+        &[
+            ("a", 10, Some("Paris")),
+            ("b", 20, Some("Lyon")),
+            ("c", 30, Some(" Paris ")),
+            ("d", 400, Some("Lyon")),
+        ],
+    );
+    let node = QuorumNode::new(
+        "quorum",
+        json!({ "branches": branch_map(&["a", "b", "c", "d"]), "required": 3 }),
+        &DataProcessorMapping::default(),
+    )
+    .unwrap();
+
+    let output = node.execute(Some("q".into()), context).await.unwrap();
+    assert_eq!(output.into_data().unwrap().as_text().unwrap(), "Paris");
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(finished.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn quorum_tie_waits_for_a_majority() {
+    let (context, finished) = context(
+        "quorum_tie",
+        &[
+            ("a", 10, Some("Paris")),
+            ("b", 20, Some("Lyon")),
+            ("c", 60, Some("Lyon")),
+        ],
+    );
+    let node = QuorumNode::new(
+        "quorum",
+        json!({ "branches": branch_map(&["a", "b", "c"]), "required": 2 }),
+        &DataProcessorMapping::default(),
+    )
+    .unwrap();
+
+    // 前两个结果平票，不取先完成的 Paris，等待 c 打破平局
+    let output = node.execute(Some("q".into()), context).await.unwrap();
+    assert_eq!(output.into_data().unwrap().as_text().unwrap(), "Lyon");
+    assert_eq!(finished.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn quorum_without_majority_fails() {
+    let (context, _) = context(
+        "quorum_split",
+        &[
+            ("a", 5, Some("Paris")),
+            ("b", 10, Some("Lyon")),
+            ("c", 15, Some("Nice")),
+        ],
+    );
+    let node = QuorumNode::new(
+        "quorum",
+        json!({ "branches": branch_map(&["a", "b", "c"]), "required": 2 }),
+        &DataProcessorMapping::default(),
+    )
+    .unwrap();
+
+    let result = node.execute(Some("q".into()), context).await;
+    assert!(result.is_err_and(|e| e.to_string().contains("no majority among 3 results")));
+}
+
+#[tokio::test]
+async fn quorum_counts_a_panicking_branch_as_failed() {
+    let (context, _) = context(
+        "quorum_panic",
+        &[
+            ("a", 5, Some("panic")),
+            ("b", 10, Some("yes")),
+            ("c", 15, Some("yes")),
+        ],
+    );
+    let node = QuorumNode::new(
+        "quorum",
+        json!({ "branches": branch_map(&["a", "b", "c"]), "required": 2 }),
+        &DataProcessorMapping::default(),
+    )
+    .unwrap();
+
+    let output = node.execute(Some("q".into()), context).await.unwrap();
+    assert_eq!(output.into_data().unwrap().as_text().unwrap(), "yes");
+}
+
+#[tokio::test]
+async fn quorum_collects_in_branch_order() {
+    let (context, _) = context(
+        "quorum_collect",
+        &[
+            ("x", 30, Some("one")),
+            ("y", 10, Some("two")),
+            ("z", 5, None),
+        ],
+    );
+    let node = QuorumNode::new(
+        "quorum",
+        json!({ "branches": branch_map(&["x", "y", "z"]), "required": 2, "combine": "collect" }),
+        &DataProcessorMapping::default(),
+    )
+    .unwrap();
+
+    let output = node.execute(Some("q".into()), context).await.unwrap();
+    assert_eq!(
+        output.into_data().unwrap().as_text_list().unwrap(),
+        vec!["one", "two"]
+    );
+}
+
+#[tokio::test]
+async fn quorum_fails_early_when_unreachable() {
+    let (context, _) = context(
+        "quorum_unreachable",
+        &[("a", 5, None), ("b", 5, None), ("c", 300, Some("late"))],
+    );
+    let node = QuorumNode::new(
+        "quorum",
+        json!({ "branches": branch_map(&["a", "b", "c"]), "required": 2 }),
+        &DataProcessorMapping::default(),
+    )
+    .unwrap();
+
+    let started = std::time::Instant::now();
+    assert!(node.execute(Some("q".into()), context).await.is_err());
+    assert!(started.elapsed() < Duration::from_millis(250));
+}
+
+#[tokio::test]
+async fn branches_run_only_inside_race_and_quorum_in_a_graph() {
+    let finished = Arc::new(AtomicUsize::new(0));
+    let race = branch_nodes(
+        "graph_race",
+        &[("fast", 5, Some("fast")), ("slow", 200, Some("slow"))],
+        &finished,
+    );
+    let quorum = branch_nodes(
+        "graph_quorum",
+        &[
+            ("a", 5, Some("yes")),
+            ("b", 10, Some("yes")),
+            ("c", 15, None),
+        ],
+        &finished,
+    );
+    let ids = |nodes: &[Node]| -> BTreeMap<String, String> {
+        nodes
+            .iter()
+            .map(|node| (node.id.clone(), node.id.clone()))
+            .collect()
+    };
+
+    let mut builder = GraphBuilder::new()
+        .input("start", "q")
+        .race(
+            "race",
+            RaceConfig {
+                branches: ids(&race),
+            },
+        )
+        .quorum(
+            "quorum",
+            QuorumConfig {
+                branches: ids(&quorum),
+                required: 2,
+                combine: QuorumCombine::Majority,
+            },
+        )
+        .identity("pick")
+        .end("end")
+        .edge("start", "race")
+        .edge("race", "pick")
+        .edge("pick", "quorum")
+        .edge("quorum", "end");
+    for node in race.into_iter().chain(quorum) {
+        builder = builder.node(node);
+    }
+    let mut graph = builder.build().unwrap();
+
+    let mut runner = Runner::new();
+    let output = runner.run(None, &mut graph, None).await.unwrap();
+
+    assert_eq!(output.as_text().unwrap(), "yes");
+    // 分支节点只由 Race / Quorum 执行，不作为独立的起始节点调度
+    let mut scheduled: Vec<&str> = runner.node_runs().keys().map(String::as_str).collect();
+    scheduled.sort();
+    assert_eq!(scheduled, ["end", "pick", "quorum", "race", "start"]);
+}
+
+#[test]
+fn quorum_validates_required() {
+    for required in [0, 3] {
+        let result = QuorumNode::new(
+            "quorum",
+            json!({ "branches": branch_map(&["a", "b"]), "required": required }),
+            &DataProcessorMapping::default(),
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use std::{collections::HashMap, time::Duration};
+
+use serde_json::Value;
+use workflow_rs::{
+    edge::LoopGuard,
+    graph::Graph,
+    model::node::{ControlNode, DataNode, DataProcessorMapping, Node, NodeType},
+    node::base::NodeState,
+    runner::NodeRun,
+};
+
+fn node(id: &str, node_type: NodeType) -> Node {
+    Node::new(
+        id,
+        node_type,
+        Value::Null,
+        DataProcessorMapping::default(),
+        None,
+        None,
+    )
+}
+
+/// start → check ─┬─ yes → draft → end，draft 最多回跳到 check 三次
+///               └─ default → end
+fn graph() -> Graph {
+    let mut graph = Graph::new_with_default_nodes().unwrap();
+    graph
+        .add_node(node("check", NodeType::Control(ControlNode::Branch)))
+        .unwrap();
+    graph
+        .add_node(node("draft", NodeType::Data(DataNode::Identity)))
+        .unwrap();
+    graph.add_edge("start", "check", None, None).unwrap();
+    graph
+        .add_edge("check", "draft", Some("yes".into()), None)
+        .unwrap();
+    graph
+        .add_edge("check", "end", Some("default".into()), None)
+        .unwrap();
+    graph.add_edge("draft", "end", None, None).unwrap();
+    let guard = LoopGuard {
+        max_iterations: Some(3),
+        until: None,
+    };
+    graph.add_loop_edge("draft", "check", guard).unwrap();
+    graph
+}
+
+fn runs() -> HashMap<String, NodeRun> {
+    let run = |state, ms: Option<u64>| NodeRun {
+        state,
+        elapsed: ms.map(Duration::from_millis),
+    };
+    HashMap::from([
+        ("start".to_string(), run(NodeState::Completed, Some(2))),
+        ("check".to_string(), run(NodeState::Completed, Some(1))),
+        ("draft".to_string(), run(NodeState::Skipped, None)),
+        ("end".to_string(), run(NodeState::Failed, Some(3))),
+    ])
+}
+
+#[test]
+fn mermaid_shapes_and_edge_labels() {
+    // 起止节点为圆角、控制节点为菱形；控制边为虚线并标注句柄，回边加粗并标注守卫
+    let expected = r#"flowchart TD
+    n0{"check<br/>Control:Branch"}
+    n1["draft<br/>Data:Identity"]
+    n2(["end<br/>Data:Identity"])
+    n3(["start<br/>Data:Input"])
+    n3 --> n0
+    n0 -.->|"yes"| n1
+    n0 -.->|"default"| n2
+    n1 --> n2
+    n1 ==>|"loop ≤3"| n0
+"#;
+    assert_eq!(graph().to_mermaid(), expected);
+}
+
+#[test]
+fn mermaid_marks_node_states() {
+    let mermaid = graph().to_mermaid_with_runs(&runs());
+
+    assert!(mermaid.contains("    n0{\"check<br/>Control:Branch<br/>1.0ms\"}\n"));
+    assert!(mermaid.contains("    n1[\"draft<br/>Data:Identity\"]\n"));
+    assert!(mermaid.ends_with(
+        r#"    classDef pending fill:#eeeeee
+    classDef running fill:#fff3cd
+    classDef completed fill:#d4edda
+    classDef failed fill:#f8d7da
+    classDef cancelled fill:#d6d8db
+    classDef skipped fill:#ffffff
+    class n0 completed
+    class n1 skipped
+    class n2 failed
+    class n3 completed
+"#
+    ));
+}
+
+#[test]
+fn dot_shapes_and_edge_styles() {
+    let expected = r#"digraph workflow {
+    rankdir=TB;
+    "check" [label="check\nControl:Branch", shape=diamond];
+    "draft" [label="draft\nData:Identity", shape=box];
+    "end" [label="end\nData:Identity", shape=oval];
+    "start" [label="start\nData:Input", shape=oval];
+    "start" -> "check";
+    "check" -> "draft" [style=dashed, label="yes"];
+    "check" -> "end" [style=dashed, label="default"];
+    "draft" -> "end";
+    "draft" -> "check" [style=bold, constraint=false, label="loop ≤3"];
+}
+"#;
+    assert_eq!(graph().to_dot(), expected);
+}
+
+#[test]
+fn dot_fills_node_states() {
+    let dot = graph().to_dot_with_runs(&runs());
+
+    assert!(dot.contains(
+        r##"    "check" [label="check\nControl:Branch\n1.0ms", shape=diamond, style=filled, fillcolor="#d4edda"];"##
+    ));
+    assert!(dot.contains(
+        r##"    "draft" [label="draft\nData:Identity", shape=box, style=filled, fillcolor="#ffffff"];"##
+    ));
+    assert!(dot.contains(
+        r##"    "end" [label="end\nData:Identity\n3.0ms", shape=oval, style=filled, fillcolor="#f8d7da"];"##
+    ));
+}
+
+#[test]
+fn labels_are_escaped() {
+    let mut graph = Graph::new();
+    graph
+        .add_node(node("say \"hi\"", NodeType::Data(DataNode::Identity)))
+        .unwrap();
+
+    graph
+        .add_node(Node::new(
+            "a<b> & c",
+            NodeType::Data(DataNode::Identity),
+            serde_json::Value::Null,
+            Default::default(),
+            None,
+            None,
+        ))
+        .unwrap();
+
+    let mermaid = graph.to_mermaid();
+    assert!(mermaid.contains("say #quot;hi#quot;"));
+    assert!(mermaid.contains("a#lt;b#gt; #amp; c"));
+    assert!(
+        graph
+            .to_dot()
+            .contains(r#""say \"hi\"" [label="say \"hi\"\nData:Identity""#)
+    );
+}
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use flow_data::{FlowData, SingleData};
+use serde_json::{Value, json};
+use workflow_rs::{
+    graph::Graph,
+    model::{
+        Context,
+        graph_data::GraphData,
+        node::{DataNode, DataProcessorMapping, Node, NodeType},
+    },
+    node::{Executable, control::RepeatNode},
+    processor::{PROCESSOR_REGISTRY, Processor},
+};
+
+/// 在文本输入后追加 `x`
+struct AppendX;
+
+#[async_trait]
+impl Processor<FlowData> for AppendX {
+    async fn process(&self, data: FlowData) -> Option<FlowData> {
+        let text = data.as_text().ok()?;
+        Some(FlowData::from(format!("{}x", text).as_str()))
+    }
+}
+
+fn identity(id: &str, input_processor: Option<&str>) -> Node {
+    Node::new(
+        id,
+        NodeType::Data(DataNode::Identity),
+        Value::Null,
+        DataProcessorMapping {
+            input: input_processor.map(str::to_string),
+            output: None,
+        },
+        None,
+        None,
+    )
+}
+
+/// 包含 `draft` 节点的上下文，draft 每次执行在输入后追加 `x`
+fn context() -> Arc<Context> {
+    PROCESSOR_REGISTRY.register_input("append_x", Some(Arc::new(AppendX)));
+
+    let mut graph = Graph::new();
+    graph.add_node(identity("draft", Some("append_x"))).unwrap();
+    Context::from_graph(&graph)
+}
+
+async fn run(config: Value) -> FlowData {
+    let context = context();
+    let node = RepeatNode::new("repeat", config, &DataProcessorMapping::default()).unwrap();
+    node.execute(Some("A".into()), context)
+        .await
+        .unwrap()
+        .into_data()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn runs_max_iterations_and_returns_last_output() {
+    let output = run(json!({ "child_id": "draft", "max_iterations": 3 })).await;
+    assert_eq!(output.as_text().unwrap(), "Axxx");
+}
+
+#[tokio::test]
+async fn stops_when_until_condition_holds() {
+    let output = run(json!({
+        "child_id": "draft",
+        "max_iterations": 10,
+        "until": { "condition": "==", "value": "Axx", "valueType": "string" }
+    }))
+    .await;
+
+    assert_eq!(output.as_text().unwrap(), "Axx");
+}
+
+#[tokio::test]
+async fn collects_every_iteration() {
+    let output = run(json!({
+        "child_id": "draft",
+        "max_iterations": 3,
+        "collect": true
+    }))
+    .await;
+
+    assert_eq!(output.as_text_list().unwrap(), vec!["Ax", "Axx", "Axxx"]);
+}
+
+/// 取文本或 Collection 的第一个元素，输出 `[<文本>x, !]`
+struct AppendXPair;
+
+#[async_trait]
+impl Processor<FlowData> for AppendXPair {
+    async fn process(&self, data: FlowData) -> Option<FlowData> {
+        let text = match data.to_json_value() {
+            Value::Array(items) => items.first()?.as_str()?.to_string(),
+            value => value.as_str()?.to_string(),
+        };
+        Some(FlowData::Collection(vec![
+            SingleData::Text(format!("{}x", text)),
+            SingleData::Text("!".into()),
+        ]))
+    }
+}
+
+#[tokio::test]
+async fn collects_collection_iterations_as_arrays() {
+    PROCESSOR_REGISTRY.register_input("append_x_pair", Some(Arc::new(AppendXPair)));
+    let mut graph = Graph::new();
+    graph
+        .add_node(identity("pair", Some("append_x_pair")))
+        .unwrap();
+    let config = json!({ "child_id": "pair", "max_iterations": 2, "collect": true });
+    let node = RepeatNode::new("repeat", config, &DataProcessorMapping::default()).unwrap();
+
+    let output = node
+        .execute(Some("A".into()), Context::from_graph(&graph))
+        .await
+        .unwrap()
+        .into_data()
+        .unwrap();
+
+    // 每轮一个元素，轮次边界保留
+    assert_eq!(output.as_collection().unwrap().len(), 2);
+    assert_eq!(output.to_json_value(), json!([["Ax", "!"], ["Axx", "!"]]));
+}
+
+#[tokio::test]
+async fn repeats_a_subgraph() {
+    let mut subgraph = Graph::new();
+    subgraph.add_node(identity("in", Some("append_x"))).unwrap();
+    subgraph
+        .add_node(identity("out", Some("append_x")))
+        .unwrap();
+    subgraph.start_node = Some("in".into());
+    subgraph.end_node = Some("out".into());
+    subgraph.add_edge("in", "out", None, None).unwrap();
+
+    let output = run(json!({
+        "subgraph": GraphData::from(subgraph),
+        "max_iterations": 2
+    }))
+    .await;
+
+    // 每次迭代经过两个追加节点
+    assert_eq!(output.as_text().unwrap(), "Axxxx");
+}
+
+#[test]
+fn requires_exactly_one_body() {
+    let mapping = DataProcessorMapping::default();
+    assert!(RepeatNode::new("repeat", json!({ "max_iterations": 1 }), &mapping).is_err());
+
+    let both = json!({
+        "child_id": "draft",
+        "subgraph": GraphData::new(),
+        "max_iterations": 1
+    });
+    assert!(RepeatNode::new("repeat", both, &mapping).is_err());
+}
+
+#[test]
+fn invalid_subgraph_is_an_error() {
+    // 边指向不存在的节点
+    let mut subgraph = GraphData::new();
+    subgraph.add_edge("in", "out", None, None);
+    let config = json!({ "subgraph": subgraph, "max_iterations": 1 });
+
+    assert!(RepeatNode::new("repeat", config, &DataProcessorMapping::default()).is_err());
+}
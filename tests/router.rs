@@ -0,0 +1,136 @@
+use flow_data::FlowData;
+use serde_json::{Value, json};
+use workflow_rs::{
+    graph::{Graph, GraphBuilder},
+    node::{
+        base::NodeState,
+        condition::{ConditionConfig, Operator},
+        config::{BranchPayload, RouterConfig, RouterMode},
+    },
+    runner::Runner,
+};
+
+fn branch(id: &str, tag: &str) -> BranchPayload {
+    BranchPayload {
+        id: id.into(),
+        when: ConditionConfig::compare_path("tags", Operator::Contains, tag),
+    }
+}
+
+/// start → route ─┬─ a → end
+///                ├─ b → end
+///                ├─ c → c2 → end
+///                └─ default → other → end
+fn graph(mode: RouterMode, tags: Value) -> Graph {
+    GraphBuilder::new()
+        .input(
+            "start",
+            FlowData::try_from_json(json!({ "tags": tags })).unwrap(),
+        )
+        .router(
+            "route",
+            RouterConfig {
+                branches: vec![branch("a", "a"), branch("b", "b"), branch("c", "c")],
+                mode,
+            },
+        )
+        .identity("na")
+        .identity("nb")
+        .identity("nc")
+        .identity("nc2")
+        .identity("other")
+        .end("end")
+        .edge("start", "route")
+        .handle_edge("route", "a", "na")
+        .handle_edge("route", "b", "nb")
+        .handle_edge("route", "c", "nc")
+        .handle_edge("route", "default", "other")
+        .edge("na", "end")
+        .edge("nb", "end")
+        .edge("nc", "nc2")
+        .edge("nc2", "end")
+        .edge("other", "end")
+        .build()
+        .unwrap()
+}
+
+async fn run(mut graph: Graph) -> (Runner, FlowData) {
+    let mut runner = Runner::new();
+    let output = runner.run(None, &mut graph, None).await.unwrap();
+    (runner, output)
+}
+
+fn state(runner: &Runner, node: &str) -> Option<NodeState> {
+    runner.node_runs().get(node).map(|run| run.state.clone())
+}
+
+fn completed(runner: &Runner, node: &str) -> bool {
+    matches!(state(runner, node), Some(NodeState::Completed))
+}
+
+fn skipped(runner: &Runner, node: &str) -> bool {
+    matches!(state(runner, node), Some(NodeState::Skipped))
+}
+
+#[tokio::test]
+async fn all_mode_activates_every_match() {
+    let (runner, output) = run(graph(RouterMode::All, json!(["a", "b"]))).await;
+
+    assert!(completed(&runner, "na"));
+    assert!(completed(&runner, "nb"));
+    assert!(skipped(&runner, "nc"));
+    // 跳过沿分支向下传递
+    assert!(skipped(&runner, "nc2"));
+    assert!(skipped(&runner, "other"));
+    assert!(completed(&runner, "end"));
+    assert_eq!(output.into_json().unwrap(), json!({ "tags": ["a", "b"] }));
+}
+
+#[tokio::test]
+async fn first_mode_activates_one_branch() {
+    let (runner, _) = run(graph(RouterMode::First, json!(["b", "c"]))).await;
+
+    assert!(completed(&runner, "nb"));
+    assert!(skipped(&runner, "na"));
+    assert!(skipped(&runner, "nc"));
+    assert!(completed(&runner, "end"));
+}
+
+#[tokio::test]
+async fn longer_branch_joins_after_skip() {
+    let (runner, _) = run(graph(RouterMode::All, json!(["c"]))).await;
+
+    assert!(completed(&runner, "nc"));
+    assert!(completed(&runner, "nc2"));
+    assert!(skipped(&runner, "na"));
+    assert!(completed(&runner, "end"));
+}
+
+#[tokio::test]
+async fn no_match_takes_default() {
+    let (runner, _) = run(graph(RouterMode::All, json!(["z"]))).await;
+
+    assert!(completed(&runner, "other"));
+    assert!(skipped(&runner, "na"));
+    assert!(skipped(&runner, "nc2"));
+    assert!(completed(&runner, "end"));
+}
+
+#[test]
+fn unknown_handle_is_rejected() {
+    let result = GraphBuilder::new()
+        .input("start", "x")
+        .router(
+            "route",
+            RouterConfig {
+                branches: vec![branch("a", "a")],
+                mode: RouterMode::All,
+            },
+        )
+        .end("end")
+        .edge("start", "route")
+        .handle_edge("route", "missing", "end")
+        .build();
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,194 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use flow_data::{FlowData, SingleData};
+use model_gateway_rs::{
+    model::llm::{ChatMessage, LlmInput, LlmOutput},
+    traits::ModelClient,
+};
+use serde_json::{Value, json};
+use workflow_rs::{
+    graph::Graph,
+    model::{Context, node::DataProcessorMapping},
+    node::{Executable, data::llm::LLMNode, json_schema},
+};
+
+/// 依次返回预设回复，并记录每次收到的消息
+#[derive(Default)]
+struct Scripted {
+    replies: Mutex<VecDeque<String>>,
+    received: Mutex<Vec<Vec<ChatMessage>>>,
+}
+
+#[async_trait]
+impl ModelClient<LlmInput, LlmOutput> for Scripted {
+    async fn infer(&self, input: LlmInput) -> model_gateway_rs::error::Result<LlmOutput> {
+        self.received.lock().unwrap().push(input.messages);
+        let reply = self.replies.lock().unwrap().pop_front().unwrap_or_default();
+        Ok(LlmOutput {
+            message: Some(ChatMessage::assistant(&reply)),
+            usage: None,
+        })
+    }
+}
+
+fn schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "sentiment": { "enum": ["positive", "negative"] },
+            "score": { "type": "number", "minimum": 0, "maximum": 1 }
+        },
+        "required": ["sentiment", "score"],
+        "additionalProperties": false
+    })
+}
+
+fn config(extra: Value) -> Value {
+    let mut config = json!({
+        "apiHost": "http://localhost:1/v1/",
+        "apiKey": "test",
+        "modelName": "test",
+        "systemPrompt": "Classify the review.",
+    });
+    config
+        .as_object_mut()
+        .unwrap()
+        .extend(extra.as_object().unwrap().clone());
+    config
+}
+
+async fn run(extra: Value, replies: &[&str]) -> (workflow_error::Result<FlowData>, Arc<Scripted>) {
+    let model = Arc::new(Scripted {
+        replies: Mutex::new(replies.iter().map(|r| r.to_string()).collect()),
+        ..Default::default()
+    });
+    let node = LLMNode::new("llm", config(extra), &DataProcessorMapping::default())
+        .unwrap()
+        .with_model_client(model.clone());
+    let output = node
+        .execute(
+            Some("Great product!".into()),
+            Context::from_graph(&Graph::new()),
+        )
+        .await
+        .and_then(|output| output.into_data());
+    (output, model)
+}
+
+#[tokio::test]
+async fn returns_json_matching_schema() {
+    let (output, model) = run(
+        json!({ "outputSchema": schema() }),
+        &[r#"{"sentiment": "positive", "score": 0.9}"#],
+    )
+    .await;
+
+    let FlowData::Single(SingleData::Json(value)) = output.unwrap() else {
+        panic!("expected JSON output");
+    };
+    assert_eq!(value, json!({ "sentiment": "positive", "score": 0.9 }));
+
+    let received = model.received.lock().unwrap();
+    assert_eq!(received.len(), 1);
+    assert!(received[0][0].content.starts_with("Classify the review."));
+    assert!(received[0][0].content.contains("\"required\""));
+}
+
+#[tokio::test]
+async fn retries_with_validation_error() {
+    let (output, model) = run(
+        json!({ "outputSchema": schema() }),
+        &[
+            "Sure! It is positive.",
+            r#"{"sentiment": "positive", "score": 3}"#,
+            "```json\n{\"sentiment\": \"negative\", \"score\": 0.2}\n```",
+        ],
+    )
+    .await;
+
+    assert_eq!(
+        output.unwrap().into_json().unwrap(),
+        json!({ "sentiment": "negative", "score": 0.2 })
+    );
+    let received = model.received.lock().unwrap();
+    assert_eq!(received.len(), 3);
+    assert!(
+        received[1]
+            .last()
+            .unwrap()
+            .content
+            .contains("not valid JSON")
+    );
+    assert!(
+        received[2]
+            .last()
+            .unwrap()
+            .content
+            .contains("`$.score`: must be at most 1")
+    );
+}
+
+#[tokio::test]
+async fn fails_after_retries_are_exhausted() {
+    let (output, model) = run(
+        json!({ "outputSchema": schema(), "maxRetries": 1 }),
+        &[r#"{"sentiment": "meh", "score": 0.5}"#, r#"{"score": 0.5}"#],
+    )
+    .await;
+
+    let error = output.unwrap_err().to_string();
+    assert!(error.contains("after 2 attempts"));
+    assert!(error.contains("missing required property `sentiment`"));
+    assert_eq!(model.received.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn plain_text_without_schema() {
+    let (output, _) = run(json!({}), &["Positive."]).await;
+
+    assert_eq!(output.unwrap().as_text().unwrap(), "Positive.");
+}
+
+#[test]
+fn invalid_schema_is_rejected() {
+    let result = LLMNode::new(
+        "llm",
+        config(json!({ "outputSchema": { "type": "text" } })),
+        &DataProcessorMapping::default(),
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn validator_reports_first_violation_with_path() {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "tags": { "type": "array", "items": { "type": "string" }, "minItems": 1 },
+            "id": { "type": ["integer", "null"] },
+            "name": { "type": "string", "maxLength": 3 }
+        }
+    });
+
+    let check = |value: Value| json_schema::validate(&schema, &value);
+    assert!(check(json!({ "tags": ["a"], "id": null, "name": "abc" })).is_ok());
+    assert_eq!(
+        check(json!({ "tags": ["a", 1] })).unwrap_err(),
+        "`$.tags[1]`: expected string, found number"
+    );
+    assert_eq!(
+        check(json!({ "tags": [] })).unwrap_err(),
+        "`$.tags`: expected at least 1 items, found 0"
+    );
+    assert_eq!(
+        check(json!({ "id": 1.5 })).unwrap_err(),
+        "`$.id`: expected integer or null, found number"
+    );
+    assert!(check(json!({ "name": "abcd" })).is_err());
+    assert!(check(json!([])).is_err());
+}
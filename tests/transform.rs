@@ -0,0 +1,162 @@
+use flow_data::{FlowData, SingleData};
+use serde_json::{Value, json};
+use workflow_rs::{
+    Workflow,
+    graph::{Graph, GraphBuilder},
+    model::{Context, node::DataProcessorMapping},
+    node::{
+        Executable,
+        config::{TransformConfig, TransformMapping, TransformOutput},
+        data::TransformNode,
+    },
+};
+
+fn response() -> FlowData {
+    FlowData::Single(SingleData::Json(json!({
+        "status": "ok",
+        "data": {
+            "total": "3",
+            "items": [
+                { "id": 1, "name": "alpha" },
+                { "id": 2, "name": "beta" },
+                { "id": 3 }
+            ]
+        }
+    })))
+}
+
+async fn transform(config: Value, input: FlowData) -> workflow_error::Result<FlowData> {
+    let node = TransformNode::new("transform", config, &DataProcessorMapping::default())?;
+    let context = Context::from_graph(&Graph::new());
+    node.execute(Some(input), context).await?.into_data()
+}
+
+#[tokio::test]
+async fn select_picks_a_field() {
+    let output = transform(json!({ "select": "$.data.items[1].name" }), response()).await;
+    assert_eq!(output.unwrap().as_text().unwrap(), "beta");
+
+    let output = transform(json!({ "select": "/data/items/0/id" }), response()).await;
+    assert_eq!(output.unwrap().as_number().unwrap(), 1.0);
+}
+
+#[tokio::test]
+async fn wildcard_collects_values() {
+    let output = transform(json!({ "select": "data.items[*].name" }), response()).await;
+    let output = output.unwrap().into_json().unwrap();
+
+    // 缺少 name 的元素被跳过
+    assert_eq!(output, json!(["alpha", "beta"]));
+}
+
+#[tokio::test]
+async fn shape_builds_a_new_object() {
+    let config = json!({
+        "shape": {
+            "ok": "status",
+            "ids": "$.data.items[*].id",
+            "first": { "name": "data.items[0].name" },
+            "missing": "data.nothing",
+            "version": 2
+        }
+    });
+    let output = transform(config, response()).await.unwrap();
+
+    assert_eq!(
+        output.into_json().unwrap(),
+        json!({
+            "ok": "ok",
+            "ids": [1, 2, 3],
+            "first": { "name": "alpha" },
+            "missing": null,
+            "version": 2
+        })
+    );
+}
+
+#[tokio::test]
+async fn output_conversion() {
+    let number = transform(
+        json!({ "select": "data.total", "output": "number" }),
+        response(),
+    )
+    .await;
+    assert_eq!(number.unwrap().as_number().unwrap(), 3.0);
+
+    let text = transform(
+        json!({ "select": "data.items[0]", "output": "text" }),
+        response(),
+    )
+    .await;
+    assert_eq!(
+        text.unwrap().as_text().unwrap(),
+        r#"{"id":1,"name":"alpha"}"#
+    );
+
+    let json = transform(json!({ "select": "status", "output": "json" }), response()).await;
+    assert!(matches!(
+        json.unwrap(),
+        FlowData::Single(SingleData::Json(Value::String(_)))
+    ));
+
+    let invalid = transform(
+        json!({ "select": "status", "output": "number" }),
+        response(),
+    )
+    .await;
+    assert!(invalid.is_err());
+}
+
+#[tokio::test]
+async fn missing_path_uses_default_or_fails() {
+    let missing = transform(json!({ "select": "data.nothing" }), response()).await;
+    assert!(missing.is_err());
+
+    let defaulted = transform(
+        json!({ "select": "data.nothing", "default": "n/a" }),
+        response(),
+    )
+    .await;
+    assert_eq!(defaulted.unwrap().as_text().unwrap(), "n/a");
+}
+
+#[tokio::test]
+async fn accepts_json_text_and_rejects_other_input() {
+    let text = FlowData::from(r#"{"a": {"b": 5}}"#);
+    let output = transform(json!({ "select": "a.b" }), text).await;
+    assert_eq!(output.unwrap().as_number().unwrap(), 5.0);
+
+    let plain = transform(json!({ "select": "a" }), FlowData::from("not json")).await;
+    assert!(plain.is_err());
+
+    let invalid_path = TransformNode::new(
+        "transform",
+        json!({ "select": "a..b" }),
+        &DataProcessorMapping::default(),
+    );
+    assert!(invalid_path.is_err());
+}
+
+#[tokio::test]
+async fn transform_in_a_graph() {
+    let graph = GraphBuilder::new()
+        .input("start", response())
+        .transform(
+            "pick",
+            TransformConfig {
+                mapping: TransformMapping::Select {
+                    select: "data.items[0].name".into(),
+                },
+                default: None,
+                output: TransformOutput::Text,
+            },
+        )
+        .end("end")
+        .edge("start", "pick")
+        .edge("pick", "end")
+        .build()
+        .unwrap();
+
+    let output = Workflow::start(graph).await.unwrap();
+    assert_eq!(output.as_text().unwrap(), "alpha");
+}
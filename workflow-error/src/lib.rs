@@ -10,6 +10,18 @@ pub enum Error {
     #[error("serde_json error: {0}")]
     SerdeJsonError(#[from] serde_json::Error),
 
+    #[error("serde_yaml error: {0}")]
+    SerdeYamlError(#[from] serde_yaml::Error),
+
+    #[error("toml deserialize error: {0}")]
+    TomlDeError(#[from] toml::de::Error),
+
+    #[error("toml serialize error: {0}")]
+    TomlSerError(#[from] toml::ser::Error),
+
+    #[error("Unsupported graph schema version: {0}.")]
+    UnsupportedSchemaVersion(u32),
+
     #[error("Graph not compiled.")]
     GraphNotCompiled,
 
@@ -22,6 +34,9 @@ pub enum Error {
     #[error("Execution error: {0}")]
     ExecutionError(Box<str>),
 
+    #[error("Expression error: {0}")]
+    ExpressionError(Box<str>),
+
     #[error("Invalid edge from `{start}` to `{end}`.")]
     InvalidEdge { start: Box<str>, end: Box<str> },
 
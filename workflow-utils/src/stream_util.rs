@@ -53,6 +53,19 @@ pub async fn print_stream_chunks(mut stream: ByteStream) -> Result<()> {
     Ok(())
 }
 
+/// 读完整个流并汇总所有数据块
+pub async fn collect_stream(mut stream: ByteStream) -> Result<Bytes> {
+    let mut collected_chunks = Vec::new();
+
+    while let Some(chunk) = stream.next().await {
+        let bytes = chunk
+            .map_err(|e| Error::StreamChunkError(format!("stream chunk error: {}", e).into()))?;
+        collected_chunks.push(bytes);
+    }
+
+    Ok(Bytes::from_iter(collected_chunks.into_iter().flatten()))
+}
+
 pub async fn forward_and_collect_stream(
     mut stream: ByteStream,
     tx: StreamSender,